@@ -1,5 +1,5 @@
 #!/bin/bash
 set -e
 cd "`dirname $0`"
-RUSTFLAGS='-C link-arg=-s' cargo build --all --target wasm32-unknown-unknown --release
+BUILD_COMMIT=`git rev-parse HEAD` RUSTFLAGS='-C link-arg=-s' cargo build --all --target wasm32-unknown-unknown --release
 cp target/wasm32-unknown-unknown/release/*.wasm ./out/main.wasm
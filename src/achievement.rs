@@ -0,0 +1,150 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const BADGE_STREAK_DAYS: u32 = 100;
+pub const STREAK_ACHIEVEMENT_DAYS: u32 = 30;
+pub const RAFFLE_ENTRIES_ACHIEVEMENT: u64 = 10;
+
+/// Stored as a bit of `User::achievements`, variants must only be appended.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    FirstWin,
+    HundredDayStreak,
+    FirstClaim,
+    FirstSpin,
+    TenRaffleEntries,
+    ThirtyDayStreak,
+    CharityChampion,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement::FirstClaim,
+    Achievement::FirstSpin,
+    Achievement::TenRaffleEntries,
+    Achievement::FirstWin,
+    Achievement::ThirtyDayStreak,
+    Achievement::HundredDayStreak,
+    Achievement::CharityChampion,
+];
+
+impl Achievement {
+    fn bit(self) -> u64 {
+        1 << self as u8
+    }
+}
+
+#[derive(Serialize)]
+pub struct AchievementOutput {
+    achievement: Achievement,
+    unlocked: bool,
+    bonus: U128,
+}
+
+impl User {
+    pub(crate) fn has_achievement(&self, achievement: Achievement) -> bool {
+        self.achievements & achievement.bit() != 0
+    }
+}
+
+impl ArkanaCoreContract {
+    /// Marks `achievement` as unlocked, grants its one-time bonus and mints
+    /// its badge. Returns false if the user already had it.
+    pub(crate) fn internal_unlock_achievement(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        achievement: Achievement,
+    ) -> bool {
+        if user.has_achievement(achievement) {
+            return false;
+        }
+
+        user.achievements |= achievement.bit();
+
+        let bonus = self.achievement_bonuses.get(&achievement).unwrap_or(0);
+        if bonus > 0 {
+            self.internal_issue_points(account_id, user, bonus);
+        }
+
+        ArkanaEvent::AchievementUnlocked(vec![AchievementUnlockedData {
+            account_id: account_id.clone(),
+            achievement,
+        }])
+        .emit();
+
+        self.internal_mint_badge(account_id, achievement);
+
+        true
+    }
+
+    pub(crate) fn internal_unlock_streak_achievements(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+    ) {
+        self.internal_unlock_achievement(account_id, user, Achievement::FirstClaim);
+        if user.activity.claim_streak() >= STREAK_ACHIEVEMENT_DAYS {
+            self.internal_unlock_achievement(account_id, user, Achievement::ThirtyDayStreak);
+        }
+        if user.activity.claim_streak() >= BADGE_STREAK_DAYS {
+            self.internal_unlock_achievement(account_id, user, Achievement::HundredDayStreak);
+        }
+    }
+
+    /// Counts tickets bought through any payment method.
+    pub(crate) fn internal_record_raffle_entries(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        amount: u64,
+    ) {
+        user.tickets_bought = user.tickets_bought.safe_add(amount);
+        self.internal_add_pass_xp(account_id, PassXpSource::Tickets { count: amount });
+        if user.tickets_bought >= RAFFLE_ENTRIES_ACHIEVEMENT {
+            self.internal_unlock_achievement(account_id, user, Achievement::TenRaffleEntries);
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Points granted once when `achievement` is unlocked, zero for none.
+    pub fn set_achievement_bonus(&mut self, achievement: Achievement, bonus: U128) {
+        self.assert_owner();
+
+        if bonus.0 > 0 {
+            self.achievement_bonuses.insert(&achievement, &bonus.0);
+        } else {
+            self.achievement_bonuses.remove(&achievement);
+        }
+
+        ArkanaEvent::admin_action(
+            "set_achievement_bonus",
+            serde_json::json!({ "achievement": achievement, "bonus": bonus }),
+        );
+    }
+
+    // View Functions
+    pub fn get_achievements(&self, account_id: AccountId) -> Vec<AchievementOutput> {
+        let user = self.internal_find_user(&account_id);
+
+        ACHIEVEMENTS
+            .iter()
+            .map(|achievement| AchievementOutput {
+                achievement: *achievement,
+                unlocked: user
+                    .as_ref()
+                    .map(|user| user.has_achievement(*achievement))
+                    .unwrap_or(false),
+                bonus: U128(self.achievement_bonuses.get(achievement).unwrap_or(0)),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,109 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use serde::Serialize;
+
+use crate::*;
+
+const MS_PER_SECOND: u64 = 1000;
+
+/// Cooldown, streak and rate limit bookkeeping of a user. Timestamps are
+/// kept as second buckets and check-ins as day numbers, with counters only as
+/// wide as they need to be, so the whole struct takes 24 bytes.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy, Default)]
+pub struct UserActivity {
+    last_daily_claim: u32,
+    last_free_spin: u32,
+    window_started_at: u32,
+    booster_expires_at: u32,
+    // UTC day (days since epoch) of the last calendar check-in
+    check_in_day: u16,
+    claim_streak: u16,
+    window_actions: u16,
+    free_spins_used: u8,
+    check_in_streak: u8,
+}
+
+fn to_bucket(timestamp: Timestamp) -> u32 {
+    u32::try_from(timestamp / MS_PER_SECOND).unwrap_or(u32::MAX)
+}
+
+fn from_bucket(bucket: u32) -> Timestamp {
+    u64::from(bucket) * MS_PER_SECOND
+}
+
+fn narrow<T: TryFrom<u32> + Copy>(value: u32, max: T) -> T {
+    T::try_from(value).unwrap_or(max)
+}
+
+impl UserActivity {
+    pub(crate) fn last_daily_claim(&self) -> Timestamp {
+        from_bucket(self.last_daily_claim)
+    }
+
+    pub(crate) fn set_last_daily_claim(&mut self, timestamp: Timestamp) {
+        self.last_daily_claim = to_bucket(timestamp);
+    }
+
+    pub(crate) fn last_free_spin(&self) -> Timestamp {
+        from_bucket(self.last_free_spin)
+    }
+
+    pub(crate) fn set_last_free_spin(&mut self, timestamp: Timestamp) {
+        self.last_free_spin = to_bucket(timestamp);
+    }
+
+    pub(crate) fn window_started_at(&self) -> Timestamp {
+        from_bucket(self.window_started_at)
+    }
+
+    pub(crate) fn set_window_started_at(&mut self, timestamp: Timestamp) {
+        self.window_started_at = to_bucket(timestamp);
+    }
+
+    pub(crate) fn booster_expires_at(&self) -> Timestamp {
+        from_bucket(self.booster_expires_at)
+    }
+
+    pub(crate) fn set_booster_expires_at(&mut self, timestamp: Timestamp) {
+        self.booster_expires_at = to_bucket(timestamp);
+    }
+
+    pub(crate) fn check_in_day(&self) -> u32 {
+        u32::from(self.check_in_day)
+    }
+
+    pub(crate) fn set_check_in_day(&mut self, day: u32) {
+        self.check_in_day = narrow(day, u16::MAX);
+    }
+
+    pub(crate) fn claim_streak(&self) -> u32 {
+        u32::from(self.claim_streak)
+    }
+
+    pub(crate) fn set_claim_streak(&mut self, streak: u32) {
+        self.claim_streak = narrow(streak, u16::MAX);
+    }
+
+    pub(crate) fn window_actions(&self) -> u32 {
+        u32::from(self.window_actions)
+    }
+
+    pub(crate) fn set_window_actions(&mut self, actions: u32) {
+        self.window_actions = narrow(actions, u16::MAX);
+    }
+
+    pub(crate) fn free_spins_used(&self) -> u32 {
+        u32::from(self.free_spins_used)
+    }
+
+    pub(crate) fn set_free_spins_used(&mut self, spins: u32) {
+        self.free_spins_used = narrow(spins, u8::MAX);
+    }
+
+    pub(crate) fn check_in_streak(&self) -> u8 {
+        self.check_in_streak
+    }
+
+    pub(crate) fn set_check_in_streak(&mut self, streak: u8) {
+        self.check_in_streak = streak;
+    }
+}
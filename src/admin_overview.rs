@@ -0,0 +1,77 @@
+use near_sdk::json_types::U64;
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+/// A raffle past its end time that has not been drawn yet.
+#[derive(Serialize)]
+pub struct OverdueRewardOutput {
+    reward_id: U64,
+    title: String,
+    ended_at: U64,
+    total_tickets: U64,
+    status: RewardStatus,
+}
+
+#[derive(Serialize)]
+pub struct AdminOverviewOutput {
+    config: ConfigOutput,
+    pause_state: PauseState,
+    treasury: TreasuryOutput,
+    guardians: Vec<AccountId>,
+    // Listed by `get_operators`, `get_membership_contracts` and
+    // `get_pending_config_changes`
+    operator_count: U64,
+    membership_contract_count: U64,
+    pending_config_change_count: U64,
+    // Among up to `MAX_PAGE_LIMIT` reward ids from `from_reward_id`, the
+    // cursor is the reward id to continue from
+    overdue_rewards: Page<OverdueRewardOutput>,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+
+    /// Everything the ops dashboard watches in one call. View calls have no
+    /// caller to check, so nothing here goes beyond what the other views
+    /// already expose. Overdue rewards are scanned by id starting at
+    /// `from_reward_id`, oldest first.
+    pub fn get_admin_overview(&self, from_reward_id: Option<U64>) -> AdminOverviewOutput {
+        let current_timestamp = env::block_timestamp_ms();
+
+        let start = from_reward_id.map(|v| v.0).unwrap_or(1).max(1);
+        let end = start
+            .saturating_add(MAX_PAGE_LIMIT)
+            .min(self.last_reward_id.saturating_add(1));
+        let overdue_rewards = (start..end.max(start))
+            .filter_map(|reward_id| {
+                let reward = self.rewards.get(&reward_id)?;
+                (!reward.status.is_closed() && reward.ended_at <= current_timestamp).then(|| {
+                    OverdueRewardOutput {
+                        reward_id: U64(reward_id),
+                        title: reward.title(),
+                        ended_at: U64(reward.ended_at),
+                        total_tickets: U64(self.reward_tickets.get(&reward_id).unwrap().total),
+                        status: reward.status,
+                    }
+                })
+            })
+            .collect();
+
+        AdminOverviewOutput {
+            config: self.get_config(),
+            pause_state: self.get_pause_state(),
+            treasury: self.get_treasury(),
+            guardians: self.guardians.to_vec(),
+            operator_count: U64(self.operators.len()),
+            membership_contract_count: U64(self.membership_contracts.len()),
+            pending_config_change_count: U64(self.pending_config_changes.len()),
+            overdue_rewards: Page {
+                items: overdue_rewards,
+                next_index: (end <= self.last_reward_id).then_some(U64(end)),
+            },
+        }
+    }
+}
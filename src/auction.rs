@@ -0,0 +1,256 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type AuctionId = u64;
+
+pub const MAX_AUCTION_EXTENSION: u64 = ONE_DAY;
+
+/// What a sale hands to its buyer: an inventory item, or only a title for
+/// prizes delivered off-chain like raffle rewards.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct SalePrize {
+    pub title: String,
+    pub item: Option<ItemGrant>,
+    // Credited with the price paid, burned when unset
+    pub proceeds_to: Option<AccountId>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Auction {
+    prize: SalePrize,
+    min_bid: Points,
+    min_increment: Points,
+    ends_at: Timestamp,
+    // Bids this close to the end push it back by the same amount
+    extension_ms: u64,
+    highest_bid: Option<(AccountId, Points)>,
+    settled: bool,
+}
+
+#[derive(Serialize)]
+pub struct AuctionOutput {
+    auction_id: U64,
+    prize: SalePrize,
+    min_bid: U128,
+    min_increment: U128,
+    ends_at: U64,
+    extension_ms: U64,
+    highest_bidder: Option<AccountId>,
+    highest_bid: Option<U128>,
+    settled: bool,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn assert_valid_sale_prize(&self, prize: &SalePrize) {
+        if let Some(item) = &prize.item {
+            self.assert_item_exists(&item.item_id);
+        }
+    }
+
+    /// Credits points held by the contract back to an account, or burns them
+    /// if the account has unregistered since.
+    pub(crate) fn internal_return_points(&mut self, account_id: &AccountId, amount: Points) {
+        if self.internal_find_user(account_id).is_some() {
+            let mut user = self.internal_get_user(account_id);
+            user.points = user.points.safe_add(amount);
+            self.internal_save_user(account_id, &user);
+        } else {
+            self.supply.burned = self.supply.burned.safe_add(amount);
+        }
+    }
+
+    /// Hands a sold prize to its buyer and settles the price paid, which the
+    /// contract holds already.
+    pub(crate) fn internal_deliver_sale_prize(
+        &mut self,
+        buyer_id: &AccountId,
+        prize: &SalePrize,
+        price: Points,
+    ) {
+        if let Some(item) = &prize.item {
+            if self.items.get(&item.item_id).is_some() {
+                self.internal_grant_item(buyer_id, &item.item_id, item.amount.0);
+            }
+        }
+
+        match &prize.proceeds_to {
+            Some(receiver_id) => self.internal_return_points(receiver_id, price),
+            None => self.supply.burned = self.supply.burned.safe_add(price),
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the auction's storage, the rest is
+    /// refunded.
+    #[payable]
+    pub fn create_auction(
+        &mut self,
+        prize: SalePrize,
+        min_bid: U128,
+        min_increment: U128,
+        ends_at: U64,
+        extension_ms: U64,
+    ) -> U64 {
+        self.assert_owner();
+        self.assert_valid_sale_prize(&prize);
+
+        assert!(min_bid.0 > 0, "Minimum bid must be greater than zero");
+        assert!(
+            min_increment.0 > 0,
+            "Minimum increment must be greater than zero"
+        );
+        assert!(
+            ends_at.0 > env::block_timestamp_ms(),
+            "Auction must end in the future"
+        );
+        assert!(
+            extension_ms.0 <= MAX_AUCTION_EXTENSION,
+            "Extension cannot exceed {} ms",
+            MAX_AUCTION_EXTENSION
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let auction_id = self.last_auction_id + 1;
+        self.last_auction_id = auction_id;
+
+        self.auctions.insert(
+            &auction_id,
+            &Auction {
+                prize: prize.clone(),
+                min_bid: min_bid.0,
+                min_increment: min_increment.0,
+                ends_at: ends_at.0,
+                extension_ms: extension_ms.0,
+                highest_bid: None,
+                settled: false,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_auction",
+            serde_json::json!({
+                "auction_id": U64(auction_id),
+                "prize": prize,
+                "min_bid": min_bid,
+                "ends_at": ends_at,
+            }),
+        );
+
+        U64(auction_id)
+    }
+
+    /// Escrows `amount` points as the new highest bid and refunds the bid it
+    /// outbids.
+    pub fn bid_auction(&mut self, auction_id: U64, amount: U128) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut auction = self
+            .auctions
+            .get(&auction_id.0)
+            .expect("Auction does not exist");
+
+        let current_timestamp = env::block_timestamp_ms();
+        assert!(current_timestamp < auction.ends_at, "Auction has ended");
+
+        let min_amount = match &auction.highest_bid {
+            Some((_, bid)) => bid.safe_add(auction.min_increment),
+            None => auction.min_bid,
+        };
+        assert!(
+            amount.0 >= min_amount,
+            "Bid must be at least {}",
+            min_amount
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        user.points = user
+            .points
+            .checked_sub(amount.0)
+            .expect("Points insufficient");
+        self.internal_save_user(&account_id, &user);
+
+        if let Some((outbid_id, outbid_amount)) = auction.highest_bid.take() {
+            self.internal_return_points(&outbid_id, outbid_amount);
+            self.internal_close_escrow(&outbid_id);
+        }
+        auction.highest_bid = Some((account_id.clone(), amount.0));
+        self.internal_open_escrow(&account_id);
+
+        if auction.ends_at - current_timestamp < auction.extension_ms {
+            auction.ends_at = current_timestamp + auction.extension_ms;
+        }
+
+        self.auctions.insert(&auction_id.0, &auction);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::AuctionBid(vec![AuctionBidData {
+            auction_id,
+            account_id,
+            amount,
+            ends_at: U64(auction.ends_at),
+        }])
+        .emit();
+    }
+
+    /// Awards the prize to the highest bidder once the auction has ended.
+    /// Callable by anyone.
+    pub fn finalize_auction(&mut self, auction_id: U64) -> Option<AccountId> {
+        let mut auction = self
+            .auctions
+            .get(&auction_id.0)
+            .expect("Auction does not exist");
+        assert!(!auction.settled, "Auction already finalized");
+        assert!(
+            env::block_timestamp_ms() >= auction.ends_at,
+            "Auction has not ended"
+        );
+
+        auction.settled = true;
+        self.auctions.insert(&auction_id.0, &auction);
+
+        let (winner, amount) = auction.highest_bid?;
+        self.internal_close_escrow(&winner);
+        self.internal_deliver_sale_prize(&winner, &auction.prize, amount);
+
+        ArkanaEvent::AuctionFinalized(vec![AuctionFinalizedData {
+            auction_id,
+            winner: winner.clone(),
+            amount: U128(amount),
+        }])
+        .emit();
+
+        Some(winner)
+    }
+
+    // View Functions
+    pub fn get_auctions(&self, from_index: Option<U64>, limit: Option<u64>) -> Page<AuctionOutput> {
+        paginate(self.auctions.iter(), from_index, limit).map(|(auction_id, auction)| {
+            AuctionOutput {
+                auction_id: U64(auction_id),
+                prize: auction.prize,
+                min_bid: U128(auction.min_bid),
+                min_increment: U128(auction.min_increment),
+                ends_at: U64(auction.ends_at),
+                extension_ms: U64(auction.extension_ms),
+                highest_bid: auction.highest_bid.as_ref().map(|(_, bid)| U128(*bid)),
+                highest_bidder: auction.highest_bid.map(|(bidder, _)| bidder),
+                settled: auction.settled,
+            }
+        })
+    }
+}
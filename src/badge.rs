@@ -0,0 +1,200 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, ext_contract, near_bindgen, AccountId, Balance, Gas, PromiseResult};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+const GAS_FOR_NFT_MINT: Gas = Gas(20_000_000_000_000);
+const GAS_FOR_ON_BADGE_MINTED: Gas = Gas(10_000_000_000_000);
+
+#[allow(dead_code)]
+#[ext_contract(ext_badge_nft)]
+trait BadgeNft {
+    fn nft_mint(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: serde_json::Value,
+    );
+}
+
+/// NFT contract badges are minted on. `mint_deposit` is attached to every
+/// mint for its storage and paid out of the treasury.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct BadgeConfig {
+    pub contract_id: AccountId,
+    pub mint_deposit: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct BadgeMetadata {
+    pub title: String,
+    pub description: Option<String>,
+    pub media: Option<String>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgeMintStatus {
+    Pending,
+    Minted,
+}
+
+#[derive(Serialize)]
+pub struct BadgeOutput {
+    achievement: Achievement,
+    #[serde(flatten)]
+    metadata: BadgeMetadata,
+}
+
+fn badge_token_id(account_id: &AccountId, achievement: Achievement) -> TokenId {
+    format!(
+        "{}:{}",
+        serde_json::to_value(achievement).unwrap().as_str().unwrap(),
+        account_id
+    )
+}
+
+impl ArkanaCoreContract {
+    /// Starts minting the badge of `achievement` to `account_id`. Skipped
+    /// when badges are not configured, the badge was already minted or is
+    /// in flight, or the treasury cannot cover the deposit.
+    pub(crate) fn internal_mint_badge(
+        &mut self,
+        account_id: &AccountId,
+        achievement: Achievement,
+    ) -> bool {
+        let config = match &self.badge_config {
+            Some(config) => config.clone(),
+            None => return false,
+        };
+        let metadata = match self.badges.get(&achievement) {
+            Some(metadata) => metadata,
+            None => return false,
+        };
+
+        let key = (account_id.clone(), achievement);
+        let deposit: Balance = config.mint_deposit.0;
+        if self.badge_mints.get(&key).is_some() || self.treasury_balance < deposit {
+            return false;
+        }
+
+        self.treasury_balance -= deposit;
+        self.badge_mints.insert(&key, &BadgeMintStatus::Pending);
+
+        ext_badge_nft::ext(config.contract_id)
+            .with_static_gas(GAS_FOR_NFT_MINT)
+            .with_attached_deposit(deposit)
+            .nft_mint(
+                badge_token_id(account_id, achievement),
+                account_id.clone(),
+                serde_json::json!({
+                    "title": metadata.title,
+                    "description": metadata.description,
+                    "media": metadata.media,
+                    "copies": 1,
+                }),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_BADGE_MINTED)
+                    .on_badge_minted(account_id.clone(), achievement, U128(deposit)),
+            );
+
+        true
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_badge_config(&mut self, config: Option<BadgeConfig>) {
+        self.assert_owner();
+
+        self.badge_config = config.clone();
+
+        ArkanaEvent::admin_action("set_badge_config", serde_json::json!({ "config": config }));
+    }
+
+    /// Sets the badge minted for `achievement`, `None` stops minting it.
+    pub fn set_badge(&mut self, achievement: Achievement, metadata: Option<BadgeMetadata>) {
+        self.assert_owner();
+
+        match &metadata {
+            Some(metadata) => self.badges.insert(&achievement, metadata),
+            None => self.badges.remove(&achievement),
+        };
+
+        ArkanaEvent::admin_action(
+            "set_badge",
+            serde_json::json!({ "achievement": achievement, "metadata": metadata }),
+        );
+    }
+
+    /// Mints the caller's badge for an achievement unlocked earlier, e.g.
+    /// before badges were configured or after a failed mint.
+    pub fn claim_badge(&mut self, achievement: Achievement) -> bool {
+        let account_id = env::predecessor_account_id();
+        let user = self.internal_get_user(&account_id);
+        assert!(
+            user.has_achievement(achievement),
+            "Achievement is not unlocked"
+        );
+
+        self.internal_mint_badge(&account_id, achievement)
+    }
+
+    #[private]
+    pub fn on_badge_minted(
+        &mut self,
+        account_id: AccountId,
+        achievement: Achievement,
+        deposit: U128,
+    ) -> bool {
+        let key = (account_id.clone(), achievement);
+
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.badge_mints.insert(&key, &BadgeMintStatus::Minted);
+
+                ArkanaEvent::BadgeMinted(vec![AchievementUnlockedData {
+                    account_id,
+                    achievement,
+                }])
+                .emit();
+
+                true
+            }
+            _ => {
+                // The NFT contract refunds the deposit on failure
+                self.badge_mints.remove(&key);
+                self.treasury_balance = self.treasury_balance.safe_add(deposit.0);
+
+                false
+            }
+        }
+    }
+
+    // View Functions
+    pub fn get_badge_config(&self) -> Option<BadgeConfig> {
+        self.badge_config.clone()
+    }
+
+    pub fn get_badges(&self) -> Vec<BadgeOutput> {
+        self.badges
+            .iter()
+            .map(|(achievement, metadata)| BadgeOutput {
+                achievement,
+                metadata,
+            })
+            .collect()
+    }
+
+    pub fn get_badge_status(
+        &self,
+        account_id: AccountId,
+        achievement: Achievement,
+    ) -> Option<BadgeMintStatus> {
+        self.badge_mints.get(&(account_id, achievement))
+    }
+}
@@ -0,0 +1,315 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+// Claimed levels are tracked as bits of a u64
+pub const MAX_PASS_LEVELS: usize = 64;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PassReward {
+    Points { amount: U128 },
+    Item { item_id: ItemId, amount: U64 },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct PassLevel {
+    pub free: Option<PassReward>,
+    pub premium: Option<PassReward>,
+}
+
+/// Pass XP per action, separate from the XP that comes with points.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+pub struct PassXpConfig {
+    pub claim: U64,
+    pub spin: U64,
+    pub ticket: U64,
+}
+
+/// Battle pass of the season it was set in. Zero prices mean the premium
+/// track is not sold for that currency.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct BattlePass {
+    pub levels: Vec<PassLevel>,
+    pub xp_per_level: U64,
+    pub xp: PassXpConfig,
+    pub premium_price: U128,
+    pub premium_price_near: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct PassProgress {
+    season: SeasonId,
+    xp: u64,
+    premium: bool,
+    claimed_free: u64,
+    claimed_premium: u64,
+}
+
+pub(crate) enum PassXpSource {
+    Claim,
+    Spin,
+    Tickets { count: u64 },
+}
+
+#[derive(Serialize)]
+pub struct BattlePassOutput {
+    season: SeasonId,
+    #[serde(flatten)]
+    pass: BattlePass,
+}
+
+#[derive(Serialize)]
+pub struct PassProgressOutput {
+    xp: U64,
+    level: u32,
+    premium: bool,
+    claimed_free: Vec<u32>,
+    claimed_premium: Vec<u32>,
+}
+
+fn claimed_levels(claimed: u64) -> Vec<u32> {
+    (0..MAX_PASS_LEVELS as u32)
+        .filter(|index| claimed & (1 << index) != 0)
+        .map(|index| index + 1)
+        .collect()
+}
+
+impl ArkanaCoreContract {
+    /// The battle pass, if one was set for the current season.
+    fn internal_current_battle_pass(&self) -> Option<&BattlePass> {
+        self.battle_pass
+            .as_ref()
+            .filter(|_| self.battle_pass_season == self.current_season)
+    }
+
+    /// Progress on the current pass, starting over every season.
+    fn internal_pass_progress(&self, account_id: &AccountId) -> PassProgress {
+        self.pass_progress
+            .get(account_id)
+            .filter(|progress| progress.season == self.battle_pass_season)
+            .unwrap_or(PassProgress {
+                season: self.battle_pass_season,
+                ..Default::default()
+            })
+    }
+
+    fn internal_pass_level(&self, progress: &PassProgress) -> u32 {
+        match self.internal_current_battle_pass() {
+            Some(pass) => (progress.xp / pass.xp_per_level.0).min(pass.levels.len() as u64) as u32,
+            None => 0,
+        }
+    }
+
+    /// Advances the user's pass. Storage is accounted by the caller.
+    pub(crate) fn internal_add_pass_xp(&mut self, account_id: &AccountId, source: PassXpSource) {
+        let config = match self.internal_current_battle_pass() {
+            Some(pass) => pass.xp,
+            None => return,
+        };
+
+        let xp = match source {
+            PassXpSource::Claim => config.claim.0,
+            PassXpSource::Spin => config.spin.0,
+            PassXpSource::Tickets { count } => config.ticket.0.safe_mul(count),
+        };
+        if xp == 0 {
+            return;
+        }
+
+        let mut progress = self.internal_pass_progress(account_id);
+        progress.xp = progress.xp.safe_add(xp);
+        self.pass_progress.insert(account_id, &progress);
+    }
+
+    fn internal_unlock_premium_pass(&mut self, account_id: &AccountId) {
+        let mut progress = self.internal_pass_progress(account_id);
+        assert!(!progress.premium, "Premium pass already unlocked");
+
+        progress.premium = true;
+        self.pass_progress.insert(account_id, &progress);
+
+        ArkanaEvent::PremiumPassUnlocked(vec![PremiumPassUnlockedData {
+            account_id: account_id.clone(),
+            season: self.battle_pass_season,
+        }])
+        .emit();
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Sets the battle pass for the current season, `None` removes it.
+    /// Replacing the pass mid-season keeps progress and claimed levels.
+    pub fn set_battle_pass(&mut self, pass: Option<BattlePass>) {
+        self.assert_owner();
+
+        if let Some(pass) = &pass {
+            assert!(
+                !pass.levels.is_empty() && pass.levels.len() <= MAX_PASS_LEVELS,
+                "Battle pass must have between 1 and {} levels",
+                MAX_PASS_LEVELS
+            );
+            assert!(
+                pass.xp_per_level.0 > 0,
+                "XP per level must be greater than zero"
+            );
+            for level in pass.levels.iter() {
+                for reward in level.free.iter().chain(level.premium.iter()) {
+                    if let PassReward::Item { item_id, .. } = reward {
+                        self.assert_item_exists(item_id);
+                    }
+                }
+            }
+        }
+
+        self.battle_pass = pass.clone();
+        self.battle_pass_season = self.current_season;
+
+        ArkanaEvent::admin_action(
+            "set_battle_pass",
+            serde_json::json!({ "season": self.current_season, "pass": pass }),
+        );
+    }
+
+    pub fn buy_premium_pass(&mut self) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let price = self
+            .internal_current_battle_pass()
+            .expect("No battle pass this season")
+            .premium_price
+            .0;
+        assert!(price > 0, "Premium pass is not sold for points");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_burn_points(&mut user, price);
+        self.internal_unlock_premium_pass(&account_id);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+    }
+
+    #[payable]
+    pub fn buy_premium_pass_with_near(&mut self) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let price = self
+            .internal_current_battle_pass()
+            .expect("No battle pass this season")
+            .premium_price_near
+            .0;
+        assert!(price > 0, "Premium pass is not sold for NEAR");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_charge_near(price, TreasurySource::BattlePass);
+        self.internal_unlock_premium_pass(&account_id);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+    }
+
+    /// Claims the free or premium reward of a reached level, counting levels
+    /// from 1.
+    pub fn claim_pass_reward(&mut self, level: u32, premium: bool) -> PassReward {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let pass = self
+            .internal_current_battle_pass()
+            .expect("No battle pass this season");
+        let mut progress = self.internal_pass_progress(&account_id);
+
+        assert!(
+            level > 0 && level <= self.internal_pass_level(&progress),
+            "Level not reached"
+        );
+        let pass_level = &pass.levels[level as usize - 1];
+        let reward = if premium {
+            assert!(progress.premium, "Premium pass required");
+            pass_level.premium.clone()
+        } else {
+            pass_level.free.clone()
+        }
+        .expect("Level has no reward on this track");
+
+        let claimed = if premium {
+            &mut progress.claimed_premium
+        } else {
+            &mut progress.claimed_free
+        };
+        let bit = 1 << (level - 1);
+        assert!(*claimed & bit == 0, "Reward already claimed");
+        *claimed |= bit;
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.pass_progress.insert(&account_id, &progress);
+
+        match &reward {
+            PassReward::Points { amount } => {
+                self.internal_issue_points(&account_id, &mut user, amount.0);
+            }
+            PassReward::Item { item_id, amount } => {
+                // Skipped if the item is no longer defined
+                if self.items.get(item_id).is_some() {
+                    self.internal_grant_item(&account_id, item_id, amount.0);
+                }
+            }
+        }
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PassRewardClaimed(vec![PassRewardClaimedData {
+            account_id,
+            season: self.battle_pass_season,
+            level,
+            premium,
+            reward: reward.clone(),
+        }])
+        .emit();
+
+        reward
+    }
+
+    // View Functions
+    pub fn get_battle_pass(&self) -> Option<BattlePassOutput> {
+        self.internal_current_battle_pass()
+            .map(|pass| BattlePassOutput {
+                season: self.battle_pass_season,
+                pass: pass.clone(),
+            })
+    }
+
+    pub fn get_pass_progress(&self, account_id: AccountId) -> PassProgressOutput {
+        let progress = self.internal_pass_progress(&account_id);
+
+        PassProgressOutput {
+            xp: U64(progress.xp),
+            level: self.internal_pass_level(&progress),
+            premium: progress.premium,
+            claimed_free: claimed_levels(progress.claimed_free),
+            claimed_premium: claimed_levels(progress.claimed_premium),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BlacklistEntry {
+    reason: String,
+    blacklisted_at: Timestamp,
+    blacklisted_by: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct BlacklistEntryOutput {
+    account_id: AccountId,
+    reason: String,
+    blacklisted_at: U64,
+    blacklisted_by: AccountId,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_is_blacklisted(&self, account_id: &AccountId) -> bool {
+        self.blacklist.get(account_id).is_some()
+    }
+
+    pub(crate) fn assert_not_blacklisted(&self, account_id: &AccountId) {
+        assert!(
+            !self.internal_is_blacklisted(account_id),
+            "Account is blacklisted"
+        );
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[payable]
+    pub fn blacklist_account(&mut self, account_id: AccountId, reason: String) {
+        assert_one_yocto();
+
+        self.assert_owner_or_operator();
+
+        assert!(!reason.is_empty(), "Reason is required");
+        assert!(account_id != self.owner, "Cannot blacklist the owner");
+
+        let operator_id = env::predecessor_account_id();
+        self.blacklist.insert(
+            &account_id,
+            &BlacklistEntry {
+                reason: reason.clone(),
+                blacklisted_at: env::block_timestamp_ms(),
+                blacklisted_by: operator_id.clone(),
+            },
+        );
+
+        ArkanaEvent::AccountBlacklisted(vec![AccountBlacklistedData {
+            account_id,
+            operator_id,
+            reason,
+        }])
+        .emit();
+    }
+
+    #[payable]
+    pub fn unblacklist_account(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner_or_operator();
+
+        self.blacklist
+            .remove(&account_id)
+            .expect("Account is not blacklisted");
+
+        ArkanaEvent::AccountUnblacklisted(vec![AccountData { account_id }]).emit();
+    }
+
+    // View Functions
+    pub fn is_blacklisted(&self, account_id: AccountId) -> bool {
+        self.internal_is_blacklisted(&account_id)
+    }
+
+    pub fn get_blacklist_entry(&self, account_id: AccountId) -> Option<BlacklistEntryOutput> {
+        self.blacklist
+            .get(&account_id)
+            .map(|entry| BlacklistEntryOutput {
+                account_id,
+                reason: entry.reason,
+                blacklisted_at: U64(entry.blacklisted_at),
+                blacklisted_by: entry.blacklisted_by,
+            })
+    }
+
+    pub fn get_blacklisted_accounts(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<BlacklistEntryOutput> {
+        paginate(self.blacklist.iter(), from_index, limit).map(|(account_id, entry)| {
+            BlacklistEntryOutput {
+                account_id,
+                reason: entry.reason,
+                blacklisted_at: U64(entry.blacklisted_at),
+                blacklisted_by: entry.blacklisted_by,
+            }
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+
+use crate::*;
+
+pub const MAX_CHAIN_ID_LEN: usize = 32;
+pub const MAX_BRIDGE_ADDRESS_LEN: usize = 128;
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[payable]
+    pub fn add_bridge_chain(&mut self, chain_id: String) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(
+            !chain_id.is_empty() && chain_id.len() <= MAX_CHAIN_ID_LEN,
+            "Chain id must be between 1 and {} bytes",
+            MAX_CHAIN_ID_LEN
+        );
+
+        self.bridge_chains.insert(&chain_id);
+
+        ArkanaEvent::admin_action(
+            "add_bridge_chain",
+            serde_json::json!({ "chain_id": chain_id }),
+        );
+    }
+
+    #[payable]
+    pub fn remove_bridge_chain(&mut self, chain_id: String) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.bridge_chains.remove(&chain_id);
+
+        ArkanaEvent::admin_action(
+            "remove_bridge_chain",
+            serde_json::json!({ "chain_id": chain_id }),
+        );
+    }
+
+    /// Locks points for the bridge relayer, which credits them to
+    /// `destination_address` on `destination_chain`. Returns the nonce of
+    /// the transfer.
+    #[payable]
+    pub fn bridge_out(
+        &mut self,
+        amount: U128,
+        destination_chain: String,
+        destination_address: String,
+    ) -> U64 {
+        assert_one_yocto();
+
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+        assert!(
+            self.bridge_chains.contains(&destination_chain),
+            "Chain is not supported"
+        );
+        assert!(
+            !destination_address.is_empty() && destination_address.len() <= MAX_BRIDGE_ADDRESS_LEN,
+            "Destination address must be between 1 and {} bytes",
+            MAX_BRIDGE_ADDRESS_LEN
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        user.points = user
+            .points
+            .checked_sub(amount.0)
+            .expect("Points insufficient");
+        self.supply.bridged = self.supply.bridged.safe_add(amount.0);
+        self.internal_save_user(&account_id, &user);
+
+        self.last_bridge_nonce += 1;
+
+        ArkanaEvent::BridgeOut(vec![BridgeOutData {
+            nonce: U64(self.last_bridge_nonce),
+            account_id,
+            amount,
+            destination_chain,
+            destination_address,
+        }])
+        .emit();
+
+        U64(self.last_bridge_nonce)
+    }
+
+    /// Releases points locked by `bridge_out` once they are burned on
+    /// `source_chain`. `source_tx` identifies the burn and can only be
+    /// processed once.
+    pub fn bridge_in(
+        &mut self,
+        source_chain: String,
+        source_tx: String,
+        account_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        self.assert_owner_or_operator();
+
+        assert!(
+            self.bridge_chains.contains(&source_chain),
+            "Chain is not supported"
+        );
+        assert!(
+            amount.0 <= self.supply.bridged,
+            "Amount exceeds the points locked in the bridge"
+        );
+
+        let transfer_key = format!("{}:{}", source_chain, source_tx);
+        assert!(
+            self.bridge_in_transfers.insert(&transfer_key),
+            "Transfer already processed"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        user.points = user.points.safe_add(amount.0);
+        self.supply.bridged = self.supply.bridged.safe_sub(amount.0);
+        self.internal_save_user(&account_id, &user);
+
+        ArkanaEvent::BridgeIn(vec![BridgeInData {
+            account_id,
+            amount,
+            source_chain,
+            source_tx,
+            operator_id: env::predecessor_account_id(),
+        }])
+        .emit();
+
+        U128(user.points)
+    }
+
+    // View Functions
+    pub fn get_bridge_chains(&self) -> Vec<String> {
+        self.bridge_chains.to_vec()
+    }
+
+    pub fn is_bridge_transfer_processed(&self, source_chain: String, source_tx: String) -> bool {
+        self.bridge_in_transfers
+            .contains(&format!("{}:{}", source_chain, source_tx))
+    }
+}
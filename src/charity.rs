@@ -0,0 +1,309 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type CampaignId = u64;
+
+/// Owner matching of charity burns: every `points_per_ticket` points a
+/// player has burned in the campaign earns a ticket for `reward_id`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct CharityMatch {
+    pub reward_id: U64,
+    pub points_per_ticket: U128,
+}
+
+/// Points burned toward a cause until `ends_at`. The top `badge_slots`
+/// burners unlock the charity champion achievement when it is closed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CharityCampaign {
+    cause: String,
+    ends_at: Timestamp,
+    badge_slots: u8,
+    matching: Option<CharityMatch>,
+    total_burned: Points,
+    top_burners: Vec<(AccountId, Points)>,
+    closed: bool,
+}
+
+#[derive(Serialize)]
+pub struct CharityBurnerOutput {
+    rank: u32,
+    account_id: AccountId,
+    burned: U128,
+}
+
+#[derive(Serialize)]
+pub struct CharityCampaignOutput {
+    campaign_id: U64,
+    cause: String,
+    ends_at: U64,
+    badge_slots: u8,
+    matching: Option<CharityMatch>,
+    total_burned: U128,
+    top_burners: Vec<CharityBurnerOutput>,
+    closed: bool,
+}
+
+impl CharityCampaign {
+    fn to_output(&self, campaign_id: CampaignId) -> CharityCampaignOutput {
+        CharityCampaignOutput {
+            campaign_id: U64(campaign_id),
+            cause: self.cause.clone(),
+            ends_at: U64(self.ends_at),
+            badge_slots: self.badge_slots,
+            matching: self.matching.clone(),
+            total_burned: U128(self.total_burned),
+            top_burners: self
+                .top_burners
+                .iter()
+                .enumerate()
+                .map(|(index, (account_id, burned))| CharityBurnerOutput {
+                    rank: index as u32 + 1,
+                    account_id: account_id.clone(),
+                    burned: U128(*burned),
+                })
+                .collect(),
+            closed: self.closed,
+        }
+    }
+
+    /// Same ordering as the season leaderboard, earlier burners win ties.
+    fn update_top_burners(&mut self, account_id: &AccountId, burned: Points) {
+        let top_burners = &mut self.top_burners;
+        if let Some(index) = top_burners.iter().position(|(a, _)| a == account_id) {
+            top_burners.remove(index);
+        }
+        let index = top_burners
+            .iter()
+            .position(|(_, amount)| *amount < burned)
+            .unwrap_or(top_burners.len());
+        top_burners.insert(index, (account_id.clone(), burned));
+        top_burners.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+impl ArkanaCoreContract {
+    /// Grants the matched tickets newly earned by a burn, skipped once the
+    /// reward has ended.
+    fn internal_match_charity_burn(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        matching: &CharityMatch,
+        previously_burned: Points,
+        burned: Points,
+    ) -> u64 {
+        let points_per_ticket = matching.points_per_ticket.0;
+        let tickets =
+            u64::try_from(burned / points_per_ticket - previously_burned / points_per_ticket)
+                .expect(ERR_OVERFLOW);
+        if tickets == 0 {
+            return 0;
+        }
+
+        match self.rewards.get(&matching.reward_id.0) {
+            Some(reward) if reward.is_open(env::block_timestamp_ms()) => {
+                self.internal_add_tickets(matching.reward_id.0, account_id, tickets);
+                self.internal_record_raffle_entries(account_id, user, tickets);
+                tickets
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the campaign's storage, the rest is
+    /// refunded.
+    #[payable]
+    pub fn create_charity_campaign(&mut self, cause: String, ends_at: U64, badge_slots: u8) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            ends_at.0 > env::block_timestamp_ms(),
+            "Campaign must end in the future"
+        );
+        assert!(
+            badge_slots as usize <= LEADERBOARD_SIZE,
+            "Cannot award more than {} badges",
+            LEADERBOARD_SIZE
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let campaign_id = self.last_charity_campaign_id + 1;
+        self.last_charity_campaign_id = campaign_id;
+
+        self.charity_campaigns.insert(
+            &campaign_id,
+            &CharityCampaign {
+                cause: cause.clone(),
+                ends_at: ends_at.0,
+                badge_slots,
+                matching: None,
+                total_burned: 0,
+                top_burners: vec![],
+                closed: false,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_charity_campaign",
+            serde_json::json!({
+                "campaign_id": U64(campaign_id),
+                "cause": cause,
+                "ends_at": ends_at,
+                "badge_slots": badge_slots,
+            }),
+        );
+
+        U64(campaign_id)
+    }
+
+    /// Matches later burns with raffle tickets, None stops matching.
+    pub fn set_charity_match(&mut self, campaign_id: U64, matching: Option<CharityMatch>) {
+        self.assert_owner();
+
+        let mut campaign = self
+            .charity_campaigns
+            .get(&campaign_id.0)
+            .expect("Campaign does not exist");
+
+        if let Some(matching) = &matching {
+            assert!(
+                matching.points_per_ticket.0 > 0,
+                "Points per ticket must be greater than zero"
+            );
+            self.assert_reward_exists(matching.reward_id.0);
+        }
+
+        campaign.matching = matching.clone();
+        self.charity_campaigns.insert(&campaign_id.0, &campaign);
+
+        ArkanaEvent::admin_action(
+            "set_charity_match",
+            serde_json::json!({ "campaign_id": campaign_id, "matching": matching }),
+        );
+    }
+
+    /// Burns points toward the campaign's cause. Returns the matched raffle
+    /// tickets granted for it.
+    pub fn burn_for_charity(&mut self, campaign_id: U64, amount: U128) -> U64 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+
+        let mut campaign = self
+            .charity_campaigns
+            .get(&campaign_id.0)
+            .expect("Campaign does not exist");
+        assert!(
+            env::block_timestamp_ms() < campaign.ends_at,
+            "Campaign has ended"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_burn_points(&mut user, amount.0);
+
+        let key = (campaign_id.0, account_id.clone());
+        let previously_burned = self.charity_burns.get(&key).unwrap_or(0);
+        let burned = previously_burned.safe_add(amount.0);
+        self.charity_burns.insert(&key, &burned);
+
+        campaign.total_burned = campaign.total_burned.safe_add(amount.0);
+        campaign.update_top_burners(&account_id, burned);
+
+        let tickets = match campaign.matching.clone() {
+            Some(matching) => self.internal_match_charity_burn(
+                &account_id,
+                &mut user,
+                &matching,
+                previously_burned,
+                burned,
+            ),
+            None => 0,
+        };
+
+        self.charity_campaigns.insert(&campaign_id.0, &campaign);
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::CharityBurn(vec![CharityBurnData {
+            campaign_id,
+            account_id,
+            amount,
+            matched_tickets: U64(tickets),
+        }])
+        .emit();
+
+        U64(tickets)
+    }
+
+    /// Awards the recognition badges of an ended campaign. Callable by
+    /// anyone, top burners who have unregistered since are skipped.
+    pub fn close_charity_campaign(&mut self, campaign_id: U64) {
+        let mut campaign = self
+            .charity_campaigns
+            .get(&campaign_id.0)
+            .expect("Campaign does not exist");
+        assert!(!campaign.closed, "Campaign already closed");
+        assert!(
+            env::block_timestamp_ms() >= campaign.ends_at,
+            "Campaign has not ended"
+        );
+
+        for (account_id, _) in campaign
+            .top_burners
+            .iter()
+            .take(campaign.badge_slots as usize)
+        {
+            if let Some(mut user) = self.internal_find_user(account_id) {
+                self.internal_unlock_achievement(
+                    account_id,
+                    &mut user,
+                    Achievement::CharityChampion,
+                );
+                self.internal_save_user(account_id, &user);
+            }
+        }
+
+        campaign.closed = true;
+        self.charity_campaigns.insert(&campaign_id.0, &campaign);
+    }
+
+    // View Functions
+    pub fn get_charity_campaign(&self, campaign_id: U64) -> Option<CharityCampaignOutput> {
+        self.charity_campaigns
+            .get(&campaign_id.0)
+            .map(|campaign| campaign.to_output(campaign_id.0))
+    }
+
+    pub fn get_charity_campaigns(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<CharityCampaignOutput> {
+        paginate(self.charity_campaigns.iter(), from_index, limit)
+            .map(|(campaign_id, campaign)| campaign.to_output(campaign_id))
+    }
+
+    pub fn get_charity_burn(&self, campaign_id: U64, account_id: AccountId) -> U128 {
+        U128(
+            self.charity_burns
+                .get(&(campaign_id.0, account_id))
+                .unwrap_or(0),
+        )
+    }
+}
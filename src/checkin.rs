@@ -0,0 +1,196 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const MAX_CALENDAR_DAYS: usize = 31;
+pub const MAX_BONUS_SPINS: u8 = 5;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CheckInBonus {
+    Tickets { reward_id: U64, amount: U64 },
+    Spins { count: u8 },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct CheckInDay {
+    pub points: U128,
+    pub bonus: Option<CheckInBonus>,
+}
+
+#[derive(Serialize)]
+pub struct CheckInCalendarOutput {
+    year: u32,
+    month: u32,
+    streak: u8,
+    checked_in_today: bool,
+    days: Vec<CheckInDayOutput>,
+}
+
+#[derive(Serialize)]
+pub struct CheckInDayOutput {
+    day: u8,
+    points: U128,
+    bonus: Option<CheckInBonus>,
+    claimed: bool,
+}
+
+fn epoch_day() -> u32 {
+    (env::block_timestamp_ms() / ONE_DAY) as u32
+}
+
+/// Converts days since the Unix epoch to a UTC (year, month) pair.
+fn civil_month(epoch_day: u32) -> (u32, u32) {
+    let z = u64::from(epoch_day) + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    (year as u32, month as u32)
+}
+
+impl ArkanaCoreContract {
+    /// Streak and calendar day a claim today would check `user` in with,
+    /// `None` if they already checked in or check-ins are disabled. Missing
+    /// a day or entering a new month restarts at day one.
+    pub(crate) fn internal_next_check_in(&self, user: &User) -> Option<(u8, CheckInDay)> {
+        if self.check_in_calendar.is_empty() {
+            return None;
+        }
+
+        let today = epoch_day();
+        let last_day = user.activity.check_in_day();
+        if last_day == today {
+            return None;
+        }
+
+        let is_consecutive = last_day + 1 == today && civil_month(last_day) == civil_month(today);
+        let streak = if is_consecutive {
+            user.activity.check_in_streak() + 1
+        } else {
+            1
+        };
+
+        let index = (streak as usize).min(self.check_in_calendar.len()) - 1;
+        Some((streak, self.check_in_calendar[index].clone()))
+    }
+
+    /// Advances the user's calendar on their first daily claim of a UTC day.
+    pub(crate) fn internal_check_in(&mut self, account_id: &AccountId, user: &mut User) {
+        let (streak, day) = match self.internal_next_check_in(user) {
+            Some(check_in) => check_in,
+            None => return,
+        };
+        user.activity.set_check_in_streak(streak);
+        user.activity.set_check_in_day(epoch_day());
+
+        let points = self.internal_apply_earnings_multiplier(day.points.0);
+        self.internal_issue_points(account_id, user, points);
+
+        match &day.bonus {
+            Some(CheckInBonus::Tickets { reward_id, amount }) => {
+                // A bonus for a reward that has ended since is skipped
+                if let Some(reward) = self.rewards.get(&reward_id.0) {
+                    if reward.is_open(env::block_timestamp_ms()) {
+                        let initial_storage_usage = env::storage_usage();
+                        self.internal_add_tickets(reward_id.0, account_id, amount.0);
+                        self.internal_record_raffle_entries(account_id, user, amount.0);
+                        self.internal_update_storage_usage(account_id, initial_storage_usage);
+                    }
+                }
+            }
+            Some(CheckInBonus::Spins { count }) => {
+                let initial_storage_usage = env::storage_usage();
+                for _ in 0..*count {
+                    let segment = self.internal_spin_wheel(user);
+                    self.internal_award_spin(account_id, user, &segment);
+                }
+                self.internal_update_storage_usage(account_id, initial_storage_usage);
+            }
+            None => {}
+        }
+
+        ArkanaEvent::CheckedIn(vec![CheckedInData {
+            account_id: account_id.clone(),
+            streak: user.activity.check_in_streak(),
+            points: day.points,
+            bonus: day.bonus,
+        }])
+        .emit();
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Rewards for each consecutive check-in day of a month, the last entry
+    /// repeats for longer streaks. An empty calendar disables check-ins.
+    pub fn set_check_in_calendar(&mut self, days: Vec<CheckInDay>) {
+        self.assert_owner();
+
+        assert!(
+            days.len() <= MAX_CALENDAR_DAYS,
+            "Calendar cannot have more than {} days",
+            MAX_CALENDAR_DAYS
+        );
+        for day in days.iter() {
+            if let Some(CheckInBonus::Spins { count }) = day.bonus {
+                assert!(
+                    count <= MAX_BONUS_SPINS,
+                    "Cannot grant more than {} spins",
+                    MAX_BONUS_SPINS
+                );
+            }
+        }
+
+        self.check_in_calendar = days.clone();
+
+        ArkanaEvent::admin_action("set_check_in_calendar", serde_json::json!({ "days": days }));
+    }
+
+    // View Functions
+    pub fn get_check_in_calendar(&self, account_id: AccountId) -> CheckInCalendarOutput {
+        let today = epoch_day();
+        let (year, month) = civil_month(today);
+
+        // A streak only carries on if it was extended yesterday or today
+        let (streak, checked_in_today) = self
+            .internal_find_user(&account_id)
+            .filter(|user| {
+                user.activity.check_in_streak() > 0
+                    && user.activity.check_in_day() + 1 >= today
+                    && civil_month(user.activity.check_in_day()) == (year, month)
+            })
+            .map(|user| {
+                (
+                    user.activity.check_in_streak(),
+                    user.activity.check_in_day() == today,
+                )
+            })
+            .unwrap_or((0, false));
+
+        CheckInCalendarOutput {
+            year,
+            month,
+            streak,
+            checked_in_today,
+            days: self
+                .check_in_calendar
+                .iter()
+                .enumerate()
+                .map(|(index, day)| CheckInDayOutput {
+                    day: index as u8 + 1,
+                    points: day.points,
+                    bonus: day.bonus.clone(),
+                    claimed: index < streak as usize,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::json_types::U64;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, StorageUsage};
+use serde::Serialize;
+
+use crate::*;
+
+// Entries removed right away when a collection is dropped, the rest is left
+// to `process_cleanups`
+pub const INLINE_CLEANUP_LIMIT: u64 = 100;
+pub const MAX_CLEANUP_BATCH: u64 = 1_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CleanupTarget {
+    History { account_id: AccountId },
+    RewardTickets { reward_id: U64 },
+}
+
+/// A dropped collection whose entries are still in storage.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum PendingCleanup {
+    History(Vector<HistoryEntry>),
+    RewardTickets(Vector<(u64, AccountId)>),
+}
+
+impl PendingCleanup {
+    fn remaining(&self) -> u64 {
+        match self {
+            PendingCleanup::History(entries) => entries.len(),
+            PendingCleanup::RewardTickets(tickets) => tickets.len(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PendingCleanupOutput {
+    target: CleanupTarget,
+    remaining: U64,
+}
+
+impl ArkanaCoreContract {
+    /// Removes the first entries of a dropped collection and queues the rest,
+    /// so dropping it never costs more than `INLINE_CLEANUP_LIMIT` removals.
+    pub(crate) fn internal_schedule_cleanup(
+        &mut self,
+        target: CleanupTarget,
+        mut cleanup: PendingCleanup,
+    ) {
+        self.internal_run_cleanup(&target, &mut cleanup, INLINE_CLEANUP_LIMIT);
+
+        if cleanup.remaining() > 0 {
+            self.pending_cleanups.insert(&target, &cleanup);
+        }
+    }
+
+    /// Removes up to `limit` entries and returns how many were removed. The
+    /// freed storage goes back to whoever paid for each entry: the history
+    /// owner or the ticket holder.
+    fn internal_run_cleanup(
+        &mut self,
+        target: &CleanupTarget,
+        cleanup: &mut PendingCleanup,
+        limit: u64,
+    ) -> u64 {
+        let removed = std::cmp::min(limit, cleanup.remaining());
+        let mut freed_bytes: BTreeMap<AccountId, StorageUsage> = BTreeMap::new();
+
+        for _ in 0..removed {
+            let initial_storage_usage = env::storage_usage();
+            let payer = match (&mut *cleanup, target) {
+                (PendingCleanup::History(entries), CleanupTarget::History { account_id }) => {
+                    entries.pop();
+                    account_id.clone()
+                }
+                (PendingCleanup::RewardTickets(tickets), _) => tickets.pop().unwrap().1,
+                _ => unreachable!(),
+            };
+            *freed_bytes.entry(payer).or_default() += initial_storage_usage - env::storage_usage();
+        }
+
+        for (account_id, bytes) in freed_bytes {
+            self.internal_release_storage(Some(&account_id), bytes);
+        }
+
+        removed
+    }
+
+    pub(crate) fn assert_no_pending_cleanup(&self, target: &CleanupTarget) {
+        assert!(
+            self.pending_cleanups.get(target).is_none(),
+            "Previous data is still being cleaned up, call process_cleanups first"
+        );
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Drops a finalized or cancelled reward, or one without tickets,
+    /// together with its tickets and prices.
+    #[payable]
+    pub fn remove_reward(&mut self, reward_id: U64) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        let initial_storage_usage = env::storage_usage();
+        let mut reward = self
+            .rewards
+            .remove(&reward_id.0)
+            .expect(ERR_REWARD_NOT_FOUND);
+        let tickets = self.reward_tickets.remove(&reward_id.0).unwrap();
+        assert!(
+            reward.status.is_closed()
+                || (reward.status == RewardStatus::Active && tickets.total == 0),
+            "Reward has not been finalized"
+        );
+
+        reward.title.remove();
+        self.reward_near_prices.remove(&reward_id.0);
+        self.reward_usd_prices.remove(&reward_id.0);
+        self.reward_items.remove(&reward_id.0);
+        // Created out of the owner's deposit, so the freed part goes to the
+        // treasury
+        self.internal_release_storage(None, initial_storage_usage - env::storage_usage());
+
+        self.internal_schedule_cleanup(
+            CleanupTarget::RewardTickets { reward_id },
+            PendingCleanup::RewardTickets(tickets.purchases),
+        );
+
+        ArkanaEvent::admin_action(
+            "remove_reward",
+            serde_json::json!({ "reward_id": reward_id }),
+        );
+    }
+
+    /// Removes up to `limit` entries of dropped collections. Callable by
+    /// anyone. Returns how many entries were removed.
+    pub fn process_cleanups(&mut self, limit: Option<U64>) -> U64 {
+        let limit = limit.map(|v| v.0).unwrap_or(MAX_CLEANUP_BATCH);
+        assert!(
+            limit <= MAX_CLEANUP_BATCH,
+            "Limit cannot exceed {}",
+            MAX_CLEANUP_BATCH
+        );
+
+        let mut removed = 0;
+        while removed < limit {
+            let target = match self.pending_cleanups.keys().next() {
+                Some(target) => target,
+                None => break,
+            };
+            let mut cleanup = self.pending_cleanups.get(&target).unwrap();
+
+            removed += self.internal_run_cleanup(&target, &mut cleanup, limit - removed);
+
+            if cleanup.remaining() == 0 {
+                self.pending_cleanups.remove(&target);
+            } else {
+                self.pending_cleanups.insert(&target, &cleanup);
+            }
+        }
+
+        U64(removed)
+    }
+
+    // View Functions
+    pub fn get_pending_cleanups(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<PendingCleanupOutput> {
+        paginate(self.pending_cleanups.iter(), from_index, limit).map(|(target, cleanup)| {
+            PendingCleanupOutput {
+                target,
+                remaining: U64(cleanup.remaining()),
+            }
+        })
+    }
+}
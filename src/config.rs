@@ -0,0 +1,269 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type ConfigChangeId = u64;
+
+pub const MAX_CONFIG_TIMELOCK: u64 = 30 * ONE_DAY;
+pub const MIN_ACTION_COOLDOWN: u64 = ONE_DAY / 24;
+pub const MAX_ACTION_COOLDOWN: u64 = 7 * ONE_DAY;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigChange {
+    SetWheelSegments { segments: Vec<WheelSegment> },
+    SetDailyClaimPoints { points: U128 },
+    SetSpinWheelPrice { price: U128 },
+    SetConfigTimelock { delay_ms: U64 },
+    SetRegistrationFee { fee: U128 },
+    SetSpinWheelPriceNear { price: U128 },
+    SetDailyClaimCooldown { cooldown_ms: U64 },
+    SetFreeSpinCooldown { cooldown_ms: U64 },
+    SetClaimBooster { booster: ClaimBooster },
+    SetRequireDirectSigner { required: bool },
+    // In USD cents, zero falls back to `spin_wheel_price_near`
+    SetSpinWheelPriceUsd { price_cents: U128 },
+    // Zero turns the repeat-winner rule off
+    SetRepeatWinnerCooldown { cooldown_ms: U64 },
+}
+
+impl ConfigChange {
+    /// Odds changes additionally need guardian confirmations.
+    pub(crate) fn is_guarded(&self) -> bool {
+        matches!(self, ConfigChange::SetWheelSegments { .. })
+    }
+
+    pub(crate) fn validate(&self) {
+        match self {
+            ConfigChange::SetWheelSegments { segments } => assert_valid_wheel_segments(segments),
+            ConfigChange::SetConfigTimelock { delay_ms } => assert!(
+                delay_ms.0 <= MAX_CONFIG_TIMELOCK,
+                "Timelock cannot exceed {} ms",
+                MAX_CONFIG_TIMELOCK
+            ),
+            ConfigChange::SetDailyClaimCooldown { cooldown_ms }
+            | ConfigChange::SetFreeSpinCooldown { cooldown_ms } => assert!(
+                (MIN_ACTION_COOLDOWN..=MAX_ACTION_COOLDOWN).contains(&cooldown_ms.0),
+                "Cooldown must be between {} and {} ms",
+                MIN_ACTION_COOLDOWN,
+                MAX_ACTION_COOLDOWN
+            ),
+            ConfigChange::SetRepeatWinnerCooldown { cooldown_ms } => assert!(
+                cooldown_ms.0 <= MAX_REPEAT_WINNER_COOLDOWN,
+                "Repeat winner cooldown cannot exceed {} ms",
+                MAX_REPEAT_WINNER_COOLDOWN
+            ),
+            ConfigChange::SetClaimBooster { booster } => assert!(
+                booster.price.0 == 0 || booster.duration_ms.0 > 0,
+                "Booster duration must be greater than zero"
+            ),
+            ConfigChange::SetDailyClaimPoints { .. }
+            | ConfigChange::SetSpinWheelPrice { .. }
+            | ConfigChange::SetRegistrationFee { .. }
+            | ConfigChange::SetSpinWheelPriceNear { .. }
+            | ConfigChange::SetRequireDirectSigner { .. }
+            | ConfigChange::SetSpinWheelPriceUsd { .. } => {}
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingConfigChange {
+    change: ConfigChange,
+    queued_at: Timestamp,
+    executable_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct PendingConfigChangeOutput {
+    change_id: U64,
+    change: ConfigChange,
+    queued_at: U64,
+    executable_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct ConfigOutput {
+    owner: AccountId,
+    daily_claim_points: U128,
+    spin_wheel_price: U128,
+    wheel_segments: Vec<WheelSegment>,
+    config_timelock_ms: U64,
+    daily_claim_cooldown_ms: U64,
+    free_spin_cooldown_ms: U64,
+    require_direct_signer: bool,
+    event_only_history: bool,
+    repeat_winner_cooldown_ms: U64,
+}
+
+impl ArkanaCoreContract {
+    fn internal_new_config_change(
+        &mut self,
+        change: ConfigChange,
+        executable_at: Timestamp,
+    ) -> (ConfigChangeId, PendingConfigChange) {
+        change.validate();
+
+        let change_id = self.last_config_change_id + 1;
+        self.last_config_change_id = change_id;
+
+        ArkanaEvent::ConfigChangeQueued(vec![ConfigChangeQueuedData {
+            change_id: U64(change_id),
+            change: change.clone(),
+            executable_at: U64(executable_at),
+        }])
+        .emit();
+
+        let pending = PendingConfigChange {
+            change,
+            queued_at: env::block_timestamp_ms(),
+            executable_at,
+        };
+        (change_id, pending)
+    }
+
+    pub(crate) fn internal_queue_config_change(&mut self, change: ConfigChange) -> ConfigChangeId {
+        let executable_at = env::block_timestamp_ms().safe_add(self.config_timelock_ms);
+        let (change_id, pending) = self.internal_new_config_change(change, executable_at);
+
+        if self.config_timelock_ms == 0 {
+            self.internal_apply_config_change(change_id, pending.change);
+        } else {
+            self.pending_config_changes.insert(&change_id, &pending);
+        }
+
+        change_id
+    }
+
+    /// Queues a change that only becomes executable at `executable_at`, which
+    /// has to respect the timelock. Used by scheduled tasks.
+    pub(crate) fn internal_queue_config_change_at(
+        &mut self,
+        change: ConfigChange,
+        executable_at: Timestamp,
+    ) -> ConfigChangeId {
+        assert!(
+            executable_at >= env::block_timestamp_ms().safe_add(self.config_timelock_ms),
+            "Config changes cannot be scheduled before the timelock"
+        );
+
+        let (change_id, pending) = self.internal_new_config_change(change, executable_at);
+        self.pending_config_changes.insert(&change_id, &pending);
+
+        change_id
+    }
+
+    fn internal_apply_config_change(&mut self, change_id: ConfigChangeId, change: ConfigChange) {
+        match change {
+            ConfigChange::SetWheelSegments { segments } => {
+                self.internal_set_wheel_segments(segments)
+            }
+            ConfigChange::SetDailyClaimPoints { points } => self.daily_claim_points = points.0,
+            ConfigChange::SetSpinWheelPrice { price } => self.spin_wheel_price = price.0,
+            ConfigChange::SetConfigTimelock { delay_ms } => self.config_timelock_ms = delay_ms.0,
+            ConfigChange::SetRegistrationFee { fee } => self.registration_fee = fee.0,
+            ConfigChange::SetSpinWheelPriceNear { price } => self.spin_wheel_price_near = price.0,
+            ConfigChange::SetDailyClaimCooldown { cooldown_ms } => {
+                self.daily_claim_cooldown_ms = cooldown_ms.0
+            }
+            ConfigChange::SetFreeSpinCooldown { cooldown_ms } => {
+                self.free_spin_cooldown_ms = cooldown_ms.0
+            }
+            ConfigChange::SetClaimBooster { booster } => self.claim_booster = booster,
+            ConfigChange::SetRequireDirectSigner { required } => {
+                self.require_direct_signer = required
+            }
+            ConfigChange::SetSpinWheelPriceUsd { price_cents } => {
+                self.spin_wheel_price_usd = price_cents.0
+            }
+            ConfigChange::SetRepeatWinnerCooldown { cooldown_ms } => {
+                self.repeat_winner_cooldown_ms = cooldown_ms.0
+            }
+        }
+
+        ArkanaEvent::ConfigChangeApplied(vec![ConfigChangeAppliedData {
+            change_id: U64(change_id),
+        }])
+        .emit();
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Queues a configuration change that can be applied once the timelock
+    /// has passed. Wheel changes go through guardian proposals when guardians
+    /// are set up.
+    #[payable]
+    pub fn queue_config_change(&mut self, change: ConfigChange) -> U64 {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(
+            !change.is_guarded() || self.guardians.is_empty(),
+            "Requires guardian approval"
+        );
+
+        U64(self.internal_queue_config_change(change))
+    }
+
+    /// Applies a queued change whose timelock has passed. Callable by anyone.
+    pub fn execute_config_change(&mut self, change_id: U64) {
+        let pending = self
+            .pending_config_changes
+            .get(&change_id.0)
+            .expect("Config change does not exist");
+
+        assert!(
+            env::block_timestamp_ms() >= pending.executable_at,
+            "Config change is still timelocked"
+        );
+
+        self.pending_config_changes.remove(&change_id.0);
+        self.internal_apply_config_change(change_id.0, pending.change);
+    }
+
+    pub fn cancel_config_change(&mut self, change_id: U64) {
+        self.assert_owner();
+
+        self.pending_config_changes
+            .remove(&change_id.0)
+            .expect("Config change does not exist");
+
+        ArkanaEvent::ConfigChangeCancelled(vec![ConfigChangeAppliedData { change_id }]).emit();
+    }
+
+    // View Functions
+    pub fn get_config(&self) -> ConfigOutput {
+        ConfigOutput {
+            owner: self.owner.clone(),
+            daily_claim_points: U128(self.daily_claim_points),
+            spin_wheel_price: U128(self.spin_wheel_price),
+            wheel_segments: self.wheel_segments.clone(),
+            config_timelock_ms: U64(self.config_timelock_ms),
+            daily_claim_cooldown_ms: U64(self.daily_claim_cooldown_ms),
+            free_spin_cooldown_ms: U64(self.free_spin_cooldown_ms),
+            require_direct_signer: self.require_direct_signer,
+            event_only_history: self.event_only_history,
+            repeat_winner_cooldown_ms: U64(self.repeat_winner_cooldown_ms),
+        }
+    }
+
+    pub fn get_pending_config_changes(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<PendingConfigChangeOutput> {
+        paginate(self.pending_config_changes.iter(), from_index, limit).map(
+            |(change_id, pending)| PendingConfigChangeOutput {
+                change_id: U64(change_id),
+                change: pending.change,
+                queued_at: U64(pending.queued_at),
+                executable_at: U64(pending.executable_at),
+            },
+        )
+    }
+}
@@ -0,0 +1,183 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::{env, near_bindgen, AccountId, CryptoHash};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const MAX_COUPONS_PER_CALL: usize = 100;
+pub const MAX_COUPON_CODE_LEN: usize = 64;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CouponReward {
+    Points { amount: U128 },
+    Item { item_id: ItemId, amount: U64 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CouponInput {
+    // sha256 of the redemption code
+    pub code_hash: Base64VecU8,
+    pub reward: CouponReward,
+    pub expires_at: Option<U64>,
+}
+
+/// Redeemed coupons are kept so a code cannot be uploaded and used again.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Coupon {
+    reward: CouponReward,
+    expires_at: Option<Timestamp>,
+    redeemed_by: Option<AccountId>,
+    redeemed_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct CouponOutput {
+    reward: CouponReward,
+    expires_at: Option<U64>,
+    redeemed_by: Option<AccountId>,
+    redeemed_at: Option<U64>,
+}
+
+fn coupon_hash(code_hash: &Base64VecU8) -> CryptoHash {
+    code_hash
+        .0
+        .as_slice()
+        .try_into()
+        .expect("Code hash must be 32 bytes")
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Uploads coupons by the sha256 of their codes. The attached deposit
+    /// covers their storage, the rest is refunded.
+    #[payable]
+    pub fn add_coupons(&mut self, coupons: Vec<CouponInput>) {
+        self.assert_owner_or_operator();
+
+        assert!(
+            !coupons.is_empty() && coupons.len() <= MAX_COUPONS_PER_CALL,
+            "Must add between 1 and {} coupons",
+            MAX_COUPONS_PER_CALL
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        for coupon in coupons.iter() {
+            if let CouponReward::Item { item_id, .. } = &coupon.reward {
+                self.assert_item_exists(item_id);
+            }
+
+            let code_hash = coupon_hash(&coupon.code_hash);
+            assert!(
+                self.coupons.get(&code_hash).is_none(),
+                "Coupon already exists"
+            );
+
+            self.coupons.insert(
+                &code_hash,
+                &Coupon {
+                    reward: coupon.reward.clone(),
+                    expires_at: coupon.expires_at.map(|e| e.0),
+                    redeemed_by: None,
+                    redeemed_at: 0,
+                },
+            );
+        }
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action("add_coupons", serde_json::json!({ "count": coupons.len() }));
+    }
+
+    /// Removes an unredeemed coupon, e.g. for a lost batch of codes.
+    pub fn remove_coupon(&mut self, code_hash: Base64VecU8) {
+        self.assert_owner_or_operator();
+
+        let key = coupon_hash(&code_hash);
+        let coupon = self.coupons.get(&key).expect("Coupon does not exist");
+        assert!(coupon.redeemed_by.is_none(), "Coupon already redeemed");
+
+        self.coupons.remove(&key);
+
+        ArkanaEvent::admin_action(
+            "remove_coupon",
+            serde_json::json!({ "code_hash": code_hash }),
+        );
+    }
+
+    /// Redeems a coupon by its code. The code is public once submitted, so
+    /// whoever submits it first gets the reward.
+    pub fn redeem_coupon(&mut self, code: String) -> CouponReward {
+        self.assert_not_paused(PauseFeature::Claims);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        assert!(
+            code.len() <= MAX_COUPON_CODE_LEN,
+            "Code cannot exceed {} bytes",
+            MAX_COUPON_CODE_LEN
+        );
+
+        let code_hash = env::sha256_array(code.as_bytes());
+        let mut coupon = self.coupons.get(&code_hash).expect("Invalid coupon code");
+        assert!(coupon.redeemed_by.is_none(), "Coupon already redeemed");
+        assert!(
+            coupon
+                .expires_at
+                .map(|expires_at| env::block_timestamp_ms() < expires_at)
+                .unwrap_or(true),
+            "Coupon has expired"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        match &coupon.reward {
+            CouponReward::Points { amount } => {
+                self.internal_issue_points(&account_id, &mut user, amount.0);
+                self.internal_record_history(
+                    &account_id,
+                    HistoryKind::Coupon,
+                    amount.0 as i128,
+                    None,
+                );
+            }
+            CouponReward::Item { item_id, amount } => {
+                self.assert_item_exists(item_id);
+                self.internal_grant_item(&account_id, item_id, amount.0);
+            }
+        }
+
+        coupon.redeemed_by = Some(account_id.clone());
+        coupon.redeemed_at = env::block_timestamp_ms();
+        self.coupons.insert(&code_hash, &coupon);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::CouponRedeemed(vec![CouponRedeemedData {
+            account_id,
+            code_hash: Base64VecU8(code_hash.to_vec()),
+            reward: coupon.reward.clone(),
+        }])
+        .emit();
+
+        coupon.reward
+    }
+
+    // View Functions
+    pub fn get_coupon(&self, code_hash: Base64VecU8) -> Option<CouponOutput> {
+        self.coupons
+            .get(&coupon_hash(&code_hash))
+            .map(|coupon| CouponOutput {
+                reward: coupon.reward,
+                expires_at: coupon.expires_at.map(U64),
+                redeemed_at: coupon.redeemed_by.as_ref().map(|_| U64(coupon.redeemed_at)),
+                redeemed_by: coupon.redeemed_by,
+            })
+    }
+}
@@ -0,0 +1,132 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type RecipeId = String;
+
+pub const MAX_RECIPE_ID_LEN: usize = 32;
+pub const MAX_RECIPE_INPUTS: usize = 8;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CraftResult {
+    Item { item_id: ItemId, amount: U64 },
+    // Applied right away, like an odds booster item
+    OddsBoost { boost: u8 },
+}
+
+/// Items (and points, burned) consumed together to produce `result`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct Recipe {
+    pub inputs: Vec<ItemGrant>,
+    pub points: U128,
+    pub result: CraftResult,
+}
+
+#[derive(Serialize)]
+pub struct RecipeOutput {
+    recipe_id: RecipeId,
+    #[serde(flatten)]
+    recipe: Recipe,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Defines, updates or (with `None`) removes a recipe.
+    #[payable]
+    pub fn set_recipe(&mut self, recipe_id: RecipeId, recipe: Option<Recipe>) {
+        self.assert_owner();
+
+        assert!(
+            !recipe_id.is_empty() && recipe_id.len() <= MAX_RECIPE_ID_LEN,
+            "Recipe id must be between 1 and {} bytes",
+            MAX_RECIPE_ID_LEN
+        );
+
+        if let Some(recipe) = &recipe {
+            assert!(
+                !recipe.inputs.is_empty() && recipe.inputs.len() <= MAX_RECIPE_INPUTS,
+                "Recipe must have between 1 and {} inputs",
+                MAX_RECIPE_INPUTS
+            );
+            for input in recipe.inputs.iter() {
+                assert!(input.amount.0 > 0, "Amount must be greater than zero");
+            }
+            if let CraftResult::Item { item_id, amount } = &recipe.result {
+                self.assert_item_exists(item_id);
+                assert!(amount.0 > 0, "Amount must be greater than zero");
+            }
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        match &recipe {
+            Some(recipe) => {
+                self.recipes.insert(&recipe_id, recipe);
+            }
+            None => {
+                self.recipes.remove(&recipe_id);
+            }
+        }
+
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        ArkanaEvent::admin_action(
+            "set_recipe",
+            serde_json::json!({ "recipe_id": recipe_id, "recipe": recipe }),
+        );
+    }
+
+    /// Consumes the recipe's inputs from the caller's inventory and hands out
+    /// its result, all or nothing.
+    pub fn craft(&mut self, recipe_id: RecipeId) -> CraftResult {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let recipe = self.recipes.get(&recipe_id).expect("Recipe does not exist");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        for input in recipe.inputs.iter() {
+            self.internal_take_item(&account_id, &input.item_id, input.amount.0);
+        }
+        self.internal_burn_points(&mut user, recipe.points.0);
+
+        match &recipe.result {
+            CraftResult::Item { item_id, amount } => {
+                self.assert_item_exists(item_id);
+                self.internal_grant_item(&account_id, item_id, amount.0);
+            }
+            CraftResult::OddsBoost { boost } => {
+                user.odds_boost = user.odds_boost.saturating_add(*boost);
+            }
+        }
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::ItemCrafted(vec![ItemCraftedData {
+            account_id,
+            recipe_id,
+            result: recipe.result.clone(),
+        }])
+        .emit();
+
+        recipe.result
+    }
+
+    // View Functions
+    pub fn get_recipes(&self) -> Vec<RecipeOutput> {
+        self.recipes
+            .iter()
+            .map(|(recipe_id, recipe)| RecipeOutput { recipe_id, recipe })
+            .collect()
+    }
+}
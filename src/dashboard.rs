@@ -0,0 +1,110 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub const MAX_FEATURED_REWARDS: usize = 5;
+
+#[derive(Serialize)]
+pub struct DashboardRewardOutput {
+    reward_id: U64,
+    title: String,
+    price: U128,
+    ended_at: U64,
+    total_tickets: U64,
+    user_tickets: U64,
+}
+
+#[derive(Serialize)]
+pub struct DashboardOutput {
+    user: Option<UserOutput>,
+    can_claim: bool,
+    next_claim_at: U64,
+    can_spin_free: bool,
+    next_free_spin_at: U64,
+    rewards: Vec<DashboardRewardOutput>,
+    jackpot: U128,
+}
+
+impl RewardTickets {
+    /// Tickets held by `account_id`, walks every purchase.
+    pub(crate) fn tickets_of(&self, account_id: &AccountId) -> u64 {
+        let mut start = 0;
+
+        self.purchases
+            .iter()
+            .map(|(end, holder)| {
+                let amount = end - start;
+                start = end;
+                (amount, holder)
+            })
+            .filter(|(_, holder)| holder == account_id)
+            .map(|(amount, _)| amount)
+            .sum()
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+
+    /// Everything the app's home screen needs: the user, whether they can
+    /// claim or spin for free, and the newest open rewards with their tickets.
+    pub fn get_dashboard(&self, account_id: AccountId) -> DashboardOutput {
+        let current_timestamp = env::block_timestamp_ms();
+        let user = self.internal_find_user(&account_id);
+
+        let (next_claim_at, next_free_spin_at, has_extra_free_spin) = match &user {
+            Some(user) => {
+                let extra_free_spins = self.internal_nft_boost(&account_id).extra_free_spins;
+                (
+                    user.activity
+                        .last_daily_claim()
+                        .saturating_add(self.daily_claim_cooldown_ms),
+                    user.activity
+                        .last_free_spin()
+                        .saturating_add(self.free_spin_cooldown_ms),
+                    user.activity.free_spins_used() > 0
+                        && user.activity.free_spins_used() <= extra_free_spins,
+                )
+            }
+            None => (0, 0, false),
+        };
+
+        let mut open_rewards: Vec<(RewardId, Reward)> = self
+            .rewards
+            .iter()
+            .filter(|(_, reward)| reward.is_open(current_timestamp))
+            .collect();
+        open_rewards.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let rewards = open_rewards
+            .into_iter()
+            .take(MAX_FEATURED_REWARDS)
+            .map(|(reward_id, reward)| {
+                let tickets = self.reward_tickets.get(&reward_id).unwrap();
+
+                DashboardRewardOutput {
+                    reward_id: U64(reward_id),
+                    user_tickets: U64(tickets.tickets_of(&account_id)),
+                    title: reward.title(),
+                    price: U128(reward.price),
+                    ended_at: U64(reward.ended_at),
+                    total_tickets: U64(tickets.total),
+                }
+            })
+            .collect();
+
+        DashboardOutput {
+            can_claim: user.is_some() && current_timestamp >= next_claim_at,
+            can_spin_free: user.is_some()
+                && (current_timestamp >= next_free_spin_at || has_extra_free_spin),
+            next_claim_at: U64(next_claim_at),
+            next_free_spin_at: U64(next_free_spin_at),
+            user: user.map(|_| self.get_user(account_id)),
+            rewards,
+            jackpot: U128(self.jackpot_pool),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub type DutchSaleId = u64;
+
+/// A prize whose price falls linearly from `start_price` at `starts_at` to
+/// `floor_price` at `floor_at` and stays there until someone buys it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DutchSale {
+    prize: SalePrize,
+    start_price: Points,
+    floor_price: Points,
+    starts_at: Timestamp,
+    floor_at: Timestamp,
+    buyer: Option<(AccountId, Points)>,
+}
+
+impl DutchSale {
+    fn price_at(&self, timestamp: Timestamp) -> Points {
+        if timestamp >= self.floor_at {
+            return self.floor_price;
+        }
+
+        let elapsed = timestamp.saturating_sub(self.starts_at) as u128;
+        let duration = (self.floor_at - self.starts_at) as u128;
+        let drop = (self.start_price - self.floor_price).safe_mul(elapsed) / duration;
+
+        self.start_price - drop
+    }
+}
+
+#[derive(Serialize)]
+pub struct DutchSaleOutput {
+    sale_id: U64,
+    prize: SalePrize,
+    start_price: U128,
+    floor_price: U128,
+    starts_at: U64,
+    floor_at: U64,
+    current_price: U128,
+    buyer: Option<AccountId>,
+    sold_for: Option<U128>,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the sale's storage, the rest is refunded.
+    #[payable]
+    pub fn create_dutch_sale(
+        &mut self,
+        prize: SalePrize,
+        start_price: U128,
+        floor_price: U128,
+        starts_at: U64,
+        floor_at: U64,
+    ) -> U64 {
+        self.assert_owner();
+        self.assert_valid_sale_prize(&prize);
+
+        assert!(
+            floor_price.0 > 0 && floor_price.0 <= start_price.0,
+            "Floor price must be between 1 and the start price"
+        );
+        assert!(
+            starts_at.0 < floor_at.0,
+            "Price must reach the floor after the sale starts"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let sale_id = self.last_dutch_sale_id + 1;
+        self.last_dutch_sale_id = sale_id;
+
+        self.dutch_sales.insert(
+            &sale_id,
+            &DutchSale {
+                prize: prize.clone(),
+                start_price: start_price.0,
+                floor_price: floor_price.0,
+                starts_at: starts_at.0,
+                floor_at: floor_at.0,
+                buyer: None,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_dutch_sale",
+            serde_json::json!({
+                "sale_id": U64(sale_id),
+                "prize": prize,
+                "start_price": start_price,
+                "floor_price": floor_price,
+                "starts_at": starts_at,
+                "floor_at": floor_at,
+            }),
+        );
+
+        U64(sale_id)
+    }
+
+    /// Removes an unsold sale.
+    pub fn cancel_dutch_sale(&mut self, sale_id: U64) {
+        self.assert_owner();
+
+        let sale = self
+            .dutch_sales
+            .get(&sale_id.0)
+            .expect("Dutch sale does not exist");
+        assert!(sale.buyer.is_none(), "Dutch sale already sold");
+
+        self.dutch_sales.remove(&sale_id.0);
+
+        ArkanaEvent::admin_action(
+            "cancel_dutch_sale",
+            serde_json::json!({ "sale_id": sale_id }),
+        );
+    }
+
+    /// Buys the prize at the current price, as long as it does not exceed
+    /// `max_price`. Returns the price paid.
+    pub fn buy_dutch_sale(&mut self, sale_id: U64, max_price: U128) -> U128 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut sale = self
+            .dutch_sales
+            .get(&sale_id.0)
+            .expect("Dutch sale does not exist");
+        assert!(sale.buyer.is_none(), "Dutch sale already sold");
+
+        let current_timestamp = env::block_timestamp_ms();
+        assert!(
+            current_timestamp >= sale.starts_at,
+            "Dutch sale has not started"
+        );
+
+        let price = sale.price_at(current_timestamp);
+        assert!(price <= max_price.0, "Price is above the maximum");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        user.points = user.points.checked_sub(price).expect("Points insufficient");
+        self.internal_save_user(&account_id, &user);
+
+        sale.buyer = Some((account_id.clone(), price));
+        self.dutch_sales.insert(&sale_id.0, &sale);
+        self.internal_deliver_sale_prize(&account_id, &sale.prize, price);
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::DutchSaleBought(vec![DutchSaleBoughtData {
+            sale_id,
+            account_id,
+            price: U128(price),
+        }])
+        .emit();
+
+        U128(price)
+    }
+
+    // View Functions
+    pub fn get_dutch_sales(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<DutchSaleOutput> {
+        let current_timestamp = env::block_timestamp_ms();
+
+        paginate(self.dutch_sales.iter(), from_index, limit).map(|(sale_id, sale)| {
+            DutchSaleOutput {
+                sale_id: U64(sale_id),
+                current_price: U128(sale.price_at(current_timestamp)),
+                start_price: U128(sale.start_price),
+                floor_price: U128(sale.floor_price),
+                starts_at: U64(sale.starts_at),
+                floor_at: U64(sale.floor_at),
+                sold_for: sale.buyer.as_ref().map(|(_, price)| U128(*price)),
+                buyer: sale.buyer.map(|(buyer, _)| buyer),
+                prize: sale.prize,
+            }
+        })
+    }
+}
@@ -0,0 +1,192 @@
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId, Balance, StorageUsage};
+use serde::Serialize;
+
+use crate::*;
+
+// Bytes NEAR charges for every stored record on top of its key and value
+pub const STORAGE_RECORD_OVERHEAD: StorageUsage = 40;
+
+/// Dry run of a user action, so wallets can warn before a transaction that
+/// would fail gets signed.
+#[derive(Serialize)]
+pub struct EstimateOutput {
+    would_succeed: bool,
+    // The first check the call would fail on
+    error: Option<String>,
+    required_points: U128,
+    available_points: U128,
+    // Storage the call adds, charged to the account's storage deposit
+    storage_bytes: U64,
+    storage_cost: U128,
+}
+
+impl EstimateOutput {
+    pub(crate) fn into_result(self) -> Result<(), String> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ArkanaCoreContract {
+    /// Runs the checks shared by claims, spins and ticket purchases, then
+    /// `check` for the action specific ones.
+    fn internal_estimate(
+        &self,
+        account_id: &AccountId,
+        feature: PauseFeature,
+        required_points: Points,
+        storage_bytes: StorageUsage,
+        check: impl FnOnce(&User) -> Result<(), String>,
+    ) -> EstimateOutput {
+        let user = self.internal_find_user(account_id);
+        let available_points = user
+            .as_ref()
+            .map(|user| self.internal_settled_points(user))
+            .unwrap_or(0);
+        let storage_cost = Balance::from(storage_bytes) * env::storage_byte_cost();
+
+        let result = (|| {
+            if self.pause_state.is_paused(feature) {
+                return Err("Paused".to_string());
+            }
+            if self.internal_is_blacklisted(account_id) {
+                return Err("Account is blacklisted".to_string());
+            }
+            let user = user
+                .as_ref()
+                .ok_or_else(|| "User does not exist".to_string())?;
+
+            if self.internal_is_rate_limited(user) {
+                return Err("Rate limit exceeded".to_string());
+            }
+
+            if available_points < required_points {
+                return Err("Points insufficient".to_string());
+            }
+
+            let storage_account = self.storage_accounts.get(account_id).unwrap_or_default();
+            if storage_account.available_balance() < storage_cost {
+                return Err("Insufficient storage balance, top up with storage_deposit".to_string());
+            }
+
+            check(user)
+        })();
+
+        EstimateOutput {
+            would_succeed: result.is_ok(),
+            error: result.err(),
+            required_points: U128(required_points),
+            available_points: U128(available_points),
+            storage_bytes: U64(storage_bytes),
+            storage_cost: U128(storage_cost),
+        }
+    }
+
+    fn internal_personhood_check(
+        &self,
+        account_id: &AccountId,
+        is_claim: bool,
+    ) -> Result<(), String> {
+        if self.internal_needs_personhood(account_id, is_claim) {
+            return Err("Proof of personhood required, call verify_personhood first".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+
+    /// Dry run of `buy_ticket`. The storage is the purchase entry added to
+    /// the reward's tickets.
+    pub fn estimate_buy_ticket(
+        &self,
+        account_id: AccountId,
+        reward_id: U64,
+        amount: U64,
+    ) -> EstimateOutput {
+        let reward = self.rewards.get(&reward_id.0);
+        let required_points = reward
+            .as_ref()
+            .and_then(|reward| reward.price.checked_mul(Points::from(amount.0)))
+            .unwrap_or(0);
+
+        let key_bytes = StorageKey::TicketRanges {
+            reward_id: reward_id.0,
+        }
+        .try_to_vec()
+        .unwrap()
+        .len() as StorageUsage
+            + 8;
+        let value_bytes = (0u64, account_id.clone()).try_to_vec().unwrap().len() as StorageUsage;
+
+        self.internal_estimate(
+            &account_id,
+            PauseFeature::Tickets,
+            required_points,
+            STORAGE_RECORD_OVERHEAD + key_bytes + value_bytes,
+            |_| match reward {
+                None => Err(ERR_REWARD_NOT_FOUND.to_string()),
+                Some(reward) if !reward.is_open(env::block_timestamp_ms()) => {
+                    Err(ERR_REWARD_ENDED.to_string())
+                }
+                Some(_) => validate_ticket_amount(amount.0),
+            },
+        )
+    }
+
+    /// Dry run of `play_spin_wheel`.
+    pub fn estimate_spin_wheel(&self, account_id: AccountId, is_free: bool) -> EstimateOutput {
+        let required_points = if is_free { 0 } else { self.spin_wheel_price };
+
+        self.internal_estimate(
+            &account_id,
+            PauseFeature::Spins,
+            required_points,
+            0,
+            |user| {
+                self.internal_spending_check(&account_id, required_points)?;
+                if !is_free {
+                    return Ok(());
+                }
+                self.internal_personhood_check(&account_id, false)?;
+
+                let delta_ms =
+                    env::block_timestamp_ms().saturating_sub(user.activity.last_free_spin());
+                let extra_free_spins = self.internal_nft_boost(&account_id).extra_free_spins;
+                if delta_ms < self.free_spin_cooldown_ms
+                    && (user.activity.free_spins_used() == 0
+                        || user.activity.free_spins_used() > extra_free_spins)
+                {
+                    return Err(format!(
+                        "Cannot play spin wheel for free, please wait {} seconds",
+                        milli_to_seconds(self.free_spin_cooldown_ms - delta_ms)
+                    ));
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Dry run of `daily_claim_point`.
+    pub fn estimate_daily_claim(&self, account_id: AccountId) -> EstimateOutput {
+        self.internal_estimate(&account_id, PauseFeature::Claims, 0, 0, |user| {
+            self.internal_personhood_check(&account_id, true)?;
+
+            let delta_ms =
+                env::block_timestamp_ms().saturating_sub(user.activity.last_daily_claim());
+            if delta_ms < self.daily_claim_cooldown_ms {
+                return Err(format!(
+                    "Cannot claim, please wait {} seconds",
+                    milli_to_seconds(self.daily_claim_cooldown_ms - delta_ms)
+                ));
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,690 @@
+use near_sdk::json_types::{Base64VecU8, I128, U128, U64};
+use near_sdk::{env, AccountId};
+use serde::Serialize;
+
+use crate::{
+    Achievement, CheckInBonus, ConfigChange, CouponReward, CraftResult, GuardedAction, HistoryKind,
+    LootboxPrize, MilestoneReward, PassReward, PauseFeature, ScheduledAction, SeasonId,
+    TournamentStatus, TreasurySource, WagerGame,
+};
+
+pub const EVENT_STANDARD: &str = "arkana";
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+// NEP-297 event log, emitted as `EVENT_JSON:{...}`
+#[derive(Serialize)]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a ArkanaEvent,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum ArkanaEvent {
+    PointsAdjusted(Vec<PointsAdjustedData>),
+    OperatorAdded(Vec<AccountData>),
+    OperatorRemoved(Vec<AccountData>),
+    SeasonStarted(Vec<SeasonStartedData>),
+    PointsStaked(Vec<PointsStakedData>),
+    PointsUnstaked(Vec<PointsUnstakedData>),
+    Paused(Vec<PauseData>),
+    Unpaused(Vec<PauseData>),
+    AccountBlacklisted(Vec<AccountBlacklistedData>),
+    AccountUnblacklisted(Vec<AccountData>),
+    ContractUpgraded(Vec<ContractUpgradedData>),
+    OwnershipTransferred(Vec<OwnershipTransferredData>),
+    GuardiansSetUp(Vec<GuardiansSetUpData>),
+    GuardedActionProposed(Vec<GuardedActionProposedData>),
+    GuardedActionConfirmed(Vec<GuardedActionConfirmedData>),
+    GuardedActionExecuted(Vec<GuardedActionExecutedData>),
+    ConfigChangeQueued(Vec<ConfigChangeQueuedData>),
+    ConfigChangeApplied(Vec<ConfigChangeAppliedData>),
+    ConfigChangeCancelled(Vec<ConfigChangeAppliedData>),
+    TreasuryDeposit(Vec<TreasuryDepositData>),
+    TreasuryWithdrawal(Vec<TreasuryWithdrawalData>),
+    AccountUnregistered(Vec<AccountUnregisteredData>),
+    AdminAction(Vec<AdminActionData>),
+    NftStaked(Vec<NftStakeData>),
+    NftUnstaked(Vec<NftStakeData>),
+    MembershipVerified(Vec<MembershipVerifiedData>),
+    AccountOnboarded(Vec<AccountOnboardedData>),
+    WalletLinked(Vec<WalletLinkData>),
+    WalletUnlinked(Vec<WalletLinkData>),
+    TaskScheduled(Vec<TaskScheduledData>),
+    TaskExecuted(Vec<TaskExecutedData>),
+    TaskCancelled(Vec<TaskExecutedData>),
+    LevelUp(Vec<LevelUpData>),
+    QuestCompleted(Vec<QuestCompletedData>),
+    AchievementUnlocked(Vec<AchievementUnlockedData>),
+    BadgeMinted(Vec<AchievementUnlockedData>),
+    BridgeOut(Vec<BridgeOutData>),
+    BridgeIn(Vec<BridgeInData>),
+    StakingPointsClaimed(Vec<StakingPointsClaimedData>),
+    RewardProposed(Vec<RewardProposedData>),
+    RewardProposalResolved(Vec<RewardProposalResolvedData>),
+    SeasonPrizePaid(Vec<SeasonPrizePaidData>),
+    GuildCreated(Vec<GuildCreatedData>),
+    GuildJoined(Vec<GuildMemberData>),
+    GuildLeft(Vec<GuildMemberData>),
+    GuildContribution(Vec<GuildContributionData>),
+    GuildTicketsBought(Vec<GuildTicketsBoughtData>),
+    CheckedIn(Vec<CheckedInData>),
+    LootboxOpened(Vec<LootboxOpenedData>),
+    ItemGranted(Vec<ItemGrantedData>),
+    ItemUsed(Vec<ItemGrantedData>),
+    CouponRedeemed(Vec<CouponRedeemedData>),
+    ScratchCardRevealed(Vec<ScratchCardRevealedData>),
+    WagerPlaced(Vec<WagerPlacedData>),
+    WagerSettled(Vec<WagerSettledData>),
+    PredictionPlaced(Vec<PredictionPlacedData>),
+    PredictionPoolResolved(Vec<PredictionPoolResolvedData>),
+    AuctionBid(Vec<AuctionBidData>),
+    AuctionFinalized(Vec<AuctionFinalizedData>),
+    DutchSaleBought(Vec<DutchSaleBoughtData>),
+    ItemListed(Vec<ItemListedData>),
+    ListingSold(Vec<ListingSoldData>),
+    ListingCancelled(Vec<ListingCancelledData>),
+    ItemCrafted(Vec<ItemCraftedData>),
+    TitleBought(Vec<TitleBoughtData>),
+    TitleEquipped(Vec<TitleEquippedData>),
+    PremiumPassUnlocked(Vec<PremiumPassUnlockedData>),
+    PassRewardClaimed(Vec<PassRewardClaimedData>),
+    TriviaGuessRevealed(Vec<TriviaGuessRevealedData>),
+    PollVoted(Vec<PollVotedData>),
+    PollFinalized(Vec<PollFinalizedData>),
+    ReferralRecorded(Vec<ReferralRecordedData>),
+    ReferralPrizePaid(Vec<ReferralPrizePaidData>),
+    LuckyDrawEntered(Vec<LuckyDrawEnteredData>),
+    MilestoneReached(Vec<MilestoneReachedData>),
+    JackpotWon(Vec<JackpotWonData>),
+    PersonhoodVerified(Vec<PersonhoodVerifiedData>),
+    TournamentEntered(Vec<TournamentEnteredData>),
+    TournamentAdvanced(Vec<TournamentAdvancedData>),
+    TournamentPrizePaid(Vec<TournamentPrizePaidData>),
+    PointsGifted(Vec<PointsGiftedData>),
+    CharityBurn(Vec<CharityBurnData>),
+    HistoryRecorded(Vec<HistoryRecordedData>),
+    RewardDrawn(Vec<RewardDrawnData>),
+    SpendingLimitsUpdated(Vec<SpendingLimitsUpdatedData>),
+}
+
+#[derive(Serialize)]
+pub struct PointsAdjustedData {
+    pub account_id: AccountId,
+    pub operator_id: AccountId,
+    pub delta: I128,
+    pub points: U128,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct SeasonStartedData {
+    pub season: SeasonId,
+    pub ended_season: SeasonId,
+    pub started_at: U64,
+    pub carry_over_bps: u16,
+}
+
+#[derive(Serialize)]
+pub struct PointsStakedData {
+    pub account_id: AccountId,
+    pub stake_id: U64,
+    pub amount: U128,
+    pub reward: U128,
+    pub duration_days: u32,
+}
+
+#[derive(Serialize)]
+pub struct PointsUnstakedData {
+    pub account_id: AccountId,
+    pub stake_id: U64,
+    pub amount: U128,
+    pub reward: U128,
+    pub penalty: U128,
+}
+
+#[derive(Serialize)]
+pub struct PauseData {
+    pub feature: PauseFeature,
+    pub account_id: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct AccountBlacklistedData {
+    pub account_id: AccountId,
+    pub operator_id: AccountId,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ContractUpgradedData {
+    pub code_hash: String,
+    pub account_id: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct OwnershipTransferredData {
+    pub old_owner: AccountId,
+    pub new_owner: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct GuardiansSetUpData {
+    pub guardians: Vec<AccountId>,
+    pub threshold: u8,
+}
+
+#[derive(Serialize)]
+pub struct GuardedActionProposedData {
+    pub proposal_id: U64,
+    pub proposer: AccountId,
+    pub action: GuardedAction,
+}
+
+#[derive(Serialize)]
+pub struct GuardedActionConfirmedData {
+    pub proposal_id: U64,
+    pub guardian_id: AccountId,
+    pub confirmations: u8,
+    pub threshold: u8,
+}
+
+#[derive(Serialize)]
+pub struct GuardedActionExecutedData {
+    pub proposal_id: U64,
+}
+
+#[derive(Serialize)]
+pub struct ConfigChangeQueuedData {
+    pub change_id: U64,
+    pub change: ConfigChange,
+    pub executable_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct ConfigChangeAppliedData {
+    pub change_id: U64,
+}
+
+#[derive(Serialize)]
+pub struct TreasuryDepositData {
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub source: TreasurySource,
+    pub protocol_fee: U128,
+}
+
+#[derive(Serialize)]
+pub struct TreasuryWithdrawalData {
+    pub receiver_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+pub struct AccountUnregisteredData {
+    pub account_id: AccountId,
+    pub burned_points: U128,
+    pub refund: U128,
+}
+
+#[derive(Serialize)]
+pub struct AdminActionData {
+    pub method: String,
+    pub actor_id: AccountId,
+    pub args: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct NftStakeData {
+    pub account_id: AccountId,
+    pub contract_id: AccountId,
+    pub token_id: String,
+}
+
+#[derive(Serialize)]
+pub struct MembershipVerifiedData {
+    pub account_id: AccountId,
+    pub collections: Vec<AccountId>,
+}
+
+#[derive(Serialize)]
+pub struct AccountOnboardedData {
+    pub account_id: AccountId,
+    pub onboarder_id: AccountId,
+    pub welcome_points: U128,
+}
+
+#[derive(Serialize)]
+pub struct WalletLinkData {
+    pub account_id: AccountId,
+    pub wallet_id: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct TaskScheduledData {
+    pub task_id: U64,
+    pub action: ScheduledAction,
+    pub execute_at: U64,
+    pub bounty: U128,
+}
+
+#[derive(Serialize)]
+pub struct TaskExecutedData {
+    pub task_id: U64,
+    pub account_id: AccountId,
+    pub bounty: U128,
+}
+
+#[derive(Serialize)]
+pub struct LevelUpData {
+    pub account_id: AccountId,
+    pub level: u32,
+}
+
+#[derive(Serialize)]
+pub struct QuestCompletedData {
+    pub account_id: AccountId,
+    pub quest_id: String,
+    pub points: U128,
+    pub proof: String,
+}
+
+#[derive(Serialize)]
+pub struct AchievementUnlockedData {
+    pub account_id: AccountId,
+    pub achievement: Achievement,
+}
+
+#[derive(Serialize)]
+pub struct BridgeOutData {
+    pub nonce: U64,
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub destination_chain: String,
+    pub destination_address: String,
+}
+
+#[derive(Serialize)]
+pub struct BridgeInData {
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub source_chain: String,
+    pub source_tx: String,
+    pub operator_id: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct StakingPointsClaimedData {
+    pub account_id: AccountId,
+    pub staked_balance: U128,
+    pub points: U128,
+}
+
+#[derive(Serialize)]
+pub struct RewardProposedData {
+    pub proposal_id: U64,
+    pub proposer: AccountId,
+    pub title: String,
+    pub price: U128,
+    pub ended_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct RewardProposalResolvedData {
+    pub proposal_id: U64,
+    pub reward_id: Option<U64>,
+}
+
+#[derive(Serialize)]
+pub struct SeasonPrizePaidData {
+    pub season: SeasonId,
+    pub rank: u32,
+    pub account_id: AccountId,
+    pub prize: U128,
+}
+
+#[derive(Serialize)]
+pub struct GuildCreatedData {
+    pub guild_id: U64,
+    pub leader: AccountId,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct GuildMemberData {
+    pub guild_id: U64,
+    pub account_id: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct GuildContributionData {
+    pub guild_id: U64,
+    pub account_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+pub struct GuildTicketsBoughtData {
+    pub guild_id: U64,
+    pub officer_id: AccountId,
+    pub reward_id: U64,
+    pub members: u32,
+    pub amount: U64,
+    pub cost: U128,
+}
+
+#[derive(Serialize)]
+pub struct CheckedInData {
+    pub account_id: AccountId,
+    pub streak: u8,
+    pub points: U128,
+    pub bonus: Option<CheckInBonus>,
+}
+
+#[derive(Serialize)]
+pub struct LootboxOpenedData {
+    pub account_id: AccountId,
+    pub lootbox_id: U64,
+    pub prize: LootboxPrize,
+}
+
+#[derive(Serialize)]
+pub struct ItemGrantedData {
+    pub account_id: AccountId,
+    pub item_id: String,
+    pub amount: U64,
+}
+
+#[derive(Serialize)]
+pub struct CouponRedeemedData {
+    pub account_id: AccountId,
+    pub code_hash: Base64VecU8,
+    pub reward: CouponReward,
+}
+
+#[derive(Serialize)]
+pub struct ScratchCardRevealedData {
+    pub account_id: AccountId,
+    pub batch_id: U64,
+    pub prize: U128,
+    pub remaining: u32,
+}
+
+#[derive(Serialize)]
+pub struct WagerPlacedData {
+    pub account_id: AccountId,
+    pub game: WagerGame,
+    pub stake: U128,
+    pub payout: U128,
+}
+
+#[derive(Serialize)]
+pub struct WagerSettledData {
+    pub account_id: AccountId,
+    pub game: WagerGame,
+    pub stake: U128,
+    pub payout: U128,
+}
+
+#[derive(Serialize)]
+pub struct PredictionPlacedData {
+    pub account_id: AccountId,
+    pub reward_id: U64,
+    pub outcome: u8,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+pub struct PredictionPoolResolvedData {
+    pub reward_id: U64,
+    pub outcome: u8,
+    pub total_tickets: U64,
+}
+
+#[derive(Serialize)]
+pub struct AuctionBidData {
+    pub auction_id: U64,
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub ends_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct AuctionFinalizedData {
+    pub auction_id: U64,
+    pub winner: AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+pub struct DutchSaleBoughtData {
+    pub sale_id: U64,
+    pub account_id: AccountId,
+    pub price: U128,
+}
+
+#[derive(Serialize)]
+pub struct ItemListedData {
+    pub listing_id: U64,
+    pub seller: AccountId,
+    pub item_id: String,
+    pub quantity: U64,
+    pub price_per_item: U128,
+}
+
+#[derive(Serialize)]
+pub struct ListingSoldData {
+    pub listing_id: U64,
+    pub buyer: AccountId,
+    pub seller: AccountId,
+    pub quantity: U64,
+    pub price: U128,
+    pub fee: U128,
+}
+
+#[derive(Serialize)]
+pub struct ListingCancelledData {
+    pub listing_id: U64,
+}
+
+#[derive(Serialize)]
+pub struct ItemCraftedData {
+    pub account_id: AccountId,
+    pub recipe_id: String,
+    pub result: CraftResult,
+}
+
+#[derive(Serialize)]
+pub struct TitleBoughtData {
+    pub account_id: AccountId,
+    pub title_id: String,
+    pub price: U128,
+}
+
+#[derive(Serialize)]
+pub struct TitleEquippedData {
+    pub account_id: AccountId,
+    pub title_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PremiumPassUnlockedData {
+    pub account_id: AccountId,
+    pub season: SeasonId,
+}
+
+#[derive(Serialize)]
+pub struct PassRewardClaimedData {
+    pub account_id: AccountId,
+    pub season: SeasonId,
+    pub level: u32,
+    pub premium: bool,
+    pub reward: PassReward,
+}
+
+#[derive(Serialize)]
+pub struct TriviaGuessRevealedData {
+    pub trivia_id: U64,
+    pub account_id: AccountId,
+    pub correct: bool,
+}
+
+#[derive(Serialize)]
+pub struct PollVotedData {
+    pub poll_id: U64,
+    pub account_id: AccountId,
+    pub candidate: u8,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+pub struct PollFinalizedData {
+    pub poll_id: U64,
+    pub candidate: Option<u8>,
+    pub reward_id: Option<U64>,
+}
+
+#[derive(Serialize)]
+pub struct ReferralRecordedData {
+    pub account_id: AccountId,
+    pub referrer_id: AccountId,
+}
+
+#[derive(Serialize)]
+pub struct ReferralPrizePaidData {
+    pub competition_id: U64,
+    pub rank: u32,
+    pub account_id: AccountId,
+    pub prize: U128,
+}
+
+#[derive(Serialize)]
+pub struct LuckyDrawEnteredData {
+    pub account_id: AccountId,
+    pub prize: U128,
+    pub pool: U128,
+}
+
+#[derive(Serialize)]
+pub struct MilestoneReachedData {
+    pub account_id: AccountId,
+    pub threshold: U64,
+    pub reward: MilestoneReward,
+}
+
+#[derive(Serialize)]
+pub struct JackpotWonData {
+    pub account_id: AccountId,
+    pub prize: U128,
+}
+
+#[derive(Serialize)]
+pub struct PersonhoodVerifiedData {
+    pub account_id: AccountId,
+    pub is_human: bool,
+}
+
+#[derive(Serialize)]
+pub struct TournamentEnteredData {
+    pub tournament_id: U64,
+    pub account_id: AccountId,
+    pub entry_fee: U128,
+}
+
+#[derive(Serialize)]
+pub struct TournamentAdvancedData {
+    pub tournament_id: U64,
+    pub status: TournamentStatus,
+    pub players: u32,
+}
+
+#[derive(Serialize)]
+pub struct TournamentPrizePaidData {
+    pub tournament_id: U64,
+    pub rank: u32,
+    pub account_id: AccountId,
+    pub prize: U128,
+}
+
+#[derive(Serialize)]
+pub struct PointsGiftedData {
+    pub account_id: AccountId,
+    pub receiver_id: AccountId,
+    pub amount: U128,
+    pub fee: U128,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CharityBurnData {
+    pub campaign_id: U64,
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub matched_tickets: U64,
+}
+
+// Only emitted while history is event-only, stored entries are queried
+// with `get_user_history` instead
+#[derive(Serialize)]
+pub struct HistoryRecordedData {
+    pub account_id: AccountId,
+    pub kind: HistoryKind,
+    pub delta: I128,
+    pub actor: AccountId,
+    pub memo: Option<String>,
+}
+
+// Everything `replay_draw` needs to recompute the drawn ticket
+#[derive(Serialize)]
+pub struct RewardDrawnData {
+    pub reward_id: U64,
+    pub random_seed: String,
+    pub caller: AccountId,
+    pub nonce: U64,
+    pub total_tickets: U64,
+    pub ticket: U64,
+    // Blacklisted or in the repeat-winner cooldown, in the order walked
+    pub skipped: Vec<AccountId>,
+    // None when every holder was skipped
+    pub winner: Option<AccountId>,
+}
+
+#[derive(Serialize)]
+pub struct SpendingLimitsUpdatedData {
+    pub account_id: AccountId,
+    pub excluded_until: U64,
+    pub daily_limit: U128,
+    pub limit_expires_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct AccountData {
+    pub account_id: AccountId,
+}
+
+impl ArkanaEvent {
+    /// Records a privileged call together with its arguments so the caller
+    /// (e.g. a DAO proposal) can verify the outcome from the receipt logs.
+    pub fn admin_action(method: &str, args: serde_json::Value) {
+        ArkanaEvent::AdminAction(vec![AdminActionData {
+            method: method.to_string(),
+            actor_id: env::predecessor_account_id(),
+            args,
+        }])
+        .emit();
+    }
+
+    pub fn emit(&self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+        env::log_str(&format!(
+            "EVENT_JSON:{}",
+            serde_json::to_string(&log).unwrap()
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+/// Every stored field of a user, for migrations and backups.
+#[derive(Serialize)]
+pub struct UserExport {
+    account_id: AccountId,
+    points: U128,
+    last_daily_claim: U64,
+    last_free_spinwheel: U64,
+    xp: U64,
+    season_xp: U64,
+    season: SeasonId,
+    window_started_at: U64,
+    window_actions: u32,
+    free_spins_used: u32,
+    claim_booster_expires_at: U64,
+    claim_streak: u32,
+    achievements: U64,
+    tickets_bought: U64,
+    check_in_day: u32,
+    check_in_streak: u8,
+    odds_boost: u8,
+    milestones: U64,
+    storage_deposit: U128,
+    storage_used_bytes: U64,
+}
+
+#[derive(Serialize)]
+pub struct RewardExport {
+    reward_id: U64,
+    title: String,
+    price: U128,
+    ended_at: U64,
+    total_tickets: U64,
+    winner: Option<AccountId>,
+    no_winner: bool,
+    status: RewardStatus,
+    ticket_ranges: U64,
+    near_price: Option<U128>,
+    usd_price: Option<U128>,
+}
+
+/// Tickets `first_ticket..` up to the next range belong to `account_id`.
+#[derive(Serialize)]
+pub struct TicketRangeExport {
+    first_ticket: U64,
+    account_id: AccountId,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Raw user state, unlike `get_user` nothing is settled or derived.
+    pub fn export_users(&self, from_index: Option<U64>, limit: Option<u64>) -> Page<UserExport> {
+        paginate(self.user_ids.as_vector().iter(), from_index, limit).map(|account_id| {
+            let user = User::from(self.users.get(&account_id).unwrap());
+            let storage_account = self.storage_accounts.get(&account_id).unwrap_or_default();
+
+            UserExport {
+                points: U128(user.points),
+                last_daily_claim: U64(user.activity.last_daily_claim()),
+                last_free_spinwheel: U64(user.activity.last_free_spin()),
+                xp: U64(user.xp),
+                season_xp: U64(user.season_xp),
+                season: user.season,
+                window_started_at: U64(user.activity.window_started_at()),
+                window_actions: user.activity.window_actions(),
+                free_spins_used: user.activity.free_spins_used(),
+                claim_booster_expires_at: U64(user.activity.booster_expires_at()),
+                claim_streak: user.activity.claim_streak(),
+                achievements: U64(user.achievements),
+                tickets_bought: U64(user.tickets_bought),
+                check_in_day: user.activity.check_in_day(),
+                check_in_streak: user.activity.check_in_streak(),
+                odds_boost: user.odds_boost,
+                milestones: U64(user.milestones),
+                storage_deposit: U128(storage_account.deposit),
+                storage_used_bytes: U64(storage_account.used_bytes),
+                account_id,
+            }
+        })
+    }
+
+    /// Rewards with their raw fields, tickets are exported separately with
+    /// `export_reward_tickets`.
+    pub fn export_rewards(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<RewardExport> {
+        paginate(self.rewards.iter(), from_index, limit).map(|(reward_id, reward)| {
+            let tickets = self.reward_tickets.get(&reward_id).unwrap();
+
+            RewardExport {
+                reward_id: U64(reward_id),
+                title: reward.title(),
+                price: U128(reward.price),
+                ended_at: U64(reward.ended_at),
+                total_tickets: U64(tickets.total),
+                no_winner: reward.has_no_winner(),
+                winner: reward.winner,
+                status: reward.status,
+                ticket_ranges: U64(tickets.purchases.len()),
+                near_price: self.reward_near_prices.get(&reward_id).map(U128),
+                usd_price: self.reward_usd_prices.get(&reward_id).map(U128),
+            }
+        })
+    }
+
+    pub fn export_reward_tickets(
+        &self,
+        reward_id: U64,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<TicketRangeExport> {
+        let tickets = self
+            .reward_tickets
+            .get(&reward_id.0)
+            .expect(ERR_REWARD_NOT_FOUND);
+        paginate(tickets.purchases.iter().enumerate(), from_index, limit).map(
+            |(index, (_, account_id))| TicketRangeExport {
+                first_ticket: U64(match index {
+                    0 => 0,
+                    _ => tickets.purchases.get(index as u64 - 1).unwrap().0,
+                }),
+                account_id,
+            },
+        )
+    }
+}
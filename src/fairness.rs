@@ -0,0 +1,22 @@
+use near_sdk::{env, AccountId};
+
+use crate::*;
+
+pub const MAX_REPEAT_WINNER_COOLDOWN: u64 = 365 * ONE_DAY;
+
+impl ArkanaCoreContract {
+    /// Whether `account_id` won a raffle within `repeat_winner_cooldown_ms`.
+    pub(crate) fn internal_in_win_cooldown(&self, account_id: &AccountId) -> bool {
+        self.repeat_winner_cooldown_ms > 0
+            && self.last_wins.get(account_id).is_some_and(|won_at| {
+                env::block_timestamp_ms() < won_at.saturating_add(self.repeat_winner_cooldown_ms)
+            })
+    }
+
+    /// Wins are recorded even while the rule is off, so enabling it applies
+    /// to recent winners right away. The entry is covered by the contract.
+    pub(crate) fn internal_record_win(&mut self, account_id: &AccountId) {
+        self.last_wins
+            .insert(account_id, &env::block_timestamp_ms());
+    }
+}
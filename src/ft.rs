@@ -0,0 +1,291 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, near_bindgen, AccountId, Balance, Gas, Promise,
+    PromiseOrValue,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
+
+#[allow(dead_code)]
+#[ext_contract(ext_ft)]
+trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+/// A NEP-141 token accepted as payment. `point_rate` is the amount of the
+/// token's smallest unit charged per point of the item's price.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AcceptedToken {
+    point_rate: Balance,
+    collected: Balance,
+}
+
+/// Buying a booster raises daily claims by `bonus_bps` for `duration_ms`.
+/// A zero price disables booster sales.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+pub struct ClaimBooster {
+    pub price: U128,
+    pub bonus_bps: u32,
+    pub duration_ms: U64,
+}
+
+/// `msg` of `ft_transfer_call`.
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FtTransferMessage {
+    Spin,
+    BuyClaimBooster,
+    // Buys as many of `amount` tickets as the transfer covers
+    BuyTicket { reward_id: RewardId, amount: u64 },
+}
+
+#[derive(Serialize)]
+pub struct AcceptedTokenOutput {
+    token_id: AccountId,
+    point_rate: U128,
+    collected: U128,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_claim_booster_bps(&self, user: &User) -> u32 {
+        if env::block_timestamp_ms() < user.activity.booster_expires_at() {
+            self.claim_booster.bonus_bps
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn internal_restore_token_balance(&mut self, token_id: &AccountId, amount: Balance) {
+        let mut token = self.accepted_tokens.get(token_id).unwrap_or(AcceptedToken {
+            point_rate: 0,
+            collected: 0,
+        });
+        token.collected += amount;
+        self.accepted_tokens.insert(token_id, &token);
+    }
+
+    fn internal_ft_spin(&mut self, account_id: &AccountId) -> Points {
+        self.assert_not_paused(PauseFeature::Spins);
+        self.assert_not_blacklisted(account_id);
+        self.assert_not_self_excluded(account_id);
+
+        let mut user = self.internal_get_user(account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        let segment = self.internal_spin_wheel(&mut user);
+        self.internal_award_spin(account_id, &mut user, &segment);
+        self.internal_save_user(account_id, &user);
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+        self.spin_wheel_price
+    }
+
+    pub(crate) fn internal_withdraw_token_treasury(
+        &mut self,
+        token_id: AccountId,
+        amount: Balance,
+        receiver_id: AccountId,
+    ) -> Promise {
+        let mut token = self
+            .accepted_tokens
+            .get(&token_id)
+            .expect("Token is not accepted");
+        assert!(amount > 0, "Amount must be greater than zero");
+        assert!(amount <= token.collected, "Token balance insufficient");
+
+        token.collected -= amount;
+        self.accepted_tokens.insert(&token_id, &token);
+
+        self.internal_track_operation(
+            ext_ft::ext(token_id.clone())
+                .with_attached_deposit(1)
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(receiver_id.clone(), U128(amount), None),
+            PendingOperation::TokenWithdrawal {
+                token_id,
+                receiver_id,
+                amount: U128(amount),
+            },
+        )
+    }
+
+    fn internal_ft_buy_claim_booster(&mut self, account_id: &AccountId) -> Points {
+        self.assert_not_paused(PauseFeature::Claims);
+
+        let booster = self.claim_booster;
+        assert!(booster.price.0 > 0, "Claim boosters are not for sale");
+
+        let mut user = self.internal_get_user(account_id);
+        self.internal_consume_rate_limit(&mut user);
+
+        // Buying again while active extends the booster
+        let starts_at = std::cmp::max(
+            env::block_timestamp_ms(),
+            user.activity.booster_expires_at(),
+        );
+        user.activity
+            .set_booster_expires_at(starts_at.safe_add(booster.duration_ms.0));
+        self.internal_save_user(account_id, &user);
+
+        booster.price.0
+    }
+
+    fn internal_ft_buy_ticket(
+        &mut self,
+        account_id: &AccountId,
+        reward_id: RewardId,
+        amount: u64,
+        budget: Points,
+    ) -> Points {
+        self.assert_not_paused(PauseFeature::Tickets);
+
+        assert_valid_ticket_amount(amount);
+        let reward = self.internal_get_open_reward(reward_id);
+
+        let amount = match budget.checked_div(reward.price) {
+            Some(affordable) => std::cmp::min(Points::from(amount), affordable) as u64,
+            None => amount,
+        };
+        assert!(amount > 0, "Transfer does not cover a single ticket");
+
+        let mut user = self.internal_get_user(account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_add_tickets(reward_id, account_id, amount);
+        self.internal_record_raffle_entries(account_id, &mut user, amount);
+        self.internal_save_user(account_id, &user);
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+        reward.price.safe_mul(Points::from(amount))
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Sets how much of `token_id` one point costs. `None` stops accepting
+    /// the token, collected funds stay withdrawable.
+    pub fn set_token_rate(&mut self, token_id: AccountId, point_rate: Option<U128>) {
+        self.assert_owner();
+
+        let collected = self
+            .accepted_tokens
+            .get(&token_id)
+            .map(|token| token.collected)
+            .unwrap_or(0);
+
+        match point_rate {
+            Some(rate) if rate.0 > 0 => {
+                self.accepted_tokens.insert(
+                    &token_id,
+                    &AcceptedToken {
+                        point_rate: rate.0,
+                        collected,
+                    },
+                );
+            }
+            _ if collected > 0 => {
+                self.accepted_tokens.insert(
+                    &token_id,
+                    &AcceptedToken {
+                        point_rate: 0,
+                        collected,
+                    },
+                );
+            }
+            _ => {
+                self.accepted_tokens.remove(&token_id);
+            }
+        }
+
+        ArkanaEvent::admin_action(
+            "set_token_rate",
+            serde_json::json!({ "token_id": token_id, "point_rate": point_rate }),
+        );
+    }
+
+    /// NEP-141 receiver. Unsupported tokens are returned in full and any
+    /// amount above the price is refunded.
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token_id = env::predecessor_account_id();
+
+        let mut token = match self.accepted_tokens.get(&token_id) {
+            Some(token) if token.point_rate > 0 => token,
+            _ => return PromiseOrValue::Value(amount),
+        };
+
+        self.assert_not_blacklisted(&sender_id);
+
+        let message: FtTransferMessage =
+            serde_json::from_str(&msg).expect("Invalid ft_transfer_call message");
+
+        let price_in_points = match message {
+            FtTransferMessage::Spin => self.internal_ft_spin(&sender_id),
+            FtTransferMessage::BuyClaimBooster => self.internal_ft_buy_claim_booster(&sender_id),
+            FtTransferMessage::BuyTicket {
+                reward_id,
+                amount: tickets,
+            } => {
+                let budget = amount.0 / token.point_rate;
+                self.internal_ft_buy_ticket(&sender_id, reward_id, tickets, budget)
+            }
+        };
+
+        let price = price_in_points.safe_mul(token.point_rate);
+        assert!(amount.0 >= price, "Must transfer {} tokens", price);
+
+        token.collected = token.collected.safe_add(price);
+        self.accepted_tokens.insert(&token_id, &token);
+
+        PromiseOrValue::Value(U128(amount.0 - price))
+    }
+
+    /// Sends collected tokens out. Like `withdraw_treasury` this needs guardian
+    /// approval once guardians are set up.
+    #[payable]
+    pub fn withdraw_token_treasury(
+        &mut self,
+        token_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+    ) -> Promise {
+        assert_one_yocto();
+
+        assert!(
+            self.treasurers.contains(&env::predecessor_account_id()),
+            "Unauthorized"
+        );
+        assert!(self.guardians.is_empty(), "Requires guardian approval");
+
+        self.internal_withdraw_token_treasury(token_id, amount.0, receiver_id)
+    }
+
+    // View Functions
+    pub fn get_accepted_tokens(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<AcceptedTokenOutput> {
+        paginate(self.accepted_tokens.iter(), from_index, limit).map(|(token_id, token)| {
+            AcceptedTokenOutput {
+                token_id,
+                point_rate: U128(token.point_rate),
+                collected: U128(token.collected),
+            }
+        })
+    }
+
+    pub fn get_claim_booster(&self) -> ClaimBooster {
+        self.claim_booster
+    }
+}
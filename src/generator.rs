@@ -0,0 +1,120 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+/// Points issued by one source during the current UTC day.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct DailyUsage {
+    day: u64,
+    used: Points,
+}
+
+impl DailyUsage {
+    pub(crate) fn used_today(&self) -> Points {
+        if self.day == env::block_timestamp_ms() / ONE_DAY {
+            self.used
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn consume(&mut self, amount: Points, cap: Points) {
+        let used = self.used_today().safe_add(amount);
+        assert!(used <= cap, "Daily generation cap exceeded");
+
+        self.day = env::block_timestamp_ms() / ONE_DAY;
+        self.used = used;
+    }
+}
+
+/// An account other than a membership contract that may call
+/// `generate_points`, limited to `daily_cap` points per UTC day.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PointGenerator {
+    daily_cap: Points,
+    usage: DailyUsage,
+}
+
+#[derive(Serialize)]
+pub struct PointGeneratorOutput {
+    account_id: AccountId,
+    daily_cap: U128,
+    issued_today: U128,
+}
+
+impl ArkanaCoreContract {
+    /// Counts `points` against the generator's daily cap.
+    pub(crate) fn internal_consume_generator_cap(
+        &mut self,
+        account_id: &AccountId,
+        points: Points,
+    ) {
+        let mut generator = self.point_generators.get(account_id).expect("Unauthorized");
+
+        generator.usage.consume(points, generator.daily_cap);
+
+        self.point_generators.insert(account_id, &generator);
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Adds a point generator or updates its cap, keeping today's usage.
+    #[payable]
+    pub fn set_point_generator(&mut self, account_id: AccountId, daily_cap: U128) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        let generator = match self.point_generators.get(&account_id) {
+            Some(generator) => PointGenerator {
+                daily_cap: daily_cap.0,
+                ..generator
+            },
+            None => PointGenerator {
+                daily_cap: daily_cap.0,
+                usage: DailyUsage::default(),
+            },
+        };
+        self.point_generators.insert(&account_id, &generator);
+
+        ArkanaEvent::admin_action(
+            "set_point_generator",
+            serde_json::json!({ "account_id": account_id, "daily_cap": daily_cap }),
+        );
+    }
+
+    #[payable]
+    pub fn remove_point_generator(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.point_generators
+            .remove(&account_id)
+            .expect("Point generator does not exist");
+
+        ArkanaEvent::admin_action(
+            "remove_point_generator",
+            serde_json::json!({ "account_id": account_id }),
+        );
+    }
+
+    // View Functions
+    pub fn get_point_generators(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<PointGeneratorOutput> {
+        paginate(self.point_generators.iter(), from_index, limit).map(|(account_id, generator)| {
+            PointGeneratorOutput {
+                account_id,
+                daily_cap: U128(generator.daily_cap),
+                issued_today: U128(generator.usage.used_today()),
+            }
+        })
+    }
+}
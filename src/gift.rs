@@ -0,0 +1,155 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const MAX_GIFT_MESSAGE_LEN: usize = 140;
+pub const MAX_GIFT_FEE_BPS: u16 = 2_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+pub struct GiftConfig {
+    // Total gifted per sender and UTC day, zero disables gifting
+    pub daily_limit: U128,
+    // Gifts up to this amount are free, larger ones pay `fee_bps` of it
+    pub fee_free_threshold: U128,
+    pub fee_bps: u16,
+}
+
+impl Default for GiftConfig {
+    fn default() -> Self {
+        Self {
+            daily_limit: U128(0),
+            fee_free_threshold: U128(0),
+            fee_bps: 0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GiftAllowanceOutput {
+    #[serde(flatten)]
+    config: GiftConfig,
+    gifted_today: U128,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_gift_config(&mut self, config: GiftConfig) {
+        self.assert_owner();
+
+        assert!(
+            config.fee_bps <= MAX_GIFT_FEE_BPS,
+            "Gift fee cannot exceed {} bps",
+            MAX_GIFT_FEE_BPS
+        );
+
+        self.gift_config = config;
+
+        ArkanaEvent::admin_action("set_gift_config", serde_json::json!({ "config": config }));
+    }
+
+    /// Sends points to another player with an optional message, shown in
+    /// both histories. The fee is burned and taken out of what the receiver
+    /// gets. Returns the points received.
+    #[payable]
+    pub fn gift_points(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        message: Option<String>,
+    ) -> U128 {
+        assert_one_yocto();
+
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        self.assert_not_blacklisted(&receiver_id);
+
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+        assert!(receiver_id != account_id, "Cannot gift yourself");
+        assert!(
+            self.internal_find_user(&receiver_id).is_some(),
+            "Receiver is not registered"
+        );
+        if let Some(message) = &message {
+            assert!(
+                message.len() <= MAX_GIFT_MESSAGE_LEN,
+                "Message cannot exceed {} bytes",
+                MAX_GIFT_MESSAGE_LEN
+            );
+        }
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        let config = self.gift_config;
+        let mut usage = self.gift_usage.get(&account_id).unwrap_or_default();
+        assert!(
+            usage.used_today().safe_add(amount.0) <= config.daily_limit.0,
+            "Daily gift limit exceeded"
+        );
+        usage.consume(amount.0, config.daily_limit.0);
+        self.gift_usage.insert(&account_id, &usage);
+
+        let fee = if amount.0 > config.fee_free_threshold.0 {
+            apply_bps(amount.0, config.fee_bps as u32)
+        } else {
+            0
+        };
+        let received = amount.0 - fee;
+
+        user.points = user
+            .points
+            .checked_sub(amount.0)
+            .expect("Points insufficient");
+        self.supply.burned = self.supply.burned.safe_add(fee);
+        self.internal_save_user(&account_id, &user);
+
+        let mut receiver = self.internal_get_user(&receiver_id);
+        receiver.points = receiver.points.safe_add(received);
+        self.internal_save_user(&receiver_id, &receiver);
+
+        self.internal_record_history(
+            &account_id,
+            HistoryKind::GiftSent,
+            -(amount.0 as i128),
+            message.clone(),
+        );
+        self.internal_record_history(
+            &receiver_id,
+            HistoryKind::GiftReceived,
+            received as i128,
+            message.clone(),
+        );
+        // The sender pays for the receiver's history entry too
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PointsGifted(vec![PointsGiftedData {
+            account_id,
+            receiver_id,
+            amount: U128(received),
+            fee: U128(fee),
+            message,
+        }])
+        .emit();
+
+        U128(received)
+    }
+
+    // View Functions
+    pub fn get_gift_allowance(&self, account_id: AccountId) -> GiftAllowanceOutput {
+        GiftAllowanceOutput {
+            config: self.gift_config,
+            gifted_today: U128(
+                self.gift_usage
+                    .get(&account_id)
+                    .map(|usage| usage.used_today())
+                    .unwrap_or(0),
+            ),
+        }
+    }
+}
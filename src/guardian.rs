@@ -0,0 +1,307 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type GuardianProposalId = u64;
+
+pub const GUARDIAN_PROPOSAL_TTL: u64 = 7 * ONE_DAY;
+
+/// Actions that need M-of-N guardian confirmations once guardians are set up.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GuardedAction {
+    TransferOwnership {
+        new_owner: AccountId,
+    },
+    AddGuardian {
+        account_id: AccountId,
+    },
+    RemoveGuardian {
+        account_id: AccountId,
+    },
+    SetGuardianThreshold {
+        threshold: u8,
+    },
+    QueueConfigChange {
+        change: ConfigChange,
+    },
+    WithdrawTreasury {
+        amount: U128,
+        receiver_id: AccountId,
+    },
+    WithdrawTokenTreasury {
+        token_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+    },
+    // Lets the owner deploy the code with this base58 sha256 once
+    ApproveUpgrade {
+        code_hash: String,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct GuardianProposal {
+    action: GuardedAction,
+    proposer: AccountId,
+    confirmations: Vec<AccountId>,
+    created_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct GuardianProposalOutput {
+    proposal_id: U64,
+    action: GuardedAction,
+    proposer: AccountId,
+    confirmations: Vec<AccountId>,
+    created_at: U64,
+    expires_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct GuardianConfigOutput {
+    guardians: Vec<AccountId>,
+    threshold: u8,
+}
+
+impl ArkanaCoreContract {
+    fn assert_guardian(&self) {
+        assert!(
+            self.guardians.contains(&env::predecessor_account_id()),
+            "Unauthorized"
+        );
+    }
+
+    fn internal_try_execute_proposal(&mut self, proposal_id: GuardianProposalId) -> bool {
+        let proposal = self.guardian_proposals.get(&proposal_id).unwrap();
+
+        // Confirmations from guardians removed in the meantime no longer count
+        let confirmations = proposal
+            .confirmations
+            .iter()
+            .filter(|account_id| self.guardians.contains(account_id))
+            .count();
+
+        if confirmations < self.guardian_threshold as usize {
+            return false;
+        }
+
+        self.guardian_proposals.remove(&proposal_id);
+        self.internal_execute_guarded_action(proposal.action);
+
+        ArkanaEvent::GuardedActionExecuted(vec![GuardedActionExecutedData {
+            proposal_id: U64(proposal_id),
+        }])
+        .emit();
+
+        true
+    }
+
+    fn internal_execute_guarded_action(&mut self, action: GuardedAction) {
+        match action {
+            GuardedAction::TransferOwnership { new_owner } => {
+                let old_owner = std::mem::replace(&mut self.owner, new_owner.clone());
+                ArkanaEvent::OwnershipTransferred(vec![OwnershipTransferredData {
+                    old_owner,
+                    new_owner,
+                }])
+                .emit();
+            }
+            GuardedAction::AddGuardian { account_id } => {
+                self.guardians.insert(&account_id);
+            }
+            GuardedAction::RemoveGuardian { account_id } => {
+                self.guardians.remove(&account_id);
+                assert!(
+                    self.guardians.len() >= self.guardian_threshold as u64,
+                    "Not enough guardians left for the threshold"
+                );
+            }
+            GuardedAction::SetGuardianThreshold { threshold } => {
+                assert!(
+                    threshold > 0 && threshold as u64 <= self.guardians.len(),
+                    "Invalid threshold"
+                );
+                self.guardian_threshold = threshold;
+            }
+            GuardedAction::QueueConfigChange { change } => {
+                self.internal_queue_config_change(change);
+            }
+            GuardedAction::WithdrawTreasury {
+                amount,
+                receiver_id,
+            } => {
+                self.internal_withdraw_treasury(amount.0, receiver_id);
+            }
+            GuardedAction::WithdrawTokenTreasury {
+                token_id,
+                amount,
+                receiver_id,
+            } => {
+                self.internal_withdraw_token_treasury(token_id, amount.0, receiver_id);
+            }
+            GuardedAction::ApproveUpgrade { code_hash } => {
+                self.approved_upgrade = Some(code_hash);
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// One-time bootstrap of the guardian set by the owner. Afterwards every
+    /// change to guardians goes through guardian proposals.
+    #[payable]
+    pub fn setup_guardians(&mut self, guardians: Vec<AccountId>, threshold: u8) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(self.guardians.is_empty(), "Guardians already set up");
+        assert!(
+            threshold > 0 && threshold as usize <= guardians.len(),
+            "Invalid threshold"
+        );
+
+        for guardian in guardians.iter() {
+            self.guardians.insert(guardian);
+        }
+        self.guardian_threshold = threshold;
+
+        ArkanaEvent::GuardiansSetUp(vec![GuardiansSetUpData {
+            guardians,
+            threshold,
+        }])
+        .emit();
+    }
+
+    /// Proposes a guarded action. Without guardians the owner's proposal is
+    /// executed immediately. A proposing guardian counts as a confirmation.
+    #[payable]
+    pub fn propose_guarded_action(&mut self, action: GuardedAction) -> Option<U64> {
+        assert_one_yocto();
+
+        let predecessor_id = env::predecessor_account_id();
+
+        if self.guardians.is_empty() {
+            self.assert_owner();
+            self.internal_execute_guarded_action(action);
+            return None;
+        }
+
+        if predecessor_id != self.owner {
+            self.assert_guardian();
+        }
+
+        let proposal_id = self.last_guardian_proposal_id + 1;
+        self.last_guardian_proposal_id = proposal_id;
+
+        let confirmations = if self.guardians.contains(&predecessor_id) {
+            vec![predecessor_id.clone()]
+        } else {
+            vec![]
+        };
+
+        self.guardian_proposals.insert(
+            &proposal_id,
+            &GuardianProposal {
+                action: action.clone(),
+                proposer: predecessor_id.clone(),
+                confirmations,
+                created_at: env::block_timestamp_ms(),
+            },
+        );
+
+        ArkanaEvent::GuardedActionProposed(vec![GuardedActionProposedData {
+            proposal_id: U64(proposal_id),
+            proposer: predecessor_id,
+            action,
+        }])
+        .emit();
+
+        self.internal_try_execute_proposal(proposal_id);
+
+        Some(U64(proposal_id))
+    }
+
+    /// Adds the caller's confirmation and executes the action once the
+    /// threshold is reached. Returns whether the action was executed.
+    #[payable]
+    pub fn confirm_guarded_action(&mut self, proposal_id: U64) -> bool {
+        assert_one_yocto();
+
+        self.assert_guardian();
+
+        let predecessor_id = env::predecessor_account_id();
+        let mut proposal = self
+            .guardian_proposals
+            .get(&proposal_id.0)
+            .expect("Proposal does not exist");
+
+        assert!(
+            env::block_timestamp_ms() < proposal.created_at + GUARDIAN_PROPOSAL_TTL,
+            "Proposal expired"
+        );
+        assert!(
+            !proposal.confirmations.contains(&predecessor_id),
+            "Already confirmed"
+        );
+
+        proposal.confirmations.push(predecessor_id.clone());
+        self.guardian_proposals.insert(&proposal_id.0, &proposal);
+
+        ArkanaEvent::GuardedActionConfirmed(vec![GuardedActionConfirmedData {
+            proposal_id,
+            guardian_id: predecessor_id,
+            confirmations: proposal.confirmations.len() as u8,
+            threshold: self.guardian_threshold,
+        }])
+        .emit();
+
+        self.internal_try_execute_proposal(proposal_id.0)
+    }
+
+    pub fn cancel_guarded_action(&mut self, proposal_id: U64) {
+        let proposal = self
+            .guardian_proposals
+            .get(&proposal_id.0)
+            .expect("Proposal does not exist");
+
+        let predecessor_id = env::predecessor_account_id();
+        let is_expired = env::block_timestamp_ms() >= proposal.created_at + GUARDIAN_PROPOSAL_TTL;
+        assert!(
+            is_expired || predecessor_id == proposal.proposer,
+            "Unauthorized"
+        );
+
+        self.guardian_proposals.remove(&proposal_id.0);
+    }
+
+    // View Functions
+    pub fn get_guardian_config(&self) -> GuardianConfigOutput {
+        GuardianConfigOutput {
+            guardians: self.guardians.to_vec(),
+            threshold: self.guardian_threshold,
+        }
+    }
+
+    pub fn get_guarded_actions(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<GuardianProposalOutput> {
+        paginate(self.guardian_proposals.iter(), from_index, limit).map(
+            |(proposal_id, proposal)| GuardianProposalOutput {
+                proposal_id: U64(proposal_id),
+                action: proposal.action,
+                proposer: proposal.proposer,
+                confirmations: proposal.confirmations,
+                created_at: U64(proposal.created_at),
+                expires_at: U64(proposal.created_at + GUARDIAN_PROPOSAL_TTL),
+            },
+        )
+    }
+}
@@ -0,0 +1,365 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub type GuildId = u64;
+
+pub const MAX_GUILD_NAME_LEN: usize = 32;
+pub const MAX_GUILD_MEMBERS: u64 = 50;
+pub const MAX_GUILD_OFFICERS: usize = 5;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Guild {
+    name: String,
+    leader: AccountId,
+    officers: Vec<AccountId>,
+    members: UnorderedSet<AccountId>,
+    treasury: Points,
+    // All-time contributions, the guild leaderboard score
+    contributed: Points,
+    created_at: Timestamp,
+}
+
+impl Guild {
+    fn is_officer(&self, account_id: &AccountId) -> bool {
+        &self.leader == account_id || self.officers.contains(account_id)
+    }
+}
+
+#[derive(Serialize)]
+pub struct GuildOutput {
+    guild_id: U64,
+    name: String,
+    leader: AccountId,
+    officers: Vec<AccountId>,
+    member_count: U64,
+    treasury: U128,
+    contributed: U128,
+    created_at: U64,
+}
+
+impl ArkanaCoreContract {
+    fn internal_guild_output(&self, guild_id: GuildId, guild: Guild) -> GuildOutput {
+        GuildOutput {
+            guild_id: U64(guild_id),
+            member_count: U64(guild.members.len()),
+            name: guild.name,
+            leader: guild.leader,
+            officers: guild.officers,
+            treasury: U128(guild.treasury),
+            contributed: U128(guild.contributed),
+            created_at: U64(guild.created_at),
+        }
+    }
+
+    fn internal_get_guild_of(&self, account_id: &AccountId) -> (GuildId, Guild) {
+        let guild_id = self
+            .guild_memberships
+            .get(account_id)
+            .expect("Not a guild member");
+
+        (guild_id, self.guilds.get(&guild_id).unwrap())
+    }
+
+    /// Keeps the top-N guilds sorted by contributions. Ties keep the guild
+    /// that reached the amount first ahead.
+    fn internal_update_guild_standings(&mut self, guild_id: GuildId, contributed: Points) {
+        let standings = &mut self.guild_standings;
+
+        if let Some(index) = standings.iter().position(|(id, _)| *id == guild_id) {
+            standings.remove(index);
+        } else if standings.len() >= LEADERBOARD_SIZE
+            && standings.last().map(|(_, points)| *points >= contributed) == Some(true)
+        {
+            return;
+        }
+
+        let index = standings
+            .iter()
+            .position(|(_, points)| *points < contributed)
+            .unwrap_or(standings.len());
+        standings.insert(index, (guild_id, contributed));
+        standings.truncate(LEADERBOARD_SIZE);
+    }
+
+    pub(crate) fn assert_not_in_guild(&self, account_id: &AccountId) {
+        assert!(
+            self.guild_memberships.get(account_id).is_none(),
+            "Leave your guild first"
+        );
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Creates a guild led by the caller. Its storage is charged to the
+    /// caller's storage deposit.
+    pub fn create_guild(&mut self, name: String) -> U64 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        self.internal_get_user(&account_id);
+        self.assert_not_in_guild(&account_id);
+
+        let name = name.trim().to_string();
+        assert!(
+            !name.is_empty() && name.len() <= MAX_GUILD_NAME_LEN,
+            "Guild name must be between 1 and {} bytes",
+            MAX_GUILD_NAME_LEN
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let guild_id = self.last_guild_id + 1;
+        self.last_guild_id = guild_id;
+
+        let mut members = UnorderedSet::new(StorageKey::GuildMembers { guild_id });
+        members.insert(&account_id);
+
+        self.guilds.insert(
+            &guild_id,
+            &Guild {
+                name: name.clone(),
+                leader: account_id.clone(),
+                officers: vec![],
+                members,
+                treasury: 0,
+                contributed: 0,
+                created_at: env::block_timestamp_ms(),
+            },
+        );
+        self.guild_memberships.insert(&account_id, &guild_id);
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::GuildCreated(vec![GuildCreatedData {
+            guild_id: U64(guild_id),
+            leader: account_id,
+            name,
+        }])
+        .emit();
+
+        U64(guild_id)
+    }
+
+    pub fn join_guild(&mut self, guild_id: U64) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        self.internal_get_user(&account_id);
+        self.assert_not_in_guild(&account_id);
+
+        let mut guild = self.guilds.get(&guild_id.0).expect("Guild does not exist");
+        assert!(guild.members.len() < MAX_GUILD_MEMBERS, "Guild is full");
+
+        let initial_storage_usage = env::storage_usage();
+
+        guild.members.insert(&account_id);
+        self.guilds.insert(&guild_id.0, &guild);
+        self.guild_memberships.insert(&account_id, &guild_id.0);
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::GuildJoined(vec![GuildMemberData {
+            guild_id,
+            account_id,
+        }])
+        .emit();
+    }
+
+    /// Leaves the caller's guild. The leader can only leave as the last
+    /// member, which disbands the guild and burns what is left in its
+    /// treasury.
+    pub fn leave_guild(&mut self) {
+        let account_id = self.internal_player_id();
+        let (guild_id, mut guild) = self.internal_get_guild_of(&account_id);
+
+        let initial_storage_usage = env::storage_usage();
+
+        if guild.leader == account_id {
+            assert!(
+                guild.members.len() == 1,
+                "Transfer leadership before leaving"
+            );
+
+            self.supply.burned = self.supply.burned.safe_add(guild.treasury);
+            guild.members.clear();
+            self.guilds.remove(&guild_id);
+            self.guild_standings.retain(|(id, _)| *id != guild_id);
+        } else {
+            guild.members.remove(&account_id);
+            guild.officers.retain(|officer| officer != &account_id);
+            self.guilds.insert(&guild_id, &guild);
+        }
+        self.guild_memberships.remove(&account_id);
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::GuildLeft(vec![GuildMemberData {
+            guild_id: U64(guild_id),
+            account_id,
+        }])
+        .emit();
+    }
+
+    #[payable]
+    pub fn set_guild_officer(&mut self, account_id: AccountId, is_officer: bool) {
+        assert_one_yocto();
+
+        let leader = self.internal_player_id();
+        let (guild_id, mut guild) = self.internal_get_guild_of(&leader);
+        assert!(guild.leader == leader, "Unauthorized");
+        assert!(guild.members.contains(&account_id), "Not a guild member");
+
+        guild.officers.retain(|officer| officer != &account_id);
+        if is_officer && account_id != leader {
+            assert!(
+                guild.officers.len() < MAX_GUILD_OFFICERS,
+                "Cannot have more than {} officers",
+                MAX_GUILD_OFFICERS
+            );
+            guild.officers.push(account_id);
+        }
+
+        self.guilds.insert(&guild_id, &guild);
+    }
+
+    #[payable]
+    pub fn transfer_guild_leadership(&mut self, new_leader: AccountId) {
+        assert_one_yocto();
+
+        let leader = self.internal_player_id();
+        let (guild_id, mut guild) = self.internal_get_guild_of(&leader);
+        assert!(guild.leader == leader, "Unauthorized");
+        assert!(guild.members.contains(&new_leader), "Not a guild member");
+
+        guild.officers.retain(|officer| officer != &new_leader);
+        guild.leader = new_leader;
+
+        self.guilds.insert(&guild_id, &guild);
+    }
+
+    /// Moves points from the caller's balance into their guild's treasury.
+    pub fn contribute_to_guild(&mut self, amount: U128) {
+        self.assert_not_paused(PauseFeature::All);
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        let (guild_id, mut guild) = self.internal_get_guild_of(&account_id);
+
+        let mut user = self.internal_get_user(&account_id);
+        user.points = user
+            .points
+            .checked_sub(amount.0)
+            .expect("Points insufficient");
+
+        guild.treasury = guild.treasury.safe_add(amount.0);
+        guild.contributed = guild.contributed.safe_add(amount.0);
+
+        self.internal_save_user(&account_id, &user);
+        self.guilds.insert(&guild_id, &guild);
+        self.internal_update_guild_standings(guild_id, guild.contributed);
+
+        ArkanaEvent::GuildContribution(vec![GuildContributionData {
+            guild_id: U64(guild_id),
+            account_id,
+            amount,
+        }])
+        .emit();
+    }
+
+    /// Officers spend the guild treasury to enter every member into a raffle
+    /// with `amount` tickets each. Ticket storage is charged to the officer.
+    pub fn buy_guild_tickets(&mut self, reward_id: U64, amount: U64) -> U128 {
+        self.assert_not_paused(PauseFeature::Tickets);
+        assert_valid_ticket_amount(amount.0);
+
+        let officer_id = self.internal_player_id();
+        self.assert_not_blacklisted(&officer_id);
+        let (guild_id, mut guild) = self.internal_get_guild_of(&officer_id);
+        assert!(guild.is_officer(&officer_id), "Unauthorized");
+
+        let reward = self.internal_get_open_reward(reward_id.0);
+
+        let members: Vec<AccountId> = guild
+            .members
+            .iter()
+            .filter(|account_id| !self.internal_is_blacklisted(account_id))
+            .collect();
+
+        let cost = reward
+            .price
+            .safe_mul(Points::from(amount.0))
+            .safe_mul(members.len() as Points);
+        assert!(guild.treasury >= cost, "Guild treasury insufficient");
+
+        let initial_storage_usage = env::storage_usage();
+
+        guild.treasury -= cost;
+        self.supply.spent_on_tickets = self.supply.spent_on_tickets.safe_add(cost);
+
+        let mut tickets = self.reward_tickets.get(&reward_id.0).unwrap();
+        for account_id in members.iter() {
+            tickets.add(account_id, amount.0);
+        }
+
+        self.reward_tickets.insert(&reward_id.0, &tickets);
+        self.guilds.insert(&guild_id, &guild);
+
+        self.internal_update_storage_usage(&officer_id, initial_storage_usage);
+
+        ArkanaEvent::GuildTicketsBought(vec![GuildTicketsBoughtData {
+            guild_id: U64(guild_id),
+            officer_id,
+            reward_id,
+            members: members.len() as u32,
+            amount,
+            cost: U128(cost),
+        }])
+        .emit();
+
+        U128(cost)
+    }
+
+    // View Functions
+    pub fn get_guild(&self, guild_id: U64) -> Option<GuildOutput> {
+        self.guilds
+            .get(&guild_id.0)
+            .map(|guild| self.internal_guild_output(guild_id.0, guild))
+    }
+
+    pub fn get_guild_of(&self, account_id: AccountId) -> Option<U64> {
+        self.guild_memberships.get(&account_id).map(U64)
+    }
+
+    pub fn get_guild_members(
+        &self,
+        guild_id: U64,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<AccountId> {
+        let guild = self.guilds.get(&guild_id.0).expect("Guild does not exist");
+
+        paginate(guild.members.as_vector().iter(), from_index, limit)
+    }
+
+    /// Up to `LEADERBOARD_SIZE` guilds ranked by all-time contributions.
+    pub fn get_guild_leaderboard(&self, limit: Option<u64>) -> Vec<GuildOutput> {
+        self.guild_standings
+            .iter()
+            .take(limit.unwrap_or(LEADERBOARD_SIZE as u64) as usize)
+            .filter_map(|(guild_id, _)| {
+                self.guilds
+                    .get(guild_id)
+                    .map(|guild| self.internal_guild_output(*guild_id, guild))
+            })
+            .collect()
+    }
+}
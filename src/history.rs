@@ -0,0 +1,127 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::json_types::{I128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKind {
+    Adjustment,
+    SeasonRollover,
+    Stake,
+    Unstake,
+    Quest,
+    SeasonPrize,
+    Coupon,
+    ReferralPrize,
+    TournamentPrize,
+    GiftSent,
+    GiftReceived,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct HistoryEntry {
+    kind: HistoryKind,
+    delta: i128,
+    timestamp: Timestamp,
+    actor: AccountId,
+    memo: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct HistoryEntryOutput {
+    kind: HistoryKind,
+    delta: I128,
+    timestamp: U64,
+    actor: AccountId,
+    memo: Option<String>,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_record_history(
+        &mut self,
+        account_id: &AccountId,
+        kind: HistoryKind,
+        delta: i128,
+        memo: Option<String>,
+    ) {
+        if self.event_only_history {
+            ArkanaEvent::HistoryRecorded(vec![HistoryRecordedData {
+                account_id: account_id.clone(),
+                kind,
+                delta: I128(delta),
+                actor: env::predecessor_account_id(),
+                memo,
+            }])
+            .emit();
+            return;
+        }
+
+        let mut entries = self.history.get(account_id).unwrap_or_else(|| {
+            Vector::new(StorageKey::UserHistory {
+                account_id_hash: env::sha256_array(account_id.as_bytes()),
+            })
+        });
+
+        entries.push(&HistoryEntry {
+            kind,
+            delta,
+            timestamp: env::block_timestamp_ms(),
+            actor: env::predecessor_account_id(),
+            memo,
+        });
+
+        self.history.insert(account_id, &entries);
+    }
+
+    pub(crate) fn internal_clear_history(&mut self, account_id: &AccountId) {
+        if let Some(entries) = self.history.remove(account_id) {
+            self.internal_schedule_cleanup(
+                CleanupTarget::History {
+                    account_id: account_id.clone(),
+                },
+                PendingCleanup::History(entries),
+            );
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Stops storing new history entries and emits them as events instead.
+    /// Entries stored so far stay queryable.
+    pub fn set_event_only_history(&mut self, enabled: bool) {
+        self.assert_owner();
+
+        self.event_only_history = enabled;
+
+        ArkanaEvent::admin_action(
+            "set_event_only_history",
+            serde_json::json!({ "enabled": enabled }),
+        );
+    }
+
+    // View Functions
+    pub fn get_user_history(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<HistoryEntryOutput> {
+        let entries = match self.history.get(&account_id) {
+            Some(entries) => entries,
+            None => return paginate(std::iter::empty(), from_index, limit),
+        };
+
+        paginate(entries.iter(), from_index, limit).map(|entry| HistoryEntryOutput {
+            kind: entry.kind,
+            delta: I128(entry.delta),
+            timestamp: U64(entry.timestamp),
+            actor: entry.actor,
+            memo: entry.memo,
+        })
+    }
+}
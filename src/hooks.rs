@@ -0,0 +1,104 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::{ext_contract, near_bindgen, AccountId, Gas};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const MAX_PARTNER_HOOKS: u64 = 5;
+// Every hook is prepaid by the transaction that triggers it
+pub const MAX_HOOK_GAS: u64 = 10_000_000_000_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    RewardFinalized,
+    LevelUp,
+}
+
+#[allow(dead_code)]
+#[ext_contract(ext_partner)]
+trait PartnerHookReceiver {
+    fn on_arkana_event(&mut self, event: HookEvent, data: serde_json::Value);
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize)]
+pub struct PartnerHook {
+    events: Vec<HookEvent>,
+    gas: U64,
+}
+
+#[derive(Serialize)]
+pub struct PartnerHookOutput {
+    contract_id: AccountId,
+    #[serde(flatten)]
+    hook: PartnerHook,
+}
+
+impl ArkanaCoreContract {
+    /// Fires `on_arkana_event` on every partner subscribed to `event`. The
+    /// calls are detached, a failing partner does not affect the caller.
+    pub(crate) fn internal_notify_partners(&self, event: HookEvent, data: serde_json::Value) {
+        for (contract_id, hook) in self.partner_hooks.iter() {
+            if hook.events.contains(&event) {
+                ext_partner::ext(contract_id)
+                    .with_static_gas(Gas(hook.gas.0))
+                    .with_unused_gas_weight(0)
+                    .on_arkana_event(event, data.clone());
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_partner_hook(&mut self, contract_id: AccountId, events: Vec<HookEvent>, gas: U64) {
+        self.assert_owner();
+
+        assert!(!events.is_empty(), "No events to subscribe to");
+        assert!(
+            gas.0 > 0 && gas.0 <= MAX_HOOK_GAS,
+            "Hook gas must be between 1 and {}",
+            MAX_HOOK_GAS
+        );
+        assert!(
+            self.partner_hooks.get(&contract_id).is_some()
+                || self.partner_hooks.len() < MAX_PARTNER_HOOKS,
+            "Too many partner hooks"
+        );
+
+        self.partner_hooks.insert(
+            &contract_id,
+            &PartnerHook {
+                events: events.clone(),
+                gas,
+            },
+        );
+
+        ArkanaEvent::admin_action(
+            "set_partner_hook",
+            serde_json::json!({ "contract_id": contract_id, "events": events, "gas": gas }),
+        );
+    }
+
+    pub fn remove_partner_hook(&mut self, contract_id: AccountId) {
+        self.assert_owner();
+
+        self.partner_hooks
+            .remove(&contract_id)
+            .expect("Partner hook does not exist");
+
+        ArkanaEvent::admin_action(
+            "remove_partner_hook",
+            serde_json::json!({ "contract_id": contract_id }),
+        );
+    }
+
+    // View Functions
+    pub fn get_partner_hooks(&self) -> Vec<PartnerHookOutput> {
+        self.partner_hooks
+            .iter()
+            .map(|(contract_id, hook)| PartnerHookOutput { contract_id, hook })
+            .collect()
+    }
+}
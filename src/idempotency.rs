@@ -0,0 +1,109 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::{env, near_bindgen, AccountId};
+
+use crate::*;
+
+pub const OPERATION_ID_TTL: u64 = 7 * ONE_DAY;
+pub const MAX_OPERATION_ID_LEN: usize = 64;
+// Expired ids forgotten per recorded one, keeps the cost of a call bounded
+const MAX_EXPIRED_PER_CALL: u64 = 10;
+
+/// Operation ids used within the last `OPERATION_ID_TTL`, so retried credits
+/// are rejected instead of applied twice.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OperationLog {
+    // Expiry per `caller:operation_id`
+    expires_at: LookupMap<String, Timestamp>,
+    // Keys in the order they were recorded, from `head` up to `tail`
+    order: LookupMap<u64, String>,
+    head: u64,
+    tail: u64,
+}
+
+impl OperationLog {
+    pub(crate) fn new() -> Self {
+        Self {
+            expires_at: LookupMap::new(StorageKey::OperationIds),
+            order: LookupMap::new(StorageKey::OperationOrder),
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    fn contains(&self, key: &String, now: Timestamp) -> bool {
+        self.expires_at
+            .get(key)
+            .is_some_and(|expires_at| expires_at > now)
+    }
+
+    /// Records `key`, returns false if it was used too recently.
+    fn record(&mut self, key: String, now: Timestamp) -> bool {
+        self.forget_expired(now);
+
+        if self.contains(&key, now) {
+            return false;
+        }
+
+        self.expires_at
+            .insert(&key, &now.safe_add(OPERATION_ID_TTL));
+        self.order.insert(&self.tail, &key);
+        self.tail += 1;
+        true
+    }
+
+    fn forget_expired(&mut self, now: Timestamp) {
+        for _ in 0..MAX_EXPIRED_PER_CALL {
+            if self.head == self.tail {
+                return;
+            }
+
+            let key = self.order.get(&self.head).unwrap();
+            // Ids reused after expiring stay until their newer entry expires
+            if self.contains(&key, now) {
+                return;
+            }
+
+            self.expires_at.remove(&key);
+            self.order.remove(&self.head);
+            self.head += 1;
+        }
+    }
+}
+
+fn operation_key(caller: &AccountId, operation_id: &str) -> String {
+    format!("{}:{}", caller, operation_id)
+}
+
+impl ArkanaCoreContract {
+    /// Rejects an operation id the predecessor already used recently.
+    pub(crate) fn internal_record_operation(&mut self, operation_id: Option<String>) {
+        let operation_id = match operation_id {
+            Some(operation_id) => operation_id,
+            None => return,
+        };
+
+        assert!(
+            !operation_id.is_empty() && operation_id.len() <= MAX_OPERATION_ID_LEN,
+            "Operation id must be between 1 and {} bytes",
+            MAX_OPERATION_ID_LEN
+        );
+
+        let key = operation_key(&env::predecessor_account_id(), &operation_id);
+        assert!(
+            self.operation_log.record(key, env::block_timestamp_ms()),
+            "Operation already processed"
+        );
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+    pub fn is_operation_processed(&self, caller_id: AccountId, operation_id: String) -> bool {
+        self.operation_log.contains(
+            &operation_key(&caller_id, &operation_id),
+            env::block_timestamp_ms(),
+        )
+    }
+}
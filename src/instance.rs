@@ -0,0 +1,160 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Balance, Promise};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 2_000;
+pub const MAX_COMMUNITY_NAME_LEN: usize = 64;
+
+/// Share of every treasury deposit set aside for a shared recipient, e.g.
+/// the factory that deployed the instance.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct ProtocolFee {
+    pub recipient: AccountId,
+    pub fee_bps: u16,
+}
+
+/// Everything a factory needs to set up a community instance in its init call.
+#[derive(Serialize, Deserialize)]
+pub struct InstanceConfig {
+    pub owner: AccountId,
+    pub daily_claim_points: U128,
+    pub spin_wheel_price: U128,
+    pub community_name: Option<String>,
+    pub wheel_segments: Option<Vec<WheelSegment>>,
+    pub registration_fee: Option<U128>,
+    pub protocol_fee: Option<ProtocolFee>,
+    // Emit history as events only, see `set_event_only_history`
+    #[serde(default)]
+    pub event_only_history: bool,
+}
+
+#[derive(Serialize)]
+pub struct InstanceOutput {
+    community_name: Option<String>,
+    factory_id: Option<AccountId>,
+    protocol_fee: Option<ProtocolFee>,
+    protocol_fee_balance: U128,
+}
+
+fn assert_valid_protocol_fee(protocol_fee: &Option<ProtocolFee>) {
+    if let Some(protocol_fee) = protocol_fee {
+        assert!(
+            protocol_fee.fee_bps <= MAX_PROTOCOL_FEE_BPS,
+            "Protocol fee cannot exceed {} bps",
+            MAX_PROTOCOL_FEE_BPS
+        );
+    }
+}
+
+impl ArkanaCoreContract {
+    /// Splits a treasury deposit, returning the protocol fee part.
+    pub(crate) fn internal_take_protocol_fee(&mut self, amount: Balance) -> Balance {
+        let fee = match &self.protocol_fee {
+            Some(protocol_fee) => {
+                amount.safe_mul(protocol_fee.fee_bps as Balance) / MAX_BPS as Balance
+            }
+            None => 0,
+        };
+
+        self.protocol_fee_balance = self.protocol_fee_balance.safe_add(fee);
+
+        fee
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Initializer for factories. Every instance lives on its own account,
+    /// so its state is isolated from other communities.
+    #[init]
+    pub fn new_instance(config: InstanceConfig) -> Self {
+        let mut this = Self::new(
+            config.owner,
+            config.daily_claim_points,
+            config.spin_wheel_price,
+        );
+
+        if let Some(name) = &config.community_name {
+            assert!(
+                name.len() <= MAX_COMMUNITY_NAME_LEN,
+                "Community name cannot exceed {} bytes",
+                MAX_COMMUNITY_NAME_LEN
+            );
+        }
+        if let Some(segments) = config.wheel_segments {
+            this.internal_set_wheel_segments(segments);
+        }
+        assert_valid_protocol_fee(&config.protocol_fee);
+
+        this.community_name = config.community_name;
+        this.registration_fee = config.registration_fee.map(|fee| fee.0).unwrap_or(0);
+        this.protocol_fee = config.protocol_fee;
+        this.event_only_history = config.event_only_history;
+
+        let predecessor_id = env::predecessor_account_id();
+        if predecessor_id != env::current_account_id() {
+            this.factory_id = Some(predecessor_id);
+        }
+
+        this
+    }
+
+    /// Only the current fee recipient can change or drop the protocol fee.
+    #[payable]
+    pub fn set_protocol_fee(&mut self, protocol_fee: Option<ProtocolFee>) {
+        assert_one_yocto();
+
+        let current = self.protocol_fee.as_ref().expect("No protocol fee");
+        assert_eq!(
+            current.recipient,
+            env::predecessor_account_id(),
+            "Unauthorized"
+        );
+        assert_valid_protocol_fee(&protocol_fee);
+
+        self.protocol_fee = protocol_fee.clone();
+
+        ArkanaEvent::admin_action(
+            "set_protocol_fee",
+            serde_json::json!({ "protocol_fee": protocol_fee }),
+        );
+    }
+
+    /// Sends the accrued protocol fees to the recipient.
+    #[payable]
+    pub fn withdraw_protocol_fees(&mut self) -> Promise {
+        assert_one_yocto();
+
+        let receiver_id = self
+            .protocol_fee
+            .as_ref()
+            .map(|protocol_fee| protocol_fee.recipient.clone())
+            .expect("No protocol fee");
+        assert_eq!(receiver_id, env::predecessor_account_id(), "Unauthorized");
+
+        let amount = self.protocol_fee_balance;
+        assert!(amount > 0, "No protocol fees to withdraw");
+        self.protocol_fee_balance = 0;
+
+        self.internal_track_operation(
+            Promise::new(receiver_id.clone()).transfer(amount),
+            PendingOperation::ProtocolFeeWithdrawal {
+                receiver_id,
+                amount: U128(amount),
+            },
+        )
+    }
+
+    // View Functions
+    pub fn get_instance(&self) -> InstanceOutput {
+        InstanceOutput {
+            community_name: self.community_name.clone(),
+            factory_id: self.factory_id.clone(),
+            protocol_fee: self.protocol_fee.clone(),
+            protocol_fee_balance: U128(self.protocol_fee_balance),
+        }
+    }
+}
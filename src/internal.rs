@@ -0,0 +1,168 @@
+use near_sdk::{env, AccountId, Balance, Promise};
+
+use crate::*;
+
+/// Charges the storage added by the current call to the attached deposit and
+/// refunds whatever is left to the predecessor.
+pub(crate) fn refund_deposit(storage_used: u64) {
+    let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
+    let attached_deposit = env::attached_deposit();
+
+    assert!(
+        required_cost <= attached_deposit,
+        "Must attach {} yoctoNEAR to cover storage",
+        required_cost
+    );
+
+    let refund = attached_deposit - required_cost;
+    if refund > 1 {
+        Promise::new(env::predecessor_account_id()).transfer(refund);
+    }
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn assert_owner(&self) {
+        if env::predecessor_account_id() != self.owner {
+            panic!("Unauthorized");
+        }
+    }
+
+    pub(crate) fn assert_owner_or_operator(&self) {
+        let predecessor_id = env::predecessor_account_id();
+
+        if predecessor_id != self.owner && !self.operators.contains(&predecessor_id) {
+            panic!("Unauthorized");
+        }
+    }
+
+    /// The account a gameplay action is performed for: the predecessor, or
+    /// the primary account of a linked wallet. NEP-366 delegate actions keep
+    /// the user as predecessor with the relayer as signer, so relayed calls
+    /// work unless `require_direct_signer` is switched on.
+    pub(crate) fn internal_player_id(&self) -> AccountId {
+        self.internal_try_player_id()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Points a daily claim credits to `user` before check-in rewards.
+    pub(crate) fn internal_daily_claim_points(
+        &self,
+        account_id: &AccountId,
+        user: &User,
+    ) -> Points {
+        let claim_bonus_bps = self.internal_nft_boost(account_id).claim_bonus_bps
+            + self.internal_claim_booster_bps(user);
+        self.internal_apply_earnings_multiplier(
+            self.daily_claim_points
+                .safe_add(apply_bps(self.daily_claim_points, claim_bonus_bps)),
+        )
+    }
+
+    /// Claim streak of `user` after claiming at `timestamp`. Claiming within
+    /// one more cooldown keeps the streak going.
+    pub(crate) fn internal_next_claim_streak(&self, user: &User, timestamp: Timestamp) -> u32 {
+        let delta_ms = timestamp.saturating_sub(user.activity.last_daily_claim());
+        let is_streak = user.activity.last_daily_claim() > 0
+            && delta_ms < self.daily_claim_cooldown_ms.safe_mul(2);
+        if is_streak {
+            user.activity.claim_streak() + 1
+        } else {
+            1
+        }
+    }
+
+    /// Reads a user without side effects, falling back to records that have
+    /// not been upgraded from the V1 layout yet.
+    pub(crate) fn internal_find_user(&self, account_id: &AccountId) -> Option<User> {
+        self.users.get(account_id).map(User::from).or_else(|| {
+            self.legacy_users
+                .get(account_id)
+                .map(|legacy| legacy.into_current())
+        })
+    }
+
+    /// Loads a user for mutation, settling any season rollover that happened
+    /// since the user's last interaction.
+    pub(crate) fn internal_get_user(&mut self, account_id: &AccountId) -> User {
+        let mut user = match self.users.get(account_id) {
+            Some(user) => User::from(user),
+            None => {
+                let user = self
+                    .legacy_users
+                    .remove(account_id)
+                    .expect("User does not exist")
+                    .into_current();
+                self.users.insert(account_id, &VUser::Current(user.clone()));
+                self.user_ids.insert(account_id);
+                user
+            }
+        };
+
+        if user.season < self.current_season {
+            self.internal_settle_season(account_id, &mut user);
+        }
+
+        user
+    }
+
+    pub(crate) fn internal_register_user(&mut self, account_id: &AccountId) {
+        // A new history would reuse the storage prefix of the old one
+        self.assert_no_pending_cleanup(&CleanupTarget::History {
+            account_id: account_id.clone(),
+        });
+
+        let mut user = User::new(self.current_season);
+        if self.former_users.contains(account_id) {
+            user.points = 0;
+        } else {
+            self.supply.issued = self.supply.issued.safe_add(INIT_POINT);
+        }
+
+        self.users.insert(account_id, &VUser::Current(user));
+        self.user_ids.insert(account_id);
+    }
+
+    pub(crate) fn internal_create_reward(
+        &mut self,
+        title: String,
+        price: Points,
+        ended_at: Timestamp,
+    ) -> RewardId {
+        assert_valid_reward(&title, price, ended_at, env::block_timestamp_ms());
+
+        let reward_id = self.last_reward_id + 1;
+
+        self.rewards.insert(
+            &reward_id,
+            &Reward {
+                title: LazyOption::new(StorageKey::RewardTitle { reward_id }, Some(&title)),
+                price,
+                ended_at,
+                winner: None,
+                status: RewardStatus::Active,
+                draw: None,
+            },
+        );
+        self.reward_tickets
+            .insert(&reward_id, &RewardTickets::new(reward_id));
+
+        self.last_reward_id = reward_id;
+
+        reward_id
+    }
+
+    pub(crate) fn internal_add_tickets(
+        &mut self,
+        reward_id: RewardId,
+        account_id: &AccountId,
+        amount: u64,
+    ) {
+        let mut tickets = self.reward_tickets.get(&reward_id).unwrap();
+        tickets.add(account_id, amount);
+        self.reward_tickets.insert(&reward_id, &tickets);
+    }
+
+    pub(crate) fn internal_save_user(&mut self, account_id: &AccountId, user: &User) {
+        self.users.insert(account_id, &VUser::Current(user.clone()));
+    }
+}
@@ -0,0 +1,263 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type ItemId = String;
+
+pub const MAX_ITEM_ID_LEN: usize = 32;
+pub const MAX_ITEM_TYPES: u64 = 32;
+
+/// What happens when a user activates an item with `use_item`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ItemEffect {
+    // Cannot be activated, only held, traded or crafted
+    Collectible,
+    // Covers one missed daily claim so the claim streak carries on
+    StreakFreeze,
+    // Added to the wheel's boost counter for the user's next spin
+    OddsBooster { boost: u8 },
+    FreeSpin,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct ItemDefinition {
+    pub name: String,
+    pub effect: ItemEffect,
+    pub media: Option<String>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ItemGrant {
+    pub item_id: ItemId,
+    pub amount: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct InventorySlot {
+    item_id: ItemId,
+    quantity: u64,
+}
+
+#[derive(Serialize)]
+pub struct InventoryItemOutput {
+    item_id: ItemId,
+    quantity: U64,
+    item: Option<ItemDefinition>,
+}
+
+#[derive(Serialize)]
+pub struct ItemOutput {
+    item_id: ItemId,
+    #[serde(flatten)]
+    item: ItemDefinition,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn assert_item_exists(&self, item_id: &ItemId) {
+        assert!(self.items.get(item_id).is_some(), "Item does not exist");
+    }
+
+    /// Adds items to a user's inventory. Storage is accounted by the caller.
+    pub(crate) fn internal_grant_item(
+        &mut self,
+        account_id: &AccountId,
+        item_id: &ItemId,
+        amount: u64,
+    ) {
+        let mut inventory = self.inventories.get(account_id).unwrap_or_default();
+
+        match inventory.iter_mut().find(|slot| &slot.item_id == item_id) {
+            Some(slot) => slot.quantity = slot.quantity.safe_add(amount),
+            None => inventory.push(InventorySlot {
+                item_id: item_id.clone(),
+                quantity: amount,
+            }),
+        }
+
+        self.inventories.insert(account_id, &inventory);
+
+        ArkanaEvent::ItemGranted(vec![ItemGrantedData {
+            account_id: account_id.clone(),
+            item_id: item_id.clone(),
+            amount: U64(amount),
+        }])
+        .emit();
+    }
+
+    /// Removes items from a user's inventory, dropping emptied slots.
+    pub(crate) fn internal_take_item(
+        &mut self,
+        account_id: &AccountId,
+        item_id: &ItemId,
+        amount: u64,
+    ) {
+        let mut inventory = self.inventories.get(account_id).unwrap_or_default();
+
+        let index = inventory
+            .iter()
+            .position(|slot| &slot.item_id == item_id && slot.quantity >= amount)
+            .expect("Not enough items");
+
+        inventory[index].quantity -= amount;
+        if inventory[index].quantity == 0 {
+            inventory.remove(index);
+        }
+
+        if inventory.is_empty() {
+            self.inventories.remove(account_id);
+        } else {
+            self.inventories.insert(account_id, &inventory);
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Defines or updates an item. Removing a definition leaves held items in
+    /// place but they can no longer be activated.
+    #[payable]
+    pub fn set_item(&mut self, item_id: ItemId, item: Option<ItemDefinition>) {
+        self.assert_owner();
+
+        assert!(
+            !item_id.is_empty() && item_id.len() <= MAX_ITEM_ID_LEN,
+            "Item id must be between 1 and {} bytes",
+            MAX_ITEM_ID_LEN
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        match &item {
+            Some(item) => {
+                assert!(
+                    self.items.get(&item_id).is_some() || self.items.len() < MAX_ITEM_TYPES,
+                    "Cannot define more than {} items",
+                    MAX_ITEM_TYPES
+                );
+                self.items.insert(&item_id, item);
+            }
+            None => {
+                self.items.remove(&item_id);
+            }
+        }
+
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        ArkanaEvent::admin_action(
+            "set_item",
+            serde_json::json!({ "item_id": item_id, "item": item }),
+        );
+    }
+
+    /// Item handed to the winner of a raffle when it is finalized.
+    pub fn set_reward_item(&mut self, reward_id: U64, item: Option<ItemGrant>) {
+        self.assert_owner();
+
+        let reward = self.internal_get_reward(reward_id.0);
+        assert!(
+            reward.status == RewardStatus::Active,
+            "Reward {}",
+            reward.status.describe()
+        );
+
+        match &item {
+            Some(item) => {
+                self.assert_item_exists(&item.item_id);
+                self.reward_items.insert(&reward_id.0, item);
+            }
+            None => {
+                self.reward_items.remove(&reward_id.0);
+            }
+        }
+
+        ArkanaEvent::admin_action(
+            "set_reward_item",
+            serde_json::json!({ "reward_id": reward_id, "item": item }),
+        );
+    }
+
+    /// Consumes one item from the caller's inventory and applies its effect.
+    /// Returns the points won for free spin tokens.
+    pub fn use_item(&mut self, item_id: ItemId) -> Option<U128> {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let item = self.items.get(&item_id).expect("Item does not exist");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_take_item(&account_id, &item_id, 1);
+
+        let result = match item.effect {
+            ItemEffect::Collectible => panic!("Item cannot be used"),
+            ItemEffect::StreakFreeze => {
+                // Moves the last claim one cooldown forward, as if the missed
+                // day had been claimed
+                let last_daily_claim = user.activity.last_daily_claim();
+                let missed_since =
+                    last_daily_claim.safe_add(self.daily_claim_cooldown_ms.safe_mul(2));
+                assert!(
+                    user.activity.claim_streak() > 0 && env::block_timestamp_ms() >= missed_since,
+                    "Claim streak is not at risk"
+                );
+                user.activity
+                    .set_last_daily_claim(last_daily_claim + self.daily_claim_cooldown_ms);
+                None
+            }
+            ItemEffect::OddsBooster { boost } => {
+                user.odds_boost = user.odds_boost.saturating_add(boost);
+                None
+            }
+            ItemEffect::FreeSpin => {
+                self.assert_not_paused(PauseFeature::Spins);
+                let segment = self.internal_spin_wheel(&mut user);
+                Some(U128(self.internal_award_spin(
+                    &account_id,
+                    &mut user,
+                    &segment,
+                )))
+            }
+        };
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::ItemUsed(vec![ItemGrantedData {
+            account_id,
+            item_id,
+            amount: U64(1),
+        }])
+        .emit();
+
+        result
+    }
+
+    // View Functions
+    pub fn get_items(&self) -> Vec<ItemOutput> {
+        self.items
+            .iter()
+            .map(|(item_id, item)| ItemOutput { item_id, item })
+            .collect()
+    }
+
+    pub fn get_inventory(&self, account_id: AccountId) -> Vec<InventoryItemOutput> {
+        self.inventories
+            .get(&account_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|slot| InventoryItemOutput {
+                item: self.items.get(&slot.item_id),
+                item_id: slot.item_id,
+                quantity: U64(slot.quantity),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,101 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const JACKPOT_CHANCE_DENOMINATOR: u32 = 1_000_000;
+// Rolls on other seed bytes than the game the jackpot is attached to
+
+/// Every paid spin, lootbox open and mini-game stake puts `contribution_bps`
+/// of its price into the jackpot and wins the whole pool with a
+/// `win_chance_ppm` in a million chance.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct JackpotConfig {
+    pub contribution_bps: u16,
+    pub win_chance_ppm: u32,
+}
+
+#[derive(Serialize)]
+pub struct JackpotOutput {
+    #[serde(flatten)]
+    config: JackpotConfig,
+    pool: U128,
+}
+
+impl ArkanaCoreContract {
+    /// Moves the jackpot's share of `spent` into the pool and rolls for it.
+    /// Returns the share so games with their own pool can leave it out.
+    pub(crate) fn internal_feed_jackpot(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        spent: Points,
+    ) -> Points {
+        let config = self.jackpot_config;
+        let contribution = apply_bps(spent, config.contribution_bps as u32);
+        self.jackpot_pool = self.jackpot_pool.safe_add(contribution);
+
+        let roll = self.internal_random_number(RandomPurpose::Jackpot) % JACKPOT_CHANCE_DENOMINATOR;
+        if self.jackpot_pool > 0 && roll < config.win_chance_ppm {
+            let prize = std::mem::take(&mut self.jackpot_pool);
+            self.internal_issue_points(account_id, user, prize);
+
+            ArkanaEvent::JackpotWon(vec![JackpotWonData {
+                account_id: account_id.clone(),
+                prize: U128(prize),
+            }])
+            .emit();
+        }
+
+        contribution
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_jackpot_config(&mut self, config: JackpotConfig) {
+        self.assert_owner();
+
+        assert!(
+            config.contribution_bps <= MAX_BPS,
+            "Basis points cannot exceed {}",
+            MAX_BPS
+        );
+        assert!(
+            config.win_chance_ppm <= JACKPOT_CHANCE_DENOMINATOR,
+            "Win chance cannot exceed {} ppm",
+            JACKPOT_CHANCE_DENOMINATOR
+        );
+
+        self.jackpot_config = config;
+
+        ArkanaEvent::admin_action(
+            "set_jackpot_config",
+            serde_json::json!({ "config": config }),
+        );
+    }
+
+    /// Seeds the jackpot with newly issued points.
+    #[payable]
+    pub fn fund_jackpot(&mut self, amount: U128) -> U128 {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.jackpot_pool = self.jackpot_pool.safe_add(amount.0);
+
+        ArkanaEvent::admin_action("fund_jackpot", serde_json::json!({ "amount": amount }));
+
+        U128(self.jackpot_pool)
+    }
+
+    // View Functions
+    pub fn get_jackpot(&self) -> JackpotOutput {
+        JackpotOutput {
+            config: self.jackpot_config,
+            pool: U128(self.jackpot_pool),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const DEFAULT_PRIZE_BATCH: u32 = 10;
+
+/// Final top-N of a season and the prizes configured when it ended.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SeasonResult {
+    standings: Vec<(AccountId, u64)>,
+    prizes: Vec<Points>,
+    // Ranks paid out so far, in rank order
+    distributed: u32,
+}
+
+impl SeasonResult {
+    fn prize_ranks(&self) -> u32 {
+        self.standings.len().min(self.prizes.len()) as u32
+    }
+}
+
+#[derive(Serialize)]
+pub struct StandingOutput {
+    rank: u32,
+    account_id: AccountId,
+    season_xp: U64,
+    prize: U128,
+    title: Option<TitleId>,
+}
+
+#[derive(Serialize)]
+pub struct SeasonResultOutput {
+    season: SeasonId,
+    standings: Vec<StandingOutput>,
+    distributed: u32,
+}
+
+impl ArkanaCoreContract {
+    fn internal_standings_output(
+        &self,
+        standings: &[(AccountId, u64)],
+        prizes: &[Points],
+    ) -> Vec<StandingOutput> {
+        standings
+            .iter()
+            .enumerate()
+            .map(|(index, (account_id, season_xp))| StandingOutput {
+                rank: index as u32 + 1,
+                account_id: account_id.clone(),
+                season_xp: U64(*season_xp),
+                prize: U128(prizes.get(index).copied().unwrap_or(0)),
+                title: self.internal_equipped_title(account_id),
+            })
+            .collect()
+    }
+
+    /// Keeps the current season's top-N sorted by seasonal XP. Ties keep the
+    /// account that reached the score first ahead.
+    pub(crate) fn internal_update_season_standings(
+        &mut self,
+        account_id: &AccountId,
+        season_xp: u64,
+    ) {
+        let standings = &mut self.season_standings;
+
+        if let Some(index) = standings.iter().position(|(a, _)| a == account_id) {
+            standings.remove(index);
+        } else if standings.len() >= LEADERBOARD_SIZE
+            && standings.last().map(|(_, xp)| *xp >= season_xp) == Some(true)
+        {
+            return;
+        }
+
+        let index = standings
+            .iter()
+            .position(|(_, xp)| *xp < season_xp)
+            .unwrap_or(standings.len());
+        standings.insert(index, (account_id.clone(), season_xp));
+        standings.truncate(LEADERBOARD_SIZE);
+    }
+
+    pub(crate) fn internal_remove_season_standing(&mut self, account_id: &AccountId) {
+        self.season_standings.retain(|(a, _)| a != account_id);
+    }
+
+    /// Freezes the standings of `season` together with its prizes, which are
+    /// paid out afterwards with `distribute_season_prizes`.
+    pub(crate) fn internal_close_season_standings(&mut self, season: SeasonId) {
+        let standings = std::mem::take(&mut self.season_standings);
+
+        self.season_results.insert(
+            &season,
+            &SeasonResult {
+                standings,
+                prizes: self.season_prizes.clone(),
+                distributed: 0,
+            },
+        );
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Point prizes by rank, paid to the top of the leaderboard when the
+    /// current season ends.
+    pub fn set_season_prizes(&mut self, prizes: Vec<U128>) {
+        self.assert_owner();
+
+        assert!(
+            prizes.len() <= LEADERBOARD_SIZE,
+            "Cannot have more than {} prizes",
+            LEADERBOARD_SIZE
+        );
+
+        self.season_prizes = prizes.iter().map(|prize| prize.0).collect();
+
+        ArkanaEvent::admin_action("set_season_prizes", serde_json::json!({ "prizes": prizes }));
+    }
+
+    /// Pays the next `limit` prizes of an ended season. Callable by anyone,
+    /// returns the number of prizes left. Prizes of accounts that have
+    /// unregistered since are forfeited.
+    pub fn distribute_season_prizes(&mut self, season: SeasonId, limit: Option<u32>) -> u32 {
+        let mut result = self
+            .season_results
+            .get(&season)
+            .expect("Season has no results");
+
+        let prize_ranks = result.prize_ranks();
+        let end = prize_ranks.min(
+            result
+                .distributed
+                .saturating_add(limit.unwrap_or(DEFAULT_PRIZE_BATCH)),
+        );
+
+        for index in result.distributed..end {
+            let (account_id, _) = result.standings[index as usize].clone();
+            let prize = result.prizes[index as usize];
+
+            if prize == 0 || self.internal_find_user(&account_id).is_none() {
+                continue;
+            }
+
+            // Prizes are not XP, they must not count towards the next season
+            let mut user = self.internal_get_user(&account_id);
+            user.points = user.points.safe_add(prize);
+            self.supply.issued = self.supply.issued.safe_add(prize);
+            self.internal_save_user(&account_id, &user);
+            self.internal_record_history(
+                &account_id,
+                HistoryKind::SeasonPrize,
+                prize as i128,
+                Some(format!("Season {} rank {}", season, index + 1)),
+            );
+
+            ArkanaEvent::SeasonPrizePaid(vec![SeasonPrizePaidData {
+                season,
+                rank: index + 1,
+                account_id,
+                prize: U128(prize),
+            }])
+            .emit();
+        }
+
+        result.distributed = end;
+        self.season_results.insert(&season, &result);
+
+        prize_ranks - end
+    }
+
+    // View Functions
+    pub fn get_season_standings(&self) -> Vec<StandingOutput> {
+        self.internal_standings_output(&self.season_standings, &self.season_prizes)
+    }
+
+    pub fn get_season_prizes(&self) -> Vec<U128> {
+        self.season_prizes
+            .iter()
+            .map(|prize| U128(*prize))
+            .collect()
+    }
+
+    pub fn get_season_result(&self, season: SeasonId) -> Option<SeasonResultOutput> {
+        self.season_results
+            .get(&season)
+            .map(|result| SeasonResultOutput {
+                season,
+                standings: self.internal_standings_output(&result.standings, &result.prizes),
+                distributed: result.distributed,
+            })
+    }
+}
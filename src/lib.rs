@@ -1,340 +1,1167 @@
-use std::collections::HashSet;
-
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{TreeMap, UnorderedMap};
-use near_sdk::json_types::U64;
-use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{I128, U128, U64};
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, AccountId, Balance, BorshStorageKey, CryptoHash,
+    PanicOnDefault, StorageUsage,
+};
 use serde::Serialize;
 
+pub use crate::achievement::*;
+pub use crate::activity::*;
+pub use crate::admin_overview::*;
+pub use crate::auction::*;
+pub use crate::badge::*;
+pub use crate::battle_pass::*;
+pub use crate::blacklist::*;
+pub use crate::bridge::*;
+pub use crate::charity::*;
+pub use crate::checkin::*;
+pub use crate::cleanup::*;
+pub use crate::config::*;
+pub use crate::coupon::*;
+pub use crate::crafting::*;
+pub use crate::dashboard::*;
+pub use crate::dutch_sale::*;
+pub use crate::estimate::*;
+pub use crate::event::*;
+pub use crate::export::*;
+pub use crate::fairness::*;
+pub use crate::ft::*;
+pub use crate::generator::*;
+pub use crate::gift::*;
+pub use crate::guardian::*;
+pub use crate::guild::*;
+pub use crate::history::*;
+pub use crate::hooks::*;
+pub use crate::idempotency::*;
+pub use crate::instance::*;
+use crate::internal::*;
+pub use crate::inventory::*;
+pub use crate::jackpot::*;
+pub use crate::leaderboard::*;
+pub use crate::lootbox::*;
+pub use crate::lucky_draw::*;
+pub use crate::marketplace::*;
+pub use crate::math::*;
+pub use crate::membership::*;
+pub use crate::metadata::*;
+pub use crate::migration::*;
+pub use crate::milestone::*;
+pub use crate::multiplier::*;
+pub use crate::nft::*;
+pub use crate::onboarding::*;
+pub use crate::oracle::*;
+pub use crate::outcome::*;
+pub use crate::pagination::*;
+pub use crate::pause::*;
+pub use crate::pending::*;
+pub use crate::personhood::*;
+pub use crate::poll::*;
+pub use crate::prediction::*;
+pub use crate::quest::*;
+pub use crate::random::*;
+pub use crate::rate_limit::*;
+pub use crate::referral::*;
+pub use crate::relayer::*;
+pub use crate::responsible_gaming::*;
+pub use crate::reward_proposal::*;
+pub use crate::reward_status::*;
+pub use crate::scheduler::*;
+pub use crate::scratch::*;
+pub use crate::season::*;
+pub use crate::simulation::*;
+pub use crate::social::*;
+pub use crate::staking::*;
+pub use crate::staking_pool::*;
+pub use crate::storage::*;
+pub use crate::supply::*;
+pub use crate::title::*;
+pub use crate::tournament::*;
+pub use crate::treasury::*;
+pub use crate::trivia::*;
+pub use crate::validation::*;
+pub use crate::verification::*;
+pub use crate::wager::*;
+pub use crate::wallet::*;
+pub use crate::wheel::*;
+
+mod achievement;
+mod activity;
+mod admin_overview;
+mod auction;
+mod badge;
+mod battle_pass;
+mod blacklist;
+mod bridge;
+mod charity;
+mod checkin;
+mod cleanup;
+mod config;
+mod coupon;
+mod crafting;
+mod dashboard;
+mod dutch_sale;
+mod estimate;
+mod event;
+mod export;
+mod fairness;
+mod ft;
+mod generator;
+mod gift;
+mod guardian;
+mod guild;
+mod history;
+mod hooks;
+mod idempotency;
+mod instance;
+mod internal;
+mod inventory;
+mod jackpot;
+mod leaderboard;
+mod lootbox;
+mod lucky_draw;
+mod marketplace;
+mod math;
+mod membership;
+mod metadata;
+mod migration;
+mod milestone;
+mod multiplier;
+mod nft;
+mod onboarding;
+mod oracle;
+mod outcome;
+mod pagination;
+mod pause;
+mod pending;
+mod personhood;
+mod poll;
+mod prediction;
+mod quest;
+mod random;
+mod rate_limit;
+mod referral;
+mod relayer;
+mod responsible_gaming;
+mod reward_proposal;
+mod reward_status;
+mod scheduler;
+mod scratch;
+mod season;
+mod simulation;
+mod social;
+mod staking;
+mod staking_pool;
+mod storage;
+mod supply;
+mod title;
+mod tournament;
+mod treasury;
+mod trivia;
+mod validation;
+mod verification;
+mod wager;
+mod wallet;
+mod wheel;
+
 pub type Timestamp = u64; // ms
 pub type TicketId = String;
 pub type RewardId = u64;
-pub type Points = u64;
+pub type Points = u128;
+pub type SeasonId = u32;
 pub const ONE_DAY: u64 = 86400000;
-pub const INIT_POINT: u64 = 25;
+pub const INIT_POINT: Points = 25;
+pub const MAX_BPS: u16 = 10_000;
+pub const XP_PER_LEVEL: u64 = 100;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct ArkanaCoreContract {
     owner: AccountId,
-    daily_claim_points: u64,
-    spin_wheel_price: u64,
-    users: UnorderedMap<AccountId, User>,
+    daily_claim_points: Points,
+    spin_wheel_price: Points,
+    // Read on every call, enumerated through `user_ids`
+    users: LookupMap<AccountId, VUser>,
     rewards: UnorderedMap<RewardId, Reward>,
     last_reward_id: RewardId,
-    membership_contracts: HashSet<AccountId>,
+    membership_contracts: UnorderedSet<AccountId>,
     spinwheel_wr: u8,
+    operators: UnorderedSet<AccountId>,
+    history: LookupMap<AccountId, Vector<HistoryEntry>>,
+    current_season: SeasonId,
+    season_started_at: Timestamp,
+    season_snapshots: LookupMap<(AccountId, SeasonId), SeasonSnapshot>,
+    season_carry_over_bps: LookupMap<SeasonId, u16>,
+    next_season_carry_over_bps: u16,
+    supply: SupplyStats,
+    staking_options: UnorderedMap<u32, StakingOption>,
+    early_unstake_penalty_bps: u16,
+    staking_emission_budget: Points,
+    stakes: UnorderedMap<StakeId, Stake>,
+    user_stakes: LookupMap<AccountId, UnorderedSet<StakeId>>,
+    last_stake_id: StakeId,
+    pause_state: PauseState,
+    blacklist: UnorderedMap<AccountId, BlacklistEntry>,
+    legacy_users: UnorderedMap<AccountId, UserV1>,
+    guardians: UnorderedSet<AccountId>,
+    guardian_threshold: u8,
+    guardian_proposals: UnorderedMap<GuardianProposalId, GuardianProposal>,
+    last_guardian_proposal_id: GuardianProposalId,
+    wheel_segments: Vec<WheelSegment>,
+    config_timelock_ms: u64,
+    pending_config_changes: UnorderedMap<ConfigChangeId, PendingConfigChange>,
+    last_config_change_id: ConfigChangeId,
+    treasury_balance: Balance,
+    treasurers: UnorderedSet<AccountId>,
+    registration_fee: Balance,
+    spin_wheel_price_near: Balance,
+    reward_near_prices: LookupMap<RewardId, Balance>,
+    storage_accounts: LookupMap<AccountId, StorageAccount>,
+    registration_storage_usage: StorageUsage,
+    rate_limit: RateLimitPolicy,
+    point_generators: UnorderedMap<AccountId, PointGenerator>,
+    membership_configs: LookupMap<AccountId, MembershipConfig>,
+    daily_claim_cooldown_ms: u64,
+    free_spin_cooldown_ms: u64,
+    pending_operations: UnorderedMap<OperationId, PendingOperation>,
+    last_operation_id: OperationId,
+    staked_nfts: LookupMap<AccountId, UnorderedSet<(AccountId, TokenId)>>,
+    membership_verifications: LookupMap<AccountId, MembershipVerification>,
+    accepted_tokens: UnorderedMap<AccountId, AcceptedToken>,
+    claim_booster: ClaimBooster,
+    onboarders: UnorderedMap<AccountId, Onboarder>,
+    require_direct_signer: bool,
+    wallet_links: LookupMap<AccountId, AccountId>,
+    linked_wallets: LookupMap<AccountId, Vec<AccountId>>,
+    pending_wallet_links: LookupMap<AccountId, PendingWalletLink>,
+    price_oracle: Option<PriceOracleConfig>,
+    near_usd_price: Option<NearUsdPrice>,
+    spin_wheel_price_usd: Balance,
+    reward_usd_prices: LookupMap<RewardId, Balance>,
+    scheduled_tasks: UnorderedMap<TaskId, ScheduledTask>,
+    last_task_id: TaskId,
+    social_db: AccountId,
+    social_profiles: LookupMap<AccountId, SocialProfile>,
+    partner_hooks: UnorderedMap<AccountId, PartnerHook>,
+    quests: UnorderedMap<QuestId, Quest>,
+    quest_completions: LookupMap<(QuestId, AccountId), Timestamp>,
+    badge_config: Option<BadgeConfig>,
+    badges: UnorderedMap<Achievement, BadgeMetadata>,
+    badge_mints: LookupMap<(AccountId, Achievement), BadgeMintStatus>,
+    bridge_chains: UnorderedSet<String>,
+    bridge_in_transfers: LookupSet<String>,
+    last_bridge_nonce: u64,
+    spin_relayers: LookupMap<AccountId, Vec<SpinRelayer>>,
+    staking_pool: Option<StakingPoolConfig>,
+    pool_stake_checkpoints: LookupMap<AccountId, PoolStakeCheckpoint>,
+    reward_proposers: UnorderedSet<AccountId>,
+    reward_proposals: UnorderedMap<RewardProposalId, RewardProposal>,
+    last_reward_proposal_id: RewardProposalId,
+    reward_approval_threshold: u8,
+    community_name: Option<String>,
+    factory_id: Option<AccountId>,
+    protocol_fee: Option<ProtocolFee>,
+    protocol_fee_balance: Balance,
+    achievement_bonuses: LookupMap<Achievement, Points>,
+    season_standings: Vec<(AccountId, u64)>,
+    season_prizes: Vec<Points>,
+    season_results: LookupMap<SeasonId, SeasonResult>,
+    guilds: UnorderedMap<GuildId, Guild>,
+    guild_memberships: LookupMap<AccountId, GuildId>,
+    last_guild_id: GuildId,
+    check_in_calendar: Vec<CheckInDay>,
+    lootboxes: UnorderedMap<LootboxId, Lootbox>,
+    last_lootbox_id: LootboxId,
+    items: UnorderedMap<ItemId, ItemDefinition>,
+    inventories: LookupMap<AccountId, Vec<InventorySlot>>,
+    reward_items: LookupMap<RewardId, ItemGrant>,
+    coupons: LookupMap<CryptoHash, Coupon>,
+    scratch_batches: UnorderedMap<ScratchBatchId, ScratchBatch>,
+    last_scratch_batch_id: ScratchBatchId,
+    wager_config: WagerConfig,
+    pending_wagers: LookupMap<AccountId, PendingWager>,
+    wager_usage: LookupMap<AccountId, DailyUsage>,
+    house_bankroll: HouseBankroll,
+    prediction_pools: LookupMap<RewardId, PredictionPool>,
+    predictions: LookupMap<(RewardId, AccountId), Prediction>,
+    auctions: UnorderedMap<AuctionId, Auction>,
+    last_auction_id: AuctionId,
+    dutch_sales: UnorderedMap<DutchSaleId, DutchSale>,
+    last_dutch_sale_id: DutchSaleId,
+    listings: UnorderedMap<ListingId, Listing>,
+    last_listing_id: ListingId,
+    marketplace_fee_bps: u16,
+    recipes: UnorderedMap<RecipeId, Recipe>,
+    titles: UnorderedMap<TitleId, TitleDefinition>,
+    purchased_titles: LookupMap<AccountId, Vec<TitleId>>,
+    equipped_titles: LookupMap<AccountId, TitleId>,
+    battle_pass: Option<BattlePass>,
+    battle_pass_season: SeasonId,
+    pass_progress: LookupMap<AccountId, PassProgress>,
+    trivia: UnorderedMap<TriviaId, Trivia>,
+    last_trivia_id: TriviaId,
+    trivia_guesses: LookupMap<(TriviaId, AccountId), TriviaGuess>,
+    polls: UnorderedMap<PollId, Poll>,
+    last_poll_id: PollId,
+    poll_locks: LookupMap<(PollId, AccountId), Points>,
+    referrers: LookupMap<AccountId, AccountId>,
+    referral_competitions: UnorderedMap<CompetitionId, ReferralCompetition>,
+    last_referral_competition_id: CompetitionId,
+    referral_counts: LookupMap<(CompetitionId, AccountId), u32>,
+    lucky_draw_config: LuckyDrawConfig,
+    lucky_draw_pool: Points,
+    milestones: Vec<Milestone>,
+    quest_rotation: Option<QuestRotation>,
+    jackpot_config: JackpotConfig,
+    jackpot_pool: Points,
+    personhood_config: Option<PersonhoodConfig>,
+    personhood_verifications: LookupMap<AccountId, Timestamp>,
+    tournaments: UnorderedMap<TournamentId, Tournament>,
+    last_tournament_id: TournamentId,
+    tournament_entries: LookupMap<AccountId, TournamentId>,
+    multiplier_events: Vec<MultiplierEvent>,
+    last_multiplier_event_id: MultiplierEventId,
+    gift_config: GiftConfig,
+    gift_usage: LookupMap<AccountId, DailyUsage>,
+    charity_campaigns: UnorderedMap<CampaignId, CharityCampaign>,
+    last_charity_campaign_id: CampaignId,
+    charity_burns: LookupMap<(CampaignId, AccountId), Points>,
+    user_ids: UnorderedSet<AccountId>,
+    pending_cleanups: UnorderedMap<CleanupTarget, PendingCleanup>,
+    wheel_weights: WheelWeights,
+    // Bytes still held back from the deposits of unregistered accounts
+    retained_storage: LookupMap<AccountId, StorageUsage>,
+    reward_tickets: LookupMap<RewardId, RewardTickets>,
+    event_only_history: bool,
+    // Bumped on every random draw, see `internal_random_number`
+    random_nonce: u64,
+    operation_log: OperationLog,
+    // Zero lets an account win any number of raffles in a row
+    repeat_winner_cooldown_ms: u64,
+    last_wins: LookupMap<AccountId, Timestamp>,
+    spending_limits: LookupMap<AccountId, SpendingLimits>,
+    // Base58 sha256 of the code approved for the next upgrade, by the owner
+    // before guardians are set up and by guardians afterwards
+    approved_upgrade: Option<String>,
+    // Re-registering does not grant `INIT_POINT` again
+    former_users: LookupSet<AccountId>,
+    // Poll locks, predictions, leading bids and listings of each account
+    open_escrows: LookupMap<AccountId, u32>,
+    // Top guilds by all-time contributions, sorted
+    guild_standings: Vec<(GuildId, Points)>,
+    // V1 rewards waiting for `migrate_legacy_rewards`
+    legacy_rewards: UnorderedMap<RewardId, RewardV1>,
 }
 
+/// Only written when created and finalized. The title lives in its own
+/// storage slot and tickets in `RewardTickets`, so purchases leave it alone.
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Reward {
-    title: String,
+    title: LazyOption<String>,
     price: Points,
     ended_at: Timestamp,
-    total_tickets: u64,
+    // Stays `None` when a finalized raffle had no tickets
     winner: Option<AccountId>,
-    tickets: TreeMap<u64, AccountId>,
+    status: RewardStatus,
+    draw: Option<RewardDraw>,
+}
+
+/// Ticket state of a reward, the only record a purchase rewrites.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RewardTickets {
+    total: u64,
+    // One `(cumulative_end, holder)` entry per purchase, so ends increase
+    purchases: Vector<(u64, AccountId)>,
 }
 
 #[derive(Serialize)]
 pub struct RewardOutput {
     title: String,
-    price: U64,
+    price: U128,
     ended_at: U64,
     total_tickets: U64,
     winner: Option<AccountId>,
+    no_winner: bool,
+    status: RewardStatus,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone)]
 pub struct User {
-    points: u64,
-    last_daily_claim: Timestamp,
-    last_free_spinwheel: Timestamp,
+    points: Points,
+    xp: u64,
+    season_xp: u64,
+    season: SeasonId,
+    activity: UserActivity,
+    achievements: u64,
+    tickets_bought: u64,
+    // Applied to the next spin, see `ItemEffect::OddsBooster`
+    odds_boost: u8,
+    // Bits of reached milestones, see `set_milestones`
+    milestones: u64,
+}
+
+/// Stored form of a user. A new layout is added as a variant and upgraded to
+/// `Current` when read, so users are never migrated all at once.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VUser {
+    Current(User),
+}
+
+impl From<VUser> for User {
+    fn from(user: VUser) -> Self {
+        match user {
+            VUser::Current(user) => user,
+        }
+    }
 }
 
 #[derive(Serialize)]
 pub struct UserOutput {
-    points: U64,
+    points: U128,
     last_daily_claim: U64,
     last_free_spinwheel: U64,
+    xp: U64,
+    level: u32,
+    season_xp: U64,
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     Users,
     Rewards,
-    Tickets { reward_id: RewardId },
+    // Ticket trees of V1 rewards, only read when migrating them
+    #[allow(dead_code)]
+    Tickets {
+        reward_id: RewardId,
+    },
+    Operators,
+    History,
+    UserHistory {
+        account_id_hash: CryptoHash,
+    },
+    SeasonSnapshots,
+    SeasonCarryOver,
+    StakingOptions,
+    Stakes,
+    StakesPerUser,
+    UserStakes {
+        account_id_hash: CryptoHash,
+    },
+    Blacklist,
+    UsersV2,
+    Guardians,
+    GuardianProposals,
+    PendingConfigChanges,
+    Treasurers,
+    RewardNearPrices,
+    StorageAccounts,
+    PointGenerators,
+    MembershipConfigs,
+    PendingOperations,
+    StakedNfts,
+    UserStakedNfts {
+        account_id_hash: CryptoHash,
+    },
+    MembershipVerifications,
+    AcceptedTokens,
+    Onboarders,
+    WalletLinks,
+    LinkedWallets,
+    PendingWalletLinks,
+    RewardUsdPrices,
+    ScheduledTasks,
+    SocialProfiles,
+    PartnerHooks,
+    Quests,
+    QuestCompletions,
+    Badges,
+    BadgeMints,
+    BridgeChains,
+    BridgeInTransfers,
+    SpinRelayers,
+    PoolStakeCheckpoints,
+    RewardProposers,
+    RewardProposals,
+    AchievementBonuses,
+    SeasonResults,
+    Guilds,
+    GuildMembers {
+        guild_id: GuildId,
+    },
+    GuildMemberships,
+    Lootboxes,
+    Items,
+    Inventories,
+    RewardItems,
+    Coupons,
+    ScratchBatches,
+    PendingWagers,
+    WagerUsage,
+    PredictionPools,
+    Predictions,
+    Auctions,
+    DutchSales,
+    Listings,
+    Recipes,
+    Titles,
+    PurchasedTitles,
+    EquippedTitles,
+    PassProgress,
+    Trivia,
+    TriviaGuesses,
+    Polls,
+    PollLocks,
+    Referrers,
+    ReferralCompetitions,
+    ReferralCounts,
+    PersonhoodVerifications,
+    Tournaments,
+    TournamentEntries,
+    GiftUsage,
+    CharityCampaigns,
+    CharityBurns,
+    UserIds,
+    RewardTitle {
+        reward_id: RewardId,
+    },
+    TicketRanges {
+        reward_id: RewardId,
+    },
+    PendingCleanups,
+    RetainedStorage,
+    MembershipContracts,
+    RewardTickets,
+    OperationIds,
+    OperationOrder,
+    LastWins,
+    SpendingLimits,
+    FormerUsers,
+    OpenEscrows,
+    RewardsV2,
 }
 
 #[near_bindgen]
 impl ArkanaCoreContract {
     #[init]
-    pub fn new(owner: AccountId, daily_claim_points: U64, spin_wheel_price: U64) -> Self {
-        Self {
+    pub fn new(owner: AccountId, daily_claim_points: U128, spin_wheel_price: U128) -> Self {
+        let mut this = Self {
             owner,
             daily_claim_points: daily_claim_points.0,
             spin_wheel_price: spin_wheel_price.0,
-            users: UnorderedMap::new(StorageKey::Users),
-            rewards: UnorderedMap::new(StorageKey::Rewards),
+            users: LookupMap::new(StorageKey::UsersV2),
+            rewards: UnorderedMap::new(StorageKey::RewardsV2),
             last_reward_id: 0,
-            membership_contracts: HashSet::new(),
+            membership_contracts: UnorderedSet::new(StorageKey::MembershipContracts),
             spinwheel_wr: 0,
-        }
+            operators: UnorderedSet::new(StorageKey::Operators),
+            history: LookupMap::new(StorageKey::History),
+            current_season: 0,
+            season_started_at: env::block_timestamp_ms(),
+            season_snapshots: LookupMap::new(StorageKey::SeasonSnapshots),
+            season_carry_over_bps: LookupMap::new(StorageKey::SeasonCarryOver),
+            next_season_carry_over_bps: MAX_BPS,
+            supply: SupplyStats::default(),
+            staking_options: UnorderedMap::new(StorageKey::StakingOptions),
+            early_unstake_penalty_bps: 0,
+            staking_emission_budget: 0,
+            stakes: UnorderedMap::new(StorageKey::Stakes),
+            user_stakes: LookupMap::new(StorageKey::StakesPerUser),
+            last_stake_id: 0,
+            pause_state: PauseState::default(),
+            blacklist: UnorderedMap::new(StorageKey::Blacklist),
+            legacy_users: UnorderedMap::new(StorageKey::Users),
+            guardians: UnorderedSet::new(StorageKey::Guardians),
+            guardian_threshold: 0,
+            guardian_proposals: UnorderedMap::new(StorageKey::GuardianProposals),
+            last_guardian_proposal_id: 0,
+            wheel_segments: default_wheel_segments(),
+            config_timelock_ms: 0,
+            pending_config_changes: UnorderedMap::new(StorageKey::PendingConfigChanges),
+            last_config_change_id: 0,
+            treasury_balance: 0,
+            treasurers: UnorderedSet::new(StorageKey::Treasurers),
+            registration_fee: 0,
+            spin_wheel_price_near: 0,
+            reward_near_prices: LookupMap::new(StorageKey::RewardNearPrices),
+            storage_accounts: LookupMap::new(StorageKey::StorageAccounts),
+            registration_storage_usage: 0,
+            rate_limit: RateLimitPolicy::default(),
+            point_generators: UnorderedMap::new(StorageKey::PointGenerators),
+            membership_configs: LookupMap::new(StorageKey::MembershipConfigs),
+            daily_claim_cooldown_ms: ONE_DAY,
+            free_spin_cooldown_ms: ONE_DAY,
+            pending_operations: UnorderedMap::new(StorageKey::PendingOperations),
+            last_operation_id: 0,
+            staked_nfts: LookupMap::new(StorageKey::StakedNfts),
+            membership_verifications: LookupMap::new(StorageKey::MembershipVerifications),
+            accepted_tokens: UnorderedMap::new(StorageKey::AcceptedTokens),
+            onboarders: UnorderedMap::new(StorageKey::Onboarders),
+            require_direct_signer: false,
+            wallet_links: LookupMap::new(StorageKey::WalletLinks),
+            linked_wallets: LookupMap::new(StorageKey::LinkedWallets),
+            pending_wallet_links: LookupMap::new(StorageKey::PendingWalletLinks),
+            price_oracle: None,
+            near_usd_price: None,
+            spin_wheel_price_usd: 0,
+            reward_usd_prices: LookupMap::new(StorageKey::RewardUsdPrices),
+            scheduled_tasks: UnorderedMap::new(StorageKey::ScheduledTasks),
+            last_task_id: 0,
+            social_db: "social.near".parse().unwrap(),
+            social_profiles: LookupMap::new(StorageKey::SocialProfiles),
+            partner_hooks: UnorderedMap::new(StorageKey::PartnerHooks),
+            quests: UnorderedMap::new(StorageKey::Quests),
+            quest_completions: LookupMap::new(StorageKey::QuestCompletions),
+            badge_config: None,
+            badges: UnorderedMap::new(StorageKey::Badges),
+            badge_mints: LookupMap::new(StorageKey::BadgeMints),
+            bridge_chains: UnorderedSet::new(StorageKey::BridgeChains),
+            bridge_in_transfers: LookupSet::new(StorageKey::BridgeInTransfers),
+            last_bridge_nonce: 0,
+            spin_relayers: LookupMap::new(StorageKey::SpinRelayers),
+            staking_pool: None,
+            pool_stake_checkpoints: LookupMap::new(StorageKey::PoolStakeCheckpoints),
+            reward_proposers: UnorderedSet::new(StorageKey::RewardProposers),
+            reward_proposals: UnorderedMap::new(StorageKey::RewardProposals),
+            last_reward_proposal_id: 0,
+            reward_approval_threshold: 1,
+            community_name: None,
+            factory_id: None,
+            protocol_fee: None,
+            protocol_fee_balance: 0,
+            achievement_bonuses: LookupMap::new(StorageKey::AchievementBonuses),
+            season_standings: Vec::new(),
+            season_prizes: Vec::new(),
+            season_results: LookupMap::new(StorageKey::SeasonResults),
+            guilds: UnorderedMap::new(StorageKey::Guilds),
+            guild_memberships: LookupMap::new(StorageKey::GuildMemberships),
+            last_guild_id: 0,
+            check_in_calendar: Vec::new(),
+            lootboxes: UnorderedMap::new(StorageKey::Lootboxes),
+            last_lootbox_id: 0,
+            items: UnorderedMap::new(StorageKey::Items),
+            inventories: LookupMap::new(StorageKey::Inventories),
+            reward_items: LookupMap::new(StorageKey::RewardItems),
+            coupons: LookupMap::new(StorageKey::Coupons),
+            scratch_batches: UnorderedMap::new(StorageKey::ScratchBatches),
+            last_scratch_batch_id: 0,
+            wager_config: WagerConfig::default(),
+            pending_wagers: LookupMap::new(StorageKey::PendingWagers),
+            wager_usage: LookupMap::new(StorageKey::WagerUsage),
+            house_bankroll: HouseBankroll::default(),
+            prediction_pools: LookupMap::new(StorageKey::PredictionPools),
+            predictions: LookupMap::new(StorageKey::Predictions),
+            auctions: UnorderedMap::new(StorageKey::Auctions),
+            last_auction_id: 0,
+            dutch_sales: UnorderedMap::new(StorageKey::DutchSales),
+            last_dutch_sale_id: 0,
+            listings: UnorderedMap::new(StorageKey::Listings),
+            last_listing_id: 0,
+            marketplace_fee_bps: 0,
+            recipes: UnorderedMap::new(StorageKey::Recipes),
+            titles: UnorderedMap::new(StorageKey::Titles),
+            purchased_titles: LookupMap::new(StorageKey::PurchasedTitles),
+            equipped_titles: LookupMap::new(StorageKey::EquippedTitles),
+            battle_pass: None,
+            battle_pass_season: 0,
+            pass_progress: LookupMap::new(StorageKey::PassProgress),
+            trivia: UnorderedMap::new(StorageKey::Trivia),
+            last_trivia_id: 0,
+            trivia_guesses: LookupMap::new(StorageKey::TriviaGuesses),
+            polls: UnorderedMap::new(StorageKey::Polls),
+            last_poll_id: 0,
+            poll_locks: LookupMap::new(StorageKey::PollLocks),
+            referrers: LookupMap::new(StorageKey::Referrers),
+            referral_competitions: UnorderedMap::new(StorageKey::ReferralCompetitions),
+            last_referral_competition_id: 0,
+            referral_counts: LookupMap::new(StorageKey::ReferralCounts),
+            lucky_draw_config: LuckyDrawConfig::default(),
+            lucky_draw_pool: 0,
+            milestones: vec![],
+            quest_rotation: None,
+            jackpot_config: JackpotConfig::default(),
+            jackpot_pool: 0,
+            personhood_config: None,
+            personhood_verifications: LookupMap::new(StorageKey::PersonhoodVerifications),
+            tournaments: UnorderedMap::new(StorageKey::Tournaments),
+            last_tournament_id: 0,
+            tournament_entries: LookupMap::new(StorageKey::TournamentEntries),
+            multiplier_events: vec![],
+            last_multiplier_event_id: 0,
+            gift_config: GiftConfig::default(),
+            gift_usage: LookupMap::new(StorageKey::GiftUsage),
+            charity_campaigns: UnorderedMap::new(StorageKey::CharityCampaigns),
+            last_charity_campaign_id: 0,
+            charity_burns: LookupMap::new(StorageKey::CharityBurns),
+            user_ids: UnorderedSet::new(StorageKey::UserIds),
+            pending_cleanups: UnorderedMap::new(StorageKey::PendingCleanups),
+            wheel_weights: WheelWeights::new(&default_wheel_segments()),
+            retained_storage: LookupMap::new(StorageKey::RetainedStorage),
+            reward_tickets: LookupMap::new(StorageKey::RewardTickets),
+            event_only_history: false,
+            random_nonce: 0,
+            operation_log: OperationLog::new(),
+            repeat_winner_cooldown_ms: 0,
+            last_wins: LookupMap::new(StorageKey::LastWins),
+            spending_limits: LookupMap::new(StorageKey::SpendingLimits),
+            approved_upgrade: None,
+            former_users: LookupSet::new(StorageKey::FormerUsers),
+            open_escrows: LookupMap::new(StorageKey::OpenEscrows),
+            guild_standings: Vec::new(),
+            legacy_rewards: UnorderedMap::new(StorageKey::Rewards),
+            claim_booster: ClaimBooster {
+                price: U128(0),
+                bonus_bps: 0,
+                duration_ms: U64(0),
+            },
+        };
+
+        this.internal_measure_registration_storage_usage();
+        write_state_version();
+
+        this
     }
 
     #[payable]
-    pub fn create_reward(&mut self, title: String, price: U64, ended_at: U64) -> RewardId {
-        let predecessor_id = env::predecessor_account_id();
+    pub fn create_reward(&mut self, title: String, price: U128, ended_at: U64) -> RewardId {
+        self.assert_owner();
 
-        if predecessor_id != self.owner {
-            panic!("Unauthorized");
-        }
+        let initial_storage_usage = env::storage_usage();
 
-        self.rewards.insert(
-            &(self.last_reward_id + 1),
-            &Reward {
-                title,
-                price: price.0,
-                ended_at: ended_at.0,
-                total_tickets: 0,
-                winner: None,
-                tickets: TreeMap::new(StorageKey::Tickets {
-                    reward_id: (self.last_reward_id + 1),
-                }),
-            },
-        );
+        self.internal_create_reward(title, price.0, ended_at.0);
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
 
-        self.last_reward_id += 1;
+        ArkanaEvent::admin_action(
+            "create_reward",
+            serde_json::json!({
+                "reward_id": U64(self.last_reward_id),
+                "price": price,
+                "ended_at": ended_at,
+            }),
+        );
 
         self.last_reward_id
     }
 
-    #[payable]
     pub fn buy_ticket(&mut self, reward_id: U64, amount: U64) -> (U64, U64) {
-        let predecessor_id = env::predecessor_account_id();
-
-        let mut reward = self.rewards.get(&reward_id.0).unwrap();
+        self.assert_not_paused(PauseFeature::Tickets);
 
-        let current_timestamp = env::block_timestamp_ms();
+        let predecessor_id = self.internal_player_id();
+        self.assert_not_blacklisted(&predecessor_id);
 
-        assert!(current_timestamp < reward.ended_at, "Reward has ended");
+        assert_valid_ticket_amount(amount.0);
+        let reward = self.internal_get_open_reward(reward_id.0);
 
-        let mut user = self.users.get(&predecessor_id).unwrap();
+        let mut user = self.internal_get_user(&predecessor_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
 
-        if user.points < reward.price * amount.0 {
+        let price = reward.price.safe_mul(Points::from(amount.0));
+        if user.points < price {
             panic!("Points insufficient");
         }
 
-        user.points -= reward.price * amount.0;
+        self.internal_spend_points(&predecessor_id, &mut user, price, SpendKind::Ticket);
 
-        reward
-            .tickets
-            .insert(&reward.total_tickets, &predecessor_id);
-        reward.total_tickets += amount.0;
+        self.internal_add_tickets(reward_id.0, &predecessor_id, amount.0);
+        self.internal_record_raffle_entries(&predecessor_id, &mut user, amount.0);
 
-        self.users.insert(&predecessor_id, &user);
-        self.rewards.insert(&reward_id.0, &reward);
+        self.internal_save_user(&predecessor_id, &user);
+        self.internal_update_storage_usage(&predecessor_id, initial_storage_usage);
 
         (reward_id, amount)
     }
 
-    pub fn finalize_reward(&mut self, reward_id: U64, force: bool) -> AccountId {
-        let mut reward = self.rewards.get(&reward_id.0).unwrap();
+    /// Draws the winner of an ended raffle. A raffle without tickets is
+    /// closed with no winner instead and returns `None`.
+    pub fn finalize_reward(&mut self, reward_id: U64, force: bool) -> Option<AccountId> {
+        self.assert_not_paused(PauseFeature::All);
+
+        let mut reward = self.internal_get_reward(reward_id.0);
 
         let predecessor_id = env::predecessor_account_id();
 
+        // Forcing only skips the end time, a reward is never drawn twice
         if !force || predecessor_id != self.owner {
             let current_timestamp = env::block_timestamp_ms();
 
-            assert!(reward.winner.is_none(), "Reward finalized");
-
             if reward.ended_at > current_timestamp {
                 panic!("Reward has not ended");
             }
         }
+        reward.set_status(RewardStatus::Drawing);
+
+        let tickets = self.reward_tickets.get(&reward_id.0).unwrap();
+        if tickets.total == 0 {
+            reward.set_status(RewardStatus::Finalized);
+            self.rewards.insert(&reward_id.0, &reward);
+            self.reward_items.remove(&reward_id.0);
+
+            self.internal_notify_partners(
+                HookEvent::RewardFinalized,
+                serde_json::json!({ "reward_id": reward_id, "winner": null }),
+            );
+
+            return None;
+        }
+
+        let (ticket, mut draw) = self.internal_draw_ticket(tickets.total);
+
+        // Walk forward from the drawn ticket, wrapping around, until a ticket
+        // held by an account that is neither blacklisted nor a recent winner
+        // is found. Without one the raffle closes with no winner.
+        let purchases = tickets.purchases.len();
+        let drawn = tickets.purchase_of(ticket);
+        let winner = (drawn..purchases)
+            .chain(0..drawn)
+            .map(|index| tickets.purchases.get(index).unwrap().1)
+            .find(|account_id| {
+                let eligible = !self.internal_is_blacklisted(account_id)
+                    && !self.internal_in_win_cooldown(account_id);
+                if !eligible && !draw.skipped.contains(account_id) {
+                    draw.skipped.push(account_id.clone());
+                }
+                eligible
+            });
+
+        ArkanaEvent::RewardDrawn(vec![RewardDrawnData {
+            reward_id,
+            random_seed: near_sdk::bs58::encode(&draw.random_seed).into_string(),
+            caller: draw.caller.clone(),
+            nonce: U64(draw.nonce),
+            total_tickets: U64(draw.total_tickets),
+            ticket: U64(ticket),
+            skipped: draw.skipped.clone(),
+            winner: winner.clone(),
+        }])
+        .emit();
+
+        reward.winner = winner.clone();
+        reward.draw = Some(draw);
+        reward.set_status(RewardStatus::Finalized);
+        self.rewards.insert(&reward_id.0, &reward);
 
-        let random_number = get_random_number(0) as u64 % reward.total_tickets;
+        let winner = match winner {
+            Some(winner) => winner,
+            None => {
+                self.reward_items.remove(&reward_id.0);
 
-        let key_winner = reward.tickets.floor_key(&random_number).unwrap();
-        let winner = reward.tickets.get(&key_winner).unwrap();
+                self.internal_notify_partners(
+                    HookEvent::RewardFinalized,
+                    serde_json::json!({ "reward_id": reward_id, "winner": null }),
+                );
+
+                return None;
+            }
+        };
+        self.internal_record_win(&winner);
+
+        // Tickets of unregistered accounts can still win
+        if let Some(mut user) = self.internal_find_user(&winner) {
+            if self.internal_unlock_achievement(&winner, &mut user, Achievement::FirstWin) {
+                self.internal_save_user(&winner, &user);
+            }
+
+            // The item's storage is covered by the contract
+            if let Some(item) = self.reward_items.remove(&reward_id.0) {
+                if self.items.get(&item.item_id).is_some() {
+                    self.internal_grant_item(&winner, &item.item_id, item.amount.0);
+                }
+            }
+        }
 
-        reward.winner = Some(winner.clone());
+        self.internal_notify_partners(
+            HookEvent::RewardFinalized,
+            serde_json::json!({ "reward_id": reward_id, "winner": winner }),
+        );
 
-        return winner;
+        Some(winner)
     }
 
+    /// Kept for existing clients, registers through `storage_deposit`.
     #[payable]
-    pub fn register_account(&mut self) {
+    pub fn register_account(&mut self) -> StorageBalance {
         let predecessor_id = env::predecessor_account_id();
-        if self.users.get(&predecessor_id).is_some() {
+        if self.internal_find_user(&predecessor_id).is_some() {
             panic!("Account already registered");
         }
 
-        self.users.insert(
-            &predecessor_id,
-            &User {
-                points: INIT_POINT,
-                last_daily_claim: 0,
-                last_free_spinwheel: 0,
-            },
-        );
+        self.storage_deposit(None, None)
     }
 
-    pub fn daily_claim_point(&mut self) -> Points {
-        let account_id = env::predecessor_account_id();
+    pub fn daily_claim_point(&mut self) -> U128 {
+        self.assert_not_paused(PauseFeature::Claims);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        self.assert_human_if_required(&account_id, true);
 
-        let mut user = self.users.get(&account_id).expect("User does not exist");
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
 
         let current_timestamp = env::block_timestamp_ms();
-        let delta_ms = current_timestamp - user.last_daily_claim;
+        let delta_ms = current_timestamp.saturating_sub(user.activity.last_daily_claim());
 
-        if delta_ms < ONE_DAY {
+        if delta_ms < self.daily_claim_cooldown_ms {
             panic!(
                 "Cannot claim, please wait {} seconds",
-                milli_to_seconds(ONE_DAY - delta_ms)
+                milli_to_seconds(self.daily_claim_cooldown_ms - delta_ms)
             );
         }
 
-        user.points += self.daily_claim_points;
-        user.last_daily_claim = current_timestamp;
+        let points = self.internal_daily_claim_points(&account_id, &user);
+        self.internal_issue_points(&account_id, &mut user, points);
+
+        user.activity
+            .set_claim_streak(self.internal_next_claim_streak(&user, current_timestamp));
+        self.internal_unlock_streak_achievements(&account_id, &mut user);
+        user.activity.set_last_daily_claim(current_timestamp);
+        self.internal_check_in(&account_id, &mut user);
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_add_pass_xp(&account_id, PassXpSource::Claim);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
 
-        self.users.insert(&account_id, &user);
+        self.internal_save_user(&account_id, &user);
 
-        user.points
+        U128(user.points)
+    }
+
+    pub fn play_spin_wheel(&mut self, is_free: bool) -> U128 {
+        let predecessor_id = self.internal_player_id();
+
+        U128(self.internal_play_spin_wheel(&predecessor_id, is_free))
     }
 
     #[payable]
-    pub fn play_spin_wheel(&mut self, is_free: bool) -> Points {
-        let predecessor_id = env::predecessor_account_id();
+    pub fn add_membership_nft_contract(&mut self, contract_id: AccountId) {
+        assert_one_yocto();
 
-        let mut user = self.users.get(&predecessor_id).unwrap();
+        self.assert_owner();
 
-        if is_free {
-            let current_timestamp = env::block_timestamp_ms();
-            let delta_ms = current_timestamp - user.last_free_spinwheel;
+        self.membership_contracts.insert(&contract_id);
 
-            if delta_ms < ONE_DAY {
-                panic!(
-                    "Cannot play spin wheel for free, please wait {} seconds",
-                    milli_to_seconds(ONE_DAY - delta_ms)
-                );
-            }
-            user.last_free_spinwheel = current_timestamp;
-        } else {
-            if user.points < self.spin_wheel_price {
-                panic!("Cannot play, user points insufficient");
-            }
+        ArkanaEvent::admin_action(
+            "add_membership_nft_contract",
+            serde_json::json!({ "contract_id": contract_id }),
+        );
+    }
 
-            user.points -= self.spin_wheel_price;
-        }
+    #[payable]
+    pub fn remove_membership_nft_contract(&mut self, contract_id: AccountId) {
+        assert_one_yocto();
 
-        let points = [1, 3, 7, 9, 12, 15];
-        let weights = [
-            50u16,
-            80u16,
-            70u16,
-            20u16 + (self.spinwheel_wr as u16 * 3) / 10,
-            10u16 + (self.spinwheel_wr as u16 * 2) / 10,
-            2u16 + (self.spinwheel_wr as u16 * 1) / 10,
-        ];
+        self.assert_owner();
 
-        let mut cumulative_weights: [u16; 6] = [0; 6];
+        self.membership_contracts.remove(&contract_id);
+        self.membership_configs.remove(&contract_id);
 
-        cumulative_weights[0] = weights[0];
-        for i in 1..weights.len() {
-            cumulative_weights[i] = weights[i] + cumulative_weights[i - 1];
-        }
+        ArkanaEvent::admin_action(
+            "remove_membership_nft_contract",
+            serde_json::json!({ "contract_id": contract_id }),
+        );
+    }
 
-        let total_weights: u16 = cumulative_weights[5]; // last index
-        let random_number = get_random_number(0) as u16 % total_weights;
-        let mut result = 0;
+    /// Callable by whitelisted membership contracts, subject to their config,
+    /// and by point generators within their daily cap. A repeated
+    /// `operation_id` is rejected, see `is_operation_processed`.
+    pub fn generate_points(
+        &mut self,
+        account_id: AccountId,
+        points: U128,
+        operation_id: Option<String>,
+    ) -> U128 {
+        self.assert_not_paused(PauseFeature::Claims);
 
-        for i in 0..weights.len() {
-            if cumulative_weights[i] >= random_number {
-                result = points[i];
-                break;
-            }
-        }
+        let predecessor_id = env::predecessor_account_id();
+        self.internal_record_operation(operation_id);
 
-        if result > 5 {
-            self.spinwheel_wr = 0;
+        let points = if self.membership_contracts.contains(&predecessor_id) {
+            self.internal_apply_membership_config(
+                &predecessor_id,
+                MembershipMethod::GeneratePoints,
+                points.0,
+            )
         } else {
-            self.spinwheel_wr += 1;
-        }
+            self.internal_consume_generator_cap(&predecessor_id, points.0);
+            points.0
+        };
 
-        user.points += result;
+        let mut user = self.internal_get_user(&account_id);
 
-        self.users.insert(&predecessor_id, &user);
+        self.internal_issue_points(&account_id, &mut user, points);
 
-        result
+        self.internal_save_user(&account_id, &user);
+
+        U128(user.points)
     }
 
-    pub fn add_membership_nft_contract(&mut self, contract_id: AccountId) {
-        let predecessor_id = env::predecessor_account_id();
+    #[payable]
+    pub fn add_operator(&mut self, account_id: AccountId) {
+        assert_one_yocto();
 
-        if predecessor_id != self.owner {
-            panic!("Unauthorized");
-        }
+        self.assert_owner();
 
-        self.membership_contracts.insert(contract_id);
+        if self.operators.insert(&account_id) {
+            ArkanaEvent::OperatorAdded(vec![AccountData { account_id }]).emit();
+        }
     }
 
-    pub fn remove_membership_nft_contract(&mut self, contract_id: AccountId) {
-        let predecessor_id = env::predecessor_account_id();
+    #[payable]
+    pub fn remove_operator(&mut self, account_id: AccountId) {
+        assert_one_yocto();
 
-        if predecessor_id != self.owner {
-            panic!("Unauthorized");
-        }
+        self.assert_owner();
 
-        self.membership_contracts.remove(&contract_id);
+        if self.operators.remove(&account_id) {
+            ArkanaEvent::OperatorRemoved(vec![AccountData { account_id }]).emit();
+        }
     }
 
-    pub fn generate_points(&mut self, account_id: AccountId, points: U64) -> U64 {
-        let predecessor_id = env::predecessor_account_id();
+    /// Credits or deducts points by hand. A repeated `operation_id` is
+    /// rejected, see `is_operation_processed`.
+    pub fn adjust_points(
+        &mut self,
+        account_id: AccountId,
+        delta: I128,
+        reason: String,
+        operation_id: Option<String>,
+    ) -> U128 {
+        self.assert_owner_or_operator();
 
-        if !self.membership_contracts.contains(&predecessor_id) {
-            panic!("Unauthorized");
-        }
+        assert!(delta.0 != 0, "Delta cannot be zero");
+        assert!(!reason.is_empty(), "Reason is required");
+        self.internal_record_operation(operation_id);
 
-        let mut user = self.users.get(&account_id).unwrap();
+        let mut user = self.internal_get_user(&account_id);
+
+        // Deductions are clamped at zero so an exploiter who already spent
+        // the points cannot end up with a negative balance
+        let applied_delta = if delta.0 < 0 {
+            let deducted = std::cmp::min(delta.0.unsigned_abs(), user.points);
+            self.internal_burn_points(&mut user, deducted);
+            -(deducted as i128)
+        } else {
+            self.internal_issue_points(&account_id, &mut user, delta.0 as Points);
+            delta.0
+        };
 
-        user.points += points.0;
+        self.internal_save_user(&account_id, &user);
+        self.internal_record_history(
+            &account_id,
+            HistoryKind::Adjustment,
+            applied_delta,
+            Some(reason.clone()),
+        );
 
-        self.users.insert(&account_id, &user);
+        ArkanaEvent::PointsAdjusted(vec![PointsAdjustedData {
+            account_id,
+            operator_id: env::predecessor_account_id(),
+            delta: I128(applied_delta),
+            points: U128(user.points),
+            reason,
+        }])
+        .emit();
 
-        U64(user.points)
+        U128(user.points)
     }
 
     // View Functions
     pub fn get_user(&self, account_id: AccountId) -> UserOutput {
-        let user = self.users.get(&account_id).expect("User does not exist");
+        let user = self
+            .internal_find_user(&account_id)
+            .expect("User does not exist");
         UserOutput {
-            points: U64(user.points),
-            last_daily_claim: U64(user.last_daily_claim),
-            last_free_spinwheel: U64(user.last_free_spinwheel),
+            points: U128(self.internal_settled_points(&user)),
+            last_daily_claim: U64(user.activity.last_daily_claim()),
+            last_free_spinwheel: U64(user.activity.last_free_spin()),
+            xp: U64(user.xp),
+            level: user.level(),
+            season_xp: U64(if user.season == self.current_season {
+                user.season_xp
+            } else {
+                0
+            }),
         }
     }
 
+    pub fn get_operators(&self) -> Vec<AccountId> {
+        self.operators.to_vec()
+    }
+
     pub fn get_reward(&self, reward_id: U64) -> RewardOutput {
-        let reward = self.rewards.get(&reward_id.0).unwrap();
+        let reward = self.internal_get_reward(reward_id.0);
 
         RewardOutput {
-            title: reward.title,
-            price: U64(reward.price),
+            title: reward.title(),
+            price: U128(reward.price),
             ended_at: U64(reward.ended_at),
-            total_tickets: U64(reward.total_tickets),
+            total_tickets: U64(self.reward_tickets.get(&reward_id.0).unwrap().total),
+            no_winner: reward.has_no_winner(),
             winner: reward.winner,
+            status: reward.status,
+        }
+    }
+}
+
+impl User {
+    pub(crate) fn new(season: SeasonId) -> Self {
+        Self {
+            points: INIT_POINT,
+            xp: 0,
+            season_xp: 0,
+            season,
+            activity: UserActivity::default(),
+            achievements: 0,
+            tickets_bought: 0,
+            odds_boost: 0,
+            milestones: 0,
         }
     }
+
+    pub(crate) fn level(&self) -> u32 {
+        (self.xp / XP_PER_LEVEL) as u32
+    }
+}
+
+impl Reward {
+    pub(crate) fn has_no_winner(&self) -> bool {
+        self.status == RewardStatus::Finalized && self.winner.is_none()
+    }
+
+    pub(crate) fn title(&self) -> String {
+        self.title.get().unwrap_or_default()
+    }
 }
 
-fn get_random_number(shift_amount: u32) -> u32 {
-    let mut seed = env::random_seed();
-    let seed_len = seed.len();
-    let mut arr: [u8; 4] = Default::default();
-    seed.rotate_left(shift_amount as usize % seed_len);
-    arr.copy_from_slice(&seed[..4]);
-    u32::from_le_bytes(arr)
+impl RewardTickets {
+    pub(crate) fn new(reward_id: RewardId) -> Self {
+        Self {
+            total: 0,
+            purchases: Vector::new(StorageKey::TicketRanges { reward_id }),
+        }
+    }
+
+    pub(crate) fn add(&mut self, account_id: &AccountId, amount: u64) {
+        self.total = self.total.safe_add(amount);
+        self.purchases.push(&(self.total, account_id.clone()));
+    }
+
+    /// Index of the purchase holding `ticket`, by binary search over the
+    /// cumulative ends.
+    pub(crate) fn purchase_of(&self, ticket: u64) -> u64 {
+        let (mut low, mut high) = (0, self.purchases.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.purchases.get(mid).unwrap().0 <= ticket {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
 }
 
 fn milli_to_seconds(ms: u64) -> u64 {
@@ -344,11 +1171,17 @@ fn milli_to_seconds(ms: u64) -> u64 {
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use near_sdk::collections::TreeMap;
+    use near_sdk::json_types::U128;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::testing_env;
 
     use super::*;
 
+    const STORAGE_DEPOSIT: Balance = 100_000_000_000_000_000_000_000;
+
     // Allows for modifying the environment of the mocked blockchain
     fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
         let mut builder = VMContextBuilder::new();
@@ -358,4 +1191,4308 @@ mod tests {
             .predecessor_account_id(predecessor_account_id);
         builder
     }
+
+    fn setup_contract() -> ArkanaCoreContract {
+        testing_env!(get_context(accounts(0)).build());
+        ArkanaCoreContract::new(accounts(0), U128(10), U128(5))
+    }
+
+    fn register(contract: &mut ArkanaCoreContract, account_id: AccountId) {
+        testing_env!(get_context(account_id)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.register_account();
+    }
+
+    #[test]
+    fn test_adjust_points_deducts_and_records_history() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        let points = contract.adjust_points(accounts(1), I128(-10), "exploit".to_string(), None);
+        assert_eq!(points.0, INIT_POINT - 10);
+
+        let history = contract.get_user_history(accounts(1), None, None);
+        assert_eq!(history.items.len(), 1);
+        let entry = serde_json::to_value(&history.items[0]).unwrap();
+        assert_eq!(entry["delta"], "-10");
+        assert_eq!(entry["kind"], "adjustment");
+    }
+
+    #[test]
+    fn test_adjust_points_clamps_at_zero() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        let points = contract.adjust_points(accounts(1), I128(-1000), "exploit".to_string(), None);
+        assert_eq!(points.0, 0);
+    }
+
+    #[test]
+    fn test_operator_can_adjust_points() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_operator(accounts(2));
+
+        testing_env!(get_context(accounts(2)).build());
+        let points = contract.adjust_points(accounts(1), I128(5), "bug bounty".to_string(), None);
+        assert_eq!(points.0, INIT_POINT + 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_adjust_points_unauthorized() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.adjust_points(accounts(1), I128(5), "self".to_string(), None);
+    }
+
+    #[test]
+    fn test_season_snapshot_settled_lazily() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        assert_eq!(contract.start_new_season(), 1);
+
+        // Before the user interacts again the snapshot is served from the live record
+        let snapshot = contract.get_season_snapshot(accounts(1), 0).unwrap();
+        let snapshot = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(snapshot["points"], (INIT_POINT + 10).to_string());
+        assert_eq!(snapshot["season_xp"], "10");
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 2_000_000)
+            .build());
+        contract.daily_claim_point();
+
+        let snapshot = contract.get_season_snapshot(accounts(1), 0).unwrap();
+        let snapshot = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(snapshot["points"], (INIT_POINT + 10).to_string());
+
+        let user = serde_json::to_value(contract.get_user(accounts(1))).unwrap();
+        assert_eq!(user["season_xp"], "10");
+        assert_eq!(user["xp"], "20");
+        assert!(contract.get_season_snapshot(accounts(1), 1).is_none());
+    }
+
+    #[test]
+    fn test_season_prizes_distributed_in_chunks() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_season_prizes(vec![U128(100), U128(50)]);
+
+        for day in 1..=2 {
+            testing_env!(get_context(accounts(2))
+                .block_timestamp(ONE_DAY * day * 1_000_000)
+                .build());
+            contract.daily_claim_point();
+        }
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+
+        let standings = serde_json::to_value(contract.get_season_standings()).unwrap();
+        assert_eq!(standings[0]["account_id"], accounts(2).to_string());
+        assert_eq!(standings[1]["prize"], "50");
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.start_new_season();
+        assert!(contract.get_season_standings().is_empty());
+
+        assert_eq!(contract.distribute_season_prizes(0, Some(1)), 1);
+        assert_eq!(
+            contract.internal_find_user(&accounts(2)).unwrap().points,
+            INIT_POINT + 120
+        );
+        assert_eq!(contract.distribute_season_prizes(0, None), 0);
+        assert_eq!(
+            contract.internal_find_user(&accounts(1)).unwrap().points,
+            INIT_POINT + 60
+        );
+        assert_eq!(
+            contract.internal_find_user(&accounts(1)).unwrap().season_xp,
+            0
+        );
+
+        let result = serde_json::to_value(contract.get_season_result(0).unwrap()).unwrap();
+        assert_eq!(result["distributed"], 2);
+    }
+
+    #[test]
+    fn test_guild_treasury_buys_tickets_for_members() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        let guild_id = contract.create_guild("Arkanauts".to_string());
+        contract.contribute_to_guild(U128(10));
+
+        testing_env!(get_context(accounts(2)).build());
+        contract.join_guild(guild_id);
+        contract.contribute_to_guild(U128(5));
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(contract.buy_guild_tickets(U64(1), U64(3)).0, 12);
+
+        let tickets = contract.reward_tickets.get(&1).unwrap();
+        assert_eq!(tickets.total, 6);
+        assert_eq!(tickets.tickets_of(&accounts(2)), 3);
+
+        let guild = serde_json::to_value(contract.get_guild(guild_id).unwrap()).unwrap();
+        assert_eq!(guild["treasury"], "3");
+        assert_eq!(guild["contributed"], "15");
+        assert_eq!(guild["member_count"], "2");
+    }
+
+    #[test]
+    fn test_guild_leaderboard_ranks_contributions() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(1)).build());
+        let first_id = contract.create_guild("Arkanauts".to_string());
+        contract.contribute_to_guild(U128(5));
+
+        testing_env!(get_context(accounts(2)).build());
+        let second_id = contract.create_guild("Voyagers".to_string());
+        contract.contribute_to_guild(U128(8));
+
+        let leaderboard = serde_json::to_value(contract.get_guild_leaderboard(None)).unwrap();
+        assert_eq!(leaderboard[0]["guild_id"], second_id.0.to_string());
+        assert_eq!(leaderboard[1]["guild_id"], first_id.0.to_string());
+
+        contract.leave_guild();
+        let leaderboard = serde_json::to_value(contract.get_guild_leaderboard(None)).unwrap();
+        assert_eq!(leaderboard.as_array().unwrap().len(), 1);
+        assert_eq!(leaderboard[0]["guild_id"], first_id.0.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_guild_member_cannot_spend_treasury() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(1)).build());
+        let guild_id = contract.create_guild("Arkanauts".to_string());
+
+        testing_env!(get_context(accounts(2)).build());
+        contract.join_guild(guild_id);
+        contract.buy_guild_tickets(U64(1), U64(1));
+    }
+
+    #[test]
+    fn test_check_in_calendar_escalates_and_resets_monthly() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        // 2024-01-30, 2024-01-31 and 2024-02-01 in UTC
+        let first_day = 19_752;
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .block_timestamp(first_day * ONE_DAY * 1_000_000)
+            .build());
+        contract.create_reward(
+            "Merch".to_string(),
+            U128(1),
+            U64((first_day + 30) * ONE_DAY),
+        );
+        contract.set_check_in_calendar(vec![
+            CheckInDay {
+                points: U128(1),
+                bonus: None,
+            },
+            CheckInDay {
+                points: U128(2),
+                bonus: Some(CheckInBonus::Tickets {
+                    reward_id: U64(1),
+                    amount: U64(3),
+                }),
+            },
+        ]);
+
+        for day in first_day..first_day + 3 {
+            testing_env!(get_context(accounts(1))
+                .block_timestamp(day * ONE_DAY * 1_000_000)
+                .build());
+            contract.daily_claim_point();
+        }
+
+        let user = contract.internal_find_user(&accounts(1)).unwrap();
+        assert_eq!(user.activity.check_in_streak(), 1);
+        assert_eq!(user.points, INIT_POINT + 30 + 1 + 2 + 1);
+        assert_eq!(contract.reward_tickets.get(&1).unwrap().total, 3);
+
+        let calendar = serde_json::to_value(contract.get_check_in_calendar(accounts(1))).unwrap();
+        assert_eq!(calendar["month"], 2);
+        assert_eq!(calendar["checked_in_today"], true);
+        assert_eq!(calendar["days"][0]["claimed"], true);
+        assert_eq!(calendar["days"][1]["claimed"], false);
+    }
+
+    fn create_points_lootbox(contract: &mut ArkanaCoreContract, max_supply: Option<U64>) -> U64 {
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+        contract.create_lootbox(
+            "Starter box".to_string(),
+            Some(U128(10)),
+            None,
+            vec![
+                LootboxEntry {
+                    prize: LootboxPrize::Points { amount: U128(25) },
+                    weight: 1,
+                },
+                // Never drawn once the reward has ended
+                LootboxEntry {
+                    prize: LootboxPrize::Tickets {
+                        reward_id: U64(1),
+                        amount: U64(1),
+                    },
+                    weight: 1_000,
+                },
+            ],
+            max_supply,
+        )
+    }
+
+    #[test]
+    fn test_open_lootbox_for_points() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let lootbox_id = create_points_lootbox(&mut contract, None);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(
+            contract.open_lootbox(lootbox_id),
+            LootboxPrize::Points { amount: U128(25) }
+        );
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 15);
+        assert_eq!(contract.supply.spent_on_lootboxes, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lootbox is sold out")]
+    fn test_lootbox_supply_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let lootbox_id = create_points_lootbox(&mut contract, Some(U64(1)));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.open_lootbox(lootbox_id);
+        contract.open_lootbox(lootbox_id);
+    }
+
+    #[test]
+    fn test_lootbox_item_used_for_free_spin() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.set_item(
+            "spin_token".to_string(),
+            Some(ItemDefinition {
+                name: "Spin token".to_string(),
+                effect: ItemEffect::FreeSpin,
+                media: None,
+            }),
+        );
+        let prize = LootboxPrize::Item {
+            item_id: "spin_token".to_string(),
+            amount: U64(2),
+        };
+        let lootbox_id = contract.create_lootbox(
+            "Token box".to_string(),
+            Some(U128(5)),
+            None,
+            vec![LootboxEntry {
+                prize: prize.clone(),
+                weight: 1,
+            }],
+            None,
+        );
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetWheelSegments {
+            segments: vec![WheelSegment {
+                points: U128(4),
+                weight: 1,
+                boost_weight: 0,
+                resets_boost: true,
+                item: None,
+            }],
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(contract.open_lootbox(lootbox_id), prize);
+        assert_eq!(contract.use_item("spin_token".to_string()), Some(U128(4)));
+
+        let inventory = serde_json::to_value(contract.get_inventory(accounts(1))).unwrap();
+        assert_eq!(inventory[0]["quantity"], "1");
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 5 + 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Claim streak is not at risk")]
+    fn test_streak_freeze_requires_missed_day() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.set_item(
+            "freeze".to_string(),
+            Some(ItemDefinition {
+                name: "Streak freeze".to_string(),
+                effect: ItemEffect::StreakFreeze,
+                media: None,
+            }),
+        );
+        contract.internal_grant_item(&accounts(1), &"freeze".to_string(), 1);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+        contract.use_item("freeze".to_string());
+    }
+
+    #[test]
+    fn test_redeem_coupon_once() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let code_hash = near_sdk::json_types::Base64VecU8(env::sha256(b"ETHDENVER-7Q2X"));
+        contract.add_coupons(vec![CouponInput {
+            code_hash: code_hash.clone(),
+            reward: CouponReward::Points { amount: U128(40) },
+            expires_at: None,
+        }]);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.redeem_coupon("ETHDENVER-7Q2X".to_string());
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 40);
+
+        let coupon = serde_json::to_value(contract.get_coupon(code_hash).unwrap()).unwrap();
+        assert_eq!(coupon["redeemed_by"], accounts(1).to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Coupon already redeemed")]
+    fn test_coupon_cannot_be_redeemed_twice() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.add_coupons(vec![CouponInput {
+            code_hash: near_sdk::json_types::Base64VecU8(env::sha256(b"CODE")),
+            reward: CouponReward::Points { amount: U128(40) },
+            expires_at: None,
+        }]);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.redeem_coupon("CODE".to_string());
+        testing_env!(get_context(accounts(2)).build());
+        contract.redeem_coupon("CODE".to_string());
+    }
+
+    #[test]
+    fn test_scratch_batch_pays_out_fixed_pool() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let batch_id = contract.create_scratch_batch(
+            "Launch batch".to_string(),
+            U128(5),
+            vec![
+                ScratchTier {
+                    prize: U128(0),
+                    count: 2,
+                },
+                ScratchTier {
+                    prize: U128(20),
+                    count: 1,
+                },
+            ],
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        let won: Points = (0..3).map(|_| contract.buy_scratch_card(batch_id).0).sum();
+        assert_eq!(won, 20);
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 15 + 20
+        );
+        let batches = serde_json::to_value(contract.get_scratch_batches()).unwrap();
+        assert_eq!(batches[0]["remaining"], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Scratch cards are sold out")]
+    fn test_scratch_batch_sold_out() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let batch_id = contract.create_scratch_batch(
+            "Tiny batch".to_string(),
+            U128(5),
+            vec![ScratchTier {
+                prize: U128(1),
+                count: 1,
+            }],
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_scratch_card(batch_id);
+        contract.buy_scratch_card(batch_id);
+    }
+
+    #[test]
+    fn test_coin_flip_settled_in_later_block() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_wager_config(WagerConfig {
+            house_edge_bps: 500,
+            daily_stake_limit: U128(100),
+        });
+        contract.fund_house_bankroll(U128(18));
+
+        testing_env!(get_context(accounts(1)).block_index(10).build());
+        assert_eq!(contract.coin_flip(U128(20), CoinSide::Heads).0, 38);
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 20);
+
+        testing_env!(get_context(accounts(2)).block_index(11).build());
+        let outcome = serde_json::to_value(contract.settle_wager(accounts(1))).unwrap();
+        let payout = if outcome["won"] == true { 38 } else { 0 };
+        assert_eq!(outcome["payout"], payout.to_string());
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 20 + payout
+        );
+        assert!(contract.get_pending_wager(accounts(1)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Daily stake limit exceeded")]
+    fn test_coin_flip_daily_stake_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_wager_config(WagerConfig {
+            house_edge_bps: 0,
+            daily_stake_limit: U128(20),
+        });
+        contract.fund_house_bankroll(U128(100));
+
+        testing_env!(get_context(accounts(1)).block_index(10).build());
+        contract.coin_flip(U128(15), CoinSide::Tails);
+        testing_env!(get_context(accounts(1)).block_index(11).build());
+        contract.settle_wager(accounts(1));
+        contract.coin_flip(U128(15), CoinSide::Tails);
+    }
+
+    #[test]
+    fn test_roll_dice_payout_scales_with_odds() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_wager_config(WagerConfig {
+            house_edge_bps: 500,
+            daily_stake_limit: U128(100),
+        });
+        contract.fund_house_bankroll(U128(1_000));
+
+        // 25% odds pay 4x the stake minus the 5% edge
+        testing_env!(get_context(accounts(1)).block_index(10).build());
+        assert_eq!(contract.roll_dice(U128(20), 1, 25).0, 76);
+
+        let bankroll = serde_json::to_value(contract.get_house_bankroll()).unwrap();
+        assert_eq!(bankroll["balance"], "1020");
+        assert_eq!(bankroll["reserved"], "76");
+
+        testing_env!(get_context(accounts(1)).block_index(11).build());
+        contract.settle_wager(accounts(1));
+        let bankroll = serde_json::to_value(contract.get_house_bankroll()).unwrap();
+        assert_eq!(bankroll["reserved"], "0");
+    }
+
+    #[test]
+    #[should_panic(expected = "House bankroll cannot cover the payout")]
+    fn test_wager_bounded_by_bankroll() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_wager_config(WagerConfig {
+            house_edge_bps: 0,
+            daily_stake_limit: U128(100),
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.roll_dice(U128(10), 1, 50);
+    }
+
+    #[test]
+    fn test_prediction_pool_pays_out_pari_mutuel() {
+        let mut contract = setup_contract();
+        for account_id in [accounts(1), accounts(2), accounts(3)] {
+            register(&mut contract, account_id);
+        }
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+        contract.create_prediction_pool(U64(1), vec![U64(5)], U64(ONE_DAY / 2), 1_000);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.predict(U64(1), 1, U128(10));
+        testing_env!(get_context(accounts(2)).build());
+        contract.predict(U64(1), 1, U128(5));
+        testing_env!(get_context(accounts(3)).build());
+        contract.predict(U64(1), 0, U128(15));
+        contract.buy_ticket(U64(1), U64(6));
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.resolve_prediction_pool(U64(1)), 1);
+        assert_eq!(contract.claim_prediction(U64(1)).0, 0);
+
+        // 30 staked, 3 kept as fee, split 2:1 among the winners
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.claim_prediction(U64(1)).0, 18);
+        testing_env!(get_context(accounts(2))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.claim_prediction(U64(1)).0, 9);
+    }
+
+    #[test]
+    fn test_auction_refunds_outbid_and_extends() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let auction_id = contract.create_auction(
+            SalePrize {
+                title: "Signed jersey".to_string(),
+                item: None,
+                proceeds_to: None,
+            },
+            U128(5),
+            U128(2),
+            U64(ONE_DAY),
+            U64(ONE_DAY / 24),
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.bid_auction(auction_id, U128(5));
+
+        // A late bid pushes the end back
+        let late = ONE_DAY - 60_000;
+        testing_env!(get_context(accounts(2))
+            .block_timestamp(late * 1_000_000)
+            .build());
+        contract.bid_auction(auction_id, U128(8));
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let auction = serde_json::to_value(&contract.get_auctions(None, None).items[0]).unwrap();
+        assert_eq!(auction["ends_at"], (late + ONE_DAY / 24).to_string());
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp((late + ONE_DAY / 24) * 1_000_000)
+            .build());
+        assert_eq!(contract.finalize_auction(auction_id), Some(accounts(2)));
+        assert_eq!(contract.get_user(accounts(2)).points.0, INIT_POINT - 8);
+        assert_eq!(contract.supply.burned, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bid must be at least 7")]
+    fn test_auction_minimum_increment() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let auction_id = contract.create_auction(
+            SalePrize {
+                title: "Signed jersey".to_string(),
+                item: None,
+                proceeds_to: None,
+            },
+            U128(5),
+            U128(2),
+            U64(ONE_DAY),
+            U64(0),
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.bid_auction(auction_id, U128(5));
+        testing_env!(get_context(accounts(2)).build());
+        contract.bid_auction(auction_id, U128(6));
+    }
+
+    #[test]
+    fn test_dutch_sale_price_declines_to_floor() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let sale_id = contract.create_dutch_sale(
+            SalePrize {
+                title: "Leftover hoodie".to_string(),
+                item: None,
+                proceeds_to: Some(accounts(2)),
+            },
+            U128(20),
+            U128(4),
+            U64(0),
+            U64(ONE_DAY),
+        );
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY / 4 * 1_000_000)
+            .build());
+        let sales = serde_json::to_value(contract.get_dutch_sales(None, None).items).unwrap();
+        assert_eq!(sales[0]["current_price"], "16");
+        assert_eq!(contract.buy_dutch_sale(sale_id, U128(16)).0, 16);
+
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 16);
+        assert_eq!(contract.get_user(accounts(2)).points.0, INIT_POINT + 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dutch sale already sold")]
+    fn test_dutch_sale_sold_once() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let sale_id = contract.create_dutch_sale(
+            SalePrize {
+                title: "Leftover hoodie".to_string(),
+                item: None,
+                proceeds_to: None,
+            },
+            U128(20),
+            U128(4),
+            U64(0),
+            U64(ONE_DAY),
+        );
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.buy_dutch_sale(sale_id, U128(4));
+        testing_env!(get_context(accounts(2))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.buy_dutch_sale(sale_id, U128(4));
+    }
+
+    #[test]
+    fn test_marketplace_partial_buy_pays_seller_minus_fee() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.set_item(
+            "badge".to_string(),
+            Some(ItemDefinition {
+                name: "Badge".to_string(),
+                effect: ItemEffect::Collectible,
+                media: None,
+            }),
+        );
+        contract.set_marketplace_fee(1_000);
+        contract.internal_grant_item(&accounts(1), &"badge".to_string(), 3);
+
+        testing_env!(get_context(accounts(1)).build());
+        let listing_id = contract.list_item("badge".to_string(), U64(3), U128(5));
+        assert!(contract.get_inventory(accounts(1)).is_empty());
+
+        testing_env!(get_context(accounts(2)).build());
+        assert_eq!(contract.buy_listing(listing_id, U64(2)).0, 10);
+
+        let inventory = serde_json::to_value(contract.get_inventory(accounts(2))).unwrap();
+        assert_eq!(inventory[0]["quantity"], "2");
+        assert_eq!(contract.get_user(accounts(2)).points.0, INIT_POINT - 10);
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 9);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.cancel_listing(listing_id);
+        let inventory = serde_json::to_value(contract.get_inventory(accounts(1))).unwrap();
+        assert_eq!(inventory[0]["quantity"], "1");
+        assert!(contract.get_listings(None, None).items.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough items")]
+    fn test_marketplace_cannot_list_unowned_items() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.list_item("badge".to_string(), U64(1), U128(5));
+    }
+
+    #[test]
+    fn test_craft_consumes_inputs_and_points() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        for item_id in ["shard", "gem"] {
+            contract.set_item(
+                item_id.to_string(),
+                Some(ItemDefinition {
+                    name: item_id.to_string(),
+                    effect: ItemEffect::Collectible,
+                    media: None,
+                }),
+            );
+        }
+        let result = CraftResult::Item {
+            item_id: "gem".to_string(),
+            amount: U64(1),
+        };
+        contract.set_recipe(
+            "gem".to_string(),
+            Some(Recipe {
+                inputs: vec![ItemGrant {
+                    item_id: "shard".to_string(),
+                    amount: U64(3),
+                }],
+                points: U128(5),
+                result: result.clone(),
+            }),
+        );
+        contract.internal_grant_item(&accounts(1), &"shard".to_string(), 4);
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(contract.craft("gem".to_string()), result);
+
+        let inventory = serde_json::to_value(contract.get_inventory(accounts(1))).unwrap();
+        assert_eq!(inventory[0]["item_id"], "shard");
+        assert_eq!(inventory[0]["quantity"], "1");
+        assert_eq!(inventory[1]["item_id"], "gem");
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 5);
+    }
+
+    #[test]
+    fn test_titles_from_achievements_and_purchases() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.set_title(
+            "early_bird".to_string(),
+            Some(TitleDefinition {
+                name: "Early bird".to_string(),
+                media: None,
+                unlock: TitleUnlock::Achievement {
+                    achievement: Achievement::FirstClaim,
+                },
+            }),
+        );
+        contract.set_title(
+            "whale".to_string(),
+            Some(TitleDefinition {
+                name: "Whale".to_string(),
+                media: None,
+                unlock: TitleUnlock::Purchase { price: U128(10) },
+            }),
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_title("whale".to_string());
+        contract.equip_title(Some("whale".to_string()));
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 10);
+
+        let users = serde_json::to_value(contract.get_users(None, None).items).unwrap();
+        assert_eq!(users[0]["title"], "whale");
+
+        let titles = serde_json::to_value(contract.get_titles(Some(accounts(1)))).unwrap();
+        assert_eq!(titles[0]["owned"], false);
+        assert_eq!(titles[1]["owned"], true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Title is not owned")]
+    fn test_cannot_equip_locked_achievement_title() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.set_title(
+            "veteran".to_string(),
+            Some(TitleDefinition {
+                name: "Veteran".to_string(),
+                media: None,
+                unlock: TitleUnlock::Achievement {
+                    achievement: Achievement::HundredDayStreak,
+                },
+            }),
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.equip_title(Some("veteran".to_string()));
+    }
+
+    #[test]
+    fn test_battle_pass_levels_and_premium_track() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY * 2));
+        contract.set_battle_pass(Some(BattlePass {
+            levels: vec![PassLevel {
+                free: Some(PassReward::Points { amount: U128(3) }),
+                premium: Some(PassReward::Points { amount: U128(7) }),
+            }],
+            xp_per_level: U64(10),
+            xp: PassXpConfig {
+                claim: U64(5),
+                spin: U64(0),
+                ticket: U64(5),
+            },
+            premium_price: U128(4),
+            premium_price_near: U128(0),
+        }));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+        let progress = serde_json::to_value(contract.get_pass_progress(accounts(1))).unwrap();
+        assert_eq!(progress["level"], 0);
+
+        contract.buy_ticket(U64(1), U64(1));
+        let progress = serde_json::to_value(contract.get_pass_progress(accounts(1))).unwrap();
+        assert_eq!(progress["level"], 1);
+
+        let points = contract.get_user(accounts(1)).points.0;
+        contract.claim_pass_reward(1, false);
+        contract.buy_premium_pass();
+        contract.claim_pass_reward(1, true);
+        assert_eq!(contract.get_user(accounts(1)).points.0, points + 3 - 4 + 7);
+
+        let progress = serde_json::to_value(contract.get_pass_progress(accounts(1))).unwrap();
+        assert_eq!(progress["claimed_free"], serde_json::json!([1]));
+        assert_eq!(progress["claimed_premium"], serde_json::json!([1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Premium pass required")]
+    fn test_battle_pass_premium_requires_unlock() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_battle_pass(Some(BattlePass {
+            levels: vec![PassLevel {
+                free: None,
+                premium: Some(PassReward::Points { amount: U128(7) }),
+            }],
+            xp_per_level: U64(5),
+            xp: PassXpConfig {
+                claim: U64(5),
+                spin: U64(0),
+                ticket: U64(0),
+            },
+            premium_price: U128(4),
+            premium_price_near: U128(0),
+        }));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+        contract.claim_pass_reward(1, true);
+    }
+
+    #[test]
+    fn test_trivia_commit_reveal_splits_pool() {
+        let mut contract = setup_contract();
+        for account_id in [accounts(1), accounts(2), accounts(3)] {
+            register(&mut contract, account_id);
+        }
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let trivia_id = contract.create_trivia(
+            "Which chain?".to_string(),
+            near_sdk::json_types::Base64VecU8(env::sha256(b"NEAR:pepper")),
+            U128(10),
+            U64(ONE_DAY),
+            U64(ONE_DAY * 2),
+        );
+
+        let guesses = [
+            (accounts(1), "near"),
+            (accounts(2), " Near "),
+            (accounts(3), "eth"),
+        ];
+        for (account_id, answer) in guesses.iter() {
+            testing_env!(get_context(account_id.clone()).build());
+            let guess = format!("1:{}:{}:salt", account_id, answer);
+            contract.submit_trivia_guess(
+                trivia_id,
+                near_sdk::json_types::Base64VecU8(env::sha256(guess.as_bytes())),
+            );
+        }
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.reveal_trivia_answer(trivia_id, "NEAR".to_string(), "pepper".to_string());
+
+        for (account_id, answer) in guesses.iter() {
+            testing_env!(get_context(account_id.clone())
+                .block_timestamp(ONE_DAY * 1_000_000)
+                .build());
+            let correct =
+                contract.reveal_trivia_guess(trivia_id, answer.to_string(), "salt".to_string());
+            assert_eq!(correct, account_id != &accounts(3));
+        }
+
+        for (account_id, prize) in [(accounts(1), 5), (accounts(3), 0)] {
+            testing_env!(get_context(account_id.clone())
+                .block_timestamp(ONE_DAY * 2 * 1_000_000)
+                .build());
+            assert_eq!(contract.claim_trivia_prize(trivia_id).0, prize);
+        }
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commit phase has ended")]
+    fn test_trivia_rejects_late_guesses() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let trivia_id = contract.create_trivia(
+            "Which chain?".to_string(),
+            near_sdk::json_types::Base64VecU8(env::sha256(b"NEAR:pepper")),
+            U128(10),
+            U64(ONE_DAY),
+            U64(ONE_DAY * 2),
+        );
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.submit_trivia_guess(
+            trivia_id,
+            near_sdk::json_types::Base64VecU8(env::sha256(b"late")),
+        );
+    }
+
+    #[test]
+    fn test_poll_winner_becomes_reward() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let candidates = ["Hoodie", "Sticker pack"]
+            .iter()
+            .map(|title| PollCandidate {
+                title: title.to_string(),
+                price: U128(2),
+                duration_ms: U64(ONE_DAY),
+            })
+            .collect();
+        let poll_id = contract.create_poll(candidates, PollMode::Lock, U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.vote_poll(poll_id, 0, U128(5));
+        testing_env!(get_context(accounts(2)).build());
+        contract.vote_poll(poll_id, 1, U128(8));
+
+        testing_env!(get_context(accounts(2))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let reward_id = contract.finalize_poll(poll_id).unwrap();
+        assert_eq!(contract.get_reward(reward_id).title, "Sticker pack");
+
+        assert_eq!(contract.withdraw_poll_lock(poll_id).0, 8);
+        assert_eq!(contract.get_user(accounts(2)).points.0, INIT_POINT);
+    }
+
+    #[test]
+    #[should_panic(expected = "Poll is closed")]
+    fn test_poll_rejects_late_votes() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let candidates = ["Hoodie", "Sticker pack"]
+            .iter()
+            .map(|title| PollCandidate {
+                title: title.to_string(),
+                price: U128(2),
+                duration_ms: U64(ONE_DAY),
+            })
+            .collect();
+        let poll_id = contract.create_poll(candidates, PollMode::Burn, U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.vote_poll(poll_id, 0, U128(5));
+    }
+
+    #[test]
+    fn test_referral_competition_pays_top_referrers() {
+        let mut contract = setup_contract();
+        for index in 1..6 {
+            register(&mut contract, accounts(index));
+        }
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let competition_id =
+            contract.create_referral_competition(U64(0), U64(ONE_DAY), vec![U128(20), U128(10)]);
+
+        for (account_id, referrer_id) in [
+            (accounts(3), accounts(2)),
+            (accounts(4), accounts(1)),
+            (accounts(5), accounts(1)),
+        ] {
+            testing_env!(get_context(account_id).build());
+            contract.set_referrer(referrer_id);
+        }
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.settle_referral_competition(competition_id);
+
+        let competition =
+            serde_json::to_value(contract.get_referral_competition(competition_id)).unwrap();
+        assert_eq!(
+            competition["standings"][0]["account_id"],
+            accounts(1).to_string()
+        );
+        assert_eq!(competition["standings"][0]["referrals"], 2);
+        assert_eq!(competition["settled"], true);
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 20);
+        assert_eq!(contract.get_user(accounts(2)).points.0, INIT_POINT + 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Referrer already set")]
+    fn test_referrer_set_once() {
+        let mut contract = setup_contract();
+        for index in 1..4 {
+            register(&mut contract, accounts(index));
+        }
+
+        testing_env!(get_context(accounts(3)).build());
+        contract.set_referrer(accounts(1));
+        contract.set_referrer(accounts(2));
+    }
+
+    #[test]
+    fn test_lucky_draw_pays_share_of_pool() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.fund_lucky_draw(U128(18));
+        contract.set_lucky_draw_config(LuckyDrawConfig {
+            entry_price: U128(2),
+            win_chance_bps: MAX_BPS,
+            payout_bps: 5_000,
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(contract.enter_lucky_draw().0, 10);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_lucky_draw_config(LuckyDrawConfig {
+            entry_price: U128(2),
+            win_chance_bps: 0,
+            payout_bps: 5_000,
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(contract.enter_lucky_draw().0, 0);
+
+        let draw = serde_json::to_value(contract.get_lucky_draw()).unwrap();
+        assert_eq!(draw["pool"], "12");
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 4 + 10);
+    }
+
+    #[test]
+    fn test_milestones_granted_once() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_milestones(vec![
+            Milestone {
+                threshold: U64(10),
+                reward: MilestoneReward::Points { amount: U128(5) },
+            },
+            Milestone {
+                threshold: U64(15),
+                reward: MilestoneReward::Points { amount: U128(100) },
+            },
+        ]);
+        contract.adjust_points(accounts(1), I128(10), "bonus".to_string(), None);
+
+        // The first milestone's bonus pushes XP past the second one
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 115);
+
+        contract.adjust_points(accounts(1), I128(10), "bonus".to_string(), None);
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 125);
+
+        let milestones = serde_json::to_value(contract.get_milestones(Some(accounts(1)))).unwrap();
+        assert_eq!(milestones[1]["reached"], true);
+    }
+
+    #[test]
+    fn test_quest_rotation_repeats_each_period() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        for quest_id in ["swap", "stake", "mint"] {
+            contract.set_quest(quest_id.to_string(), accounts(4), U128(5), U64(0));
+        }
+        contract.set_quest_rotation(Some(QuestRotation {
+            period: RotationPeriod::Daily,
+            active_count: 1,
+            pool: vec!["swap".to_string(), "stake".to_string()],
+        }));
+
+        let active_quest = |contract: &ArkanaCoreContract| {
+            let active = serde_json::to_value(contract.get_active_quests()).unwrap();
+            active["quests"][0]["quest_id"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        testing_env!(get_context(accounts(4)).build());
+        let quest_id = active_quest(&contract);
+        contract.on_quest_completed(accounts(1), quest_id.clone(), "".to_string());
+        assert!(contract.is_quest_completed(accounts(1), quest_id));
+        // Quests outside the pool are always active
+        contract.on_quest_completed(accounts(1), "mint".to_string(), "".to_string());
+
+        // The next day's quest can be completed again, even if it is the same
+        testing_env!(get_context(accounts(4))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let quest_id = active_quest(&contract);
+        assert!(!contract.is_quest_completed(accounts(1), quest_id.clone()));
+        contract.on_quest_completed(accounts(1), quest_id, "".to_string());
+
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 15);
+    }
+
+    #[test]
+    fn test_jackpot_fed_by_games_and_won_whole() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_lucky_draw_config(LuckyDrawConfig {
+            entry_price: U128(10),
+            win_chance_bps: 0,
+            payout_bps: 0,
+        });
+        contract.set_jackpot_config(JackpotConfig {
+            contribution_bps: 5_000,
+            win_chance_ppm: 0,
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.enter_lucky_draw();
+        let jackpot = serde_json::to_value(contract.get_jackpot()).unwrap();
+        assert_eq!(jackpot["pool"], "5");
+        let draw = serde_json::to_value(contract.get_lucky_draw()).unwrap();
+        assert_eq!(draw["pool"], "5");
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_jackpot_config(JackpotConfig {
+            contribution_bps: 5_000,
+            win_chance_ppm: JACKPOT_CHANCE_DENOMINATOR,
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.enter_lucky_draw();
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 20 + 10
+        );
+
+        let dashboard = serde_json::to_value(contract.get_dashboard(accounts(1))).unwrap();
+        assert_eq!(dashboard["jackpot"], "0");
+    }
+
+    #[test]
+    fn test_free_claim_requires_verified_human() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_personhood_config(Some(PersonhoodConfig {
+            registry_id: accounts(5),
+            required_for_claims: true,
+            required_for_free_spins: false,
+        }));
+
+        testing_env!(
+            get_context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(
+                b"[[\"fractal.i-am-human.near\", [7]]]".to_vec()
+            )],
+        );
+        assert!(contract.on_personhood_verified(accounts(1)));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(PERSONHOOD_CACHE_TTL * 1_000_000)
+            .build());
+        let personhood = serde_json::to_value(contract.get_personhood(accounts(1))).unwrap();
+        assert_eq!(personhood["is_human"], false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof of personhood required")]
+    fn test_unverified_claim_is_rejected() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_personhood_config(Some(PersonhoodConfig {
+            registry_id: accounts(5),
+            required_for_claims: true,
+            required_for_free_spins: true,
+        }));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+    }
+
+    #[test]
+    fn test_tournament_stages_and_prizes() {
+        let mut contract = setup_contract();
+        for index in 1..4 {
+            register(&mut contract, accounts(index));
+        }
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let stage = |advancing| TournamentStage {
+            duration_ms: U64(ONE_DAY),
+            advancing,
+        };
+        contract.create_tournament(
+            "Cup".to_string(),
+            U128(5),
+            U64(ONE_DAY),
+            vec![stage(2), stage(1)],
+            vec![10_000],
+        );
+
+        for index in 1..4 {
+            testing_env!(get_context(accounts(index)).build());
+            contract.enter_tournament(U64(1));
+        }
+
+        let score = |contract: &mut ArkanaCoreContract, index: usize, points: Points| {
+            let mut user = contract.internal_get_user(&accounts(index));
+            contract.internal_issue_points(&accounts(index), &mut user, points);
+            contract.internal_save_user(&accounts(index), &user);
+        };
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.advance_tournament(U64(1));
+        score(&mut contract, 1, 1);
+        score(&mut contract, 2, 3);
+        score(&mut contract, 3, 2);
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(2 * ONE_DAY * 1_000_000)
+            .build());
+        contract.advance_tournament(U64(1));
+        assert_eq!(contract.get_player_tournament(accounts(1)), None);
+        score(&mut contract, 3, 4);
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(3 * ONE_DAY * 1_000_000)
+            .build());
+        assert!(contract.advance_tournament(U64(1)) == TournamentStatus::Finished);
+        assert_eq!(
+            contract.get_user(accounts(3)).points.0,
+            INIT_POINT - 5 + 2 + 4 + 15
+        );
+        assert_eq!(contract.get_player_tournament(accounts(3)), None);
+    }
+
+    #[test]
+    fn test_multiplier_event_doubles_claims() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let claimed = contract.daily_claim_point().0 - INIT_POINT;
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.schedule_multiplier_event(
+            "2x weekend".to_string(),
+            U64(2 * ONE_DAY),
+            U64(3 * ONE_DAY),
+            20_000,
+        );
+        assert!(contract.get_active_multiplier_event().is_none());
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(2 * ONE_DAY * 1_000_000)
+            .build());
+        assert!(contract.get_active_multiplier_event().is_some());
+        assert_eq!(
+            contract.daily_claim_point().0,
+            INIT_POINT + claimed + 2 * claimed
+        );
+    }
+
+    #[test]
+    fn test_gift_points_fee_and_daily_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_gift_config(GiftConfig {
+            daily_limit: U128(15),
+            fee_free_threshold: U128(5),
+            fee_bps: 1_000,
+        });
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert_eq!(contract.gift_points(accounts(2), U128(5), None).0, 5);
+        assert_eq!(
+            contract
+                .gift_points(accounts(2), U128(10), Some("gg".to_string()))
+                .0,
+            9
+        );
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 15);
+        assert_eq!(contract.get_user(accounts(2)).points.0, INIT_POINT + 14);
+
+        let history =
+            serde_json::to_value(contract.get_user_history(accounts(2), None, None).items).unwrap();
+        assert_eq!(history[1]["memo"], "gg");
+    }
+
+    #[test]
+    #[should_panic(expected = "Daily gift limit exceeded")]
+    fn test_gift_points_over_daily_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_gift_config(GiftConfig {
+            daily_limit: U128(5),
+            fee_free_threshold: U128(0),
+            fee_bps: 0,
+        });
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.gift_points(accounts(2), U128(3), None);
+        contract.gift_points(accounts(2), U128(3), None);
+    }
+
+    #[test]
+    fn test_charity_burns_matched_and_badged() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Prize".to_string(), U128(1), U64(2 * ONE_DAY));
+        contract.create_charity_campaign("Trees".to_string(), U64(ONE_DAY), 1);
+        contract.set_charity_match(
+            U64(1),
+            Some(CharityMatch {
+                reward_id: U64(1),
+                points_per_ticket: U128(4),
+            }),
+        );
+
+        testing_env!(get_context(accounts(1))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        assert_eq!(contract.burn_for_charity(U64(1), U128(3)).0, 0);
+        assert_eq!(contract.burn_for_charity(U64(1), U128(6)).0, 2);
+        testing_env!(get_context(accounts(2))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.burn_for_charity(U64(1), U128(5));
+
+        let campaign = serde_json::to_value(contract.get_charity_campaign(U64(1))).unwrap();
+        assert_eq!(campaign["total_burned"], "14");
+        assert_eq!(
+            campaign["top_burners"][0]["account_id"],
+            accounts(1).to_string()
+        );
+
+        testing_env!(get_context(accounts(2))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.close_charity_campaign(U64(1));
+        let user = contract.internal_get_user(&accounts(1));
+        assert!(user.has_achievement(Achievement::CharityChampion));
+        assert!(!contract
+            .internal_get_user(&accounts(2))
+            .has_achievement(Achievement::CharityChampion));
+    }
+
+    #[test]
+    fn test_long_history_cleanup_is_resumable() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        for _ in 0..INLINE_CLEANUP_LIMIT + 5 {
+            testing_env!(get_context(accounts(0)).build());
+            contract.adjust_points(accounts(1), I128(1), "bonus".to_string(), None);
+        }
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert!(contract.unregister_account(true));
+        let cleanups =
+            serde_json::to_value(contract.get_pending_cleanups(None, None).items).unwrap();
+        assert_eq!(cleanups[0]["remaining"], "5");
+
+        testing_env!(get_context(accounts(2)).build());
+        assert_eq!(contract.process_cleanups(Some(U64(3))).0, 3);
+        assert_eq!(contract.process_cleanups(None).0, 2);
+        assert!(contract.get_pending_cleanups(None, None).items.is_empty());
+        // Entries added by an operator were never charged to the account
+        assert!(contract.treasury_balance > 0);
+
+        register(&mut contract, accounts(1));
+        assert!(contract
+            .get_user_history(accounts(1), None, None)
+            .items
+            .is_empty());
+    }
+
+    #[test]
+    fn test_users_are_stored_versioned() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.adjust_points(accounts(1), I128(5), "bonus".to_string(), None);
+
+        match contract.users.get(&accounts(1)) {
+            Some(VUser::Current(user)) => assert_eq!(user.points, INIT_POINT + 5),
+            None => panic!("User not stored"),
+        }
+    }
+
+    #[test]
+    fn test_pagination_caps_limit_and_returns_cursor() {
+        let page = paginate(0..250u32, None, Some(1000));
+        assert_eq!(page.items.len() as u64, MAX_PAGE_LIMIT);
+        assert_eq!(page.next_index, Some(U64(MAX_PAGE_LIMIT)));
+
+        let page = paginate(0..250u32, Some(U64(200)), None);
+        assert_eq!(page.items, (200..250).collect::<Vec<_>>());
+        assert_eq!(page.next_index, None);
+
+        let page = paginate(0..250u32, Some(U64(300)), None);
+        assert!(page.items.is_empty());
+    }
+
+    #[test]
+    fn test_wheel_weights_draw_from_cumulative_table() {
+        let weights = WheelWeights::new(&default_wheel_segments());
+
+        assert_eq!(weights.draw(0, 50), 0);
+        assert_eq!(weights.draw(0, 51), 1);
+        assert_eq!(weights.draw(0, 231), 5);
+        // Boost 10 adds the boost weights 3, 2 and 1 to the last segments
+        assert_eq!(weights.draw(10, 231), 4);
+        assert_eq!(weights.draw(10, 236), 5);
+        assert_eq!(weights.draw(10, 238), 0);
+    }
+
+    #[test]
+    fn test_remove_reward_releases_storage_to_payers() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+        contract.buy_ticket(U64(1), U64(2));
+        let used_bytes = contract
+            .storage_accounts
+            .get(&accounts(1))
+            .unwrap()
+            .used_bytes;
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .attached_deposit(1)
+            .build());
+        contract.finalize_reward(U64(1), false);
+        contract.remove_reward(U64(1));
+
+        assert!(
+            contract
+                .storage_accounts
+                .get(&accounts(1))
+                .unwrap()
+                .used_bytes
+                < used_bytes
+        );
+        assert!(contract.treasury_balance > 0);
+    }
+
+    #[test]
+    fn test_buy_ticket_only_rewrites_ticket_state() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+        let reward = contract.rewards.get(&1).unwrap().try_to_vec().unwrap();
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(4));
+
+        assert_eq!(
+            contract.rewards.get(&1).unwrap().try_to_vec().unwrap(),
+            reward
+        );
+        assert_eq!(contract.get_reward(U64(1)).total_tickets, U64(4));
+    }
+
+    #[test]
+    fn test_estimate_buy_ticket_matches_purchase() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        let estimate = serde_json::to_value(contract.estimate_buy_ticket(
+            accounts(1),
+            U64(1),
+            U64(INIT_POINT as u64 + 1),
+        ))
+        .unwrap();
+        assert_eq!(estimate["would_succeed"], false);
+        assert_eq!(estimate["error"], "Points insufficient");
+
+        let estimate =
+            serde_json::to_value(contract.estimate_buy_ticket(accounts(1), U64(1), U64(2)))
+                .unwrap();
+        assert_eq!(estimate["would_succeed"], true);
+        assert_eq!(estimate["required_points"], "2");
+
+        let initial_storage_usage = env::storage_usage();
+        contract.buy_ticket(U64(1), U64(2));
+        assert_eq!(
+            estimate["storage_bytes"],
+            (env::storage_usage() - initial_storage_usage).to_string()
+        );
+    }
+
+    #[test]
+    fn test_estimate_daily_claim_reports_cooldown() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let estimate = serde_json::to_value(contract.estimate_daily_claim(accounts(1))).unwrap();
+        assert_eq!(estimate["would_succeed"], true);
+
+        contract.daily_claim_point();
+        let estimate = serde_json::to_value(contract.estimate_daily_claim(accounts(1))).unwrap();
+        assert_eq!(estimate["would_succeed"], false);
+        assert!(estimate["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Cannot claim"));
+    }
+
+    #[test]
+    fn test_event_only_history_skips_storage() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.adjust_points(accounts(1), I128(1), "bonus".to_string(), None);
+        contract.set_event_only_history(true);
+
+        let initial_storage_usage = env::storage_usage();
+        contract.adjust_points(accounts(1), I128(1), "bonus".to_string(), None);
+        assert_eq!(env::storage_usage(), initial_storage_usage);
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"history_recorded\"")));
+
+        let history = contract.get_user_history(accounts(1), None, None);
+        assert_eq!(history.items.len(), 1);
+    }
+
+    #[test]
+    fn test_points_above_u64_range() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        let amount = u64::MAX as u128 * 2;
+        let points =
+            contract.adjust_points(accounts(1), I128(amount as i128), "bonus".to_string(), None);
+        assert_eq!(points.0, INIT_POINT + amount);
+
+        let user = serde_json::to_value(contract.get_user(accounts(1))).unwrap();
+        assert_eq!(user["points"], (INIT_POINT + amount).to_string());
+        assert_eq!(user["xp"], u64::MAX.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "refusing to downgrade")]
+    fn test_migrate_rejects_newer_state() {
+        let contract = setup_contract();
+        env::state_write(&contract);
+        env::storage_write(
+            STATE_VERSION_KEY,
+            &(CURRENT_STATE_VERSION + 1).try_to_vec().unwrap(),
+        );
+
+        ArkanaCoreContract::migrate();
+    }
+
+    #[test]
+    fn test_user_activity_is_packed() {
+        let mut activity = UserActivity::default();
+        assert_eq!(activity.try_to_vec().unwrap().len(), 24);
+
+        activity.set_last_daily_claim(ONE_DAY * 20_000 + 1_500);
+        assert_eq!(activity.last_daily_claim(), ONE_DAY * 20_000 + 1_000);
+
+        activity.set_free_spins_used(300);
+        assert_eq!(activity.free_spins_used(), u8::MAX as u32);
+    }
+
+    #[test]
+    fn test_random_draws_are_independent() {
+        let mut contract = setup_contract();
+
+        let first = contract.internal_random_number(RandomPurpose::Spin);
+        let second = contract.internal_random_number(RandomPurpose::Spin);
+        assert_ne!(first, second);
+        assert_eq!(contract.random_nonce, 2);
+
+        contract.random_nonce = 0;
+        assert_eq!(contract.internal_random_number(RandomPurpose::Spin), first);
+        contract.random_nonce = 0;
+        assert_ne!(
+            contract.internal_random_number(RandomPurpose::Jackpot),
+            first
+        );
+    }
+
+    #[test]
+    fn test_finalize_reward_without_tickets() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.finalize_reward(U64(1), false), None);
+
+        let reward = serde_json::to_value(contract.get_reward(U64(1))).unwrap();
+        assert_eq!(reward["no_winner"], true);
+        assert!(reward["winner"].is_null());
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward finalized")]
+    fn test_closed_reward_cannot_be_finalized_again() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.finalize_reward(U64(1), false);
+        contract.finalize_reward(U64(1), false);
+    }
+
+    #[test]
+    fn test_replay_draw_matches_finalize() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(3));
+        testing_env!(get_context(accounts(2)).build());
+        contract.buy_ticket(U64(1), U64(4));
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .random_seed([7; 32])
+            .build());
+        let winner = contract.finalize_reward(U64(1), false).unwrap();
+
+        let log = near_sdk::test_utils::get_logs()
+            .into_iter()
+            .find(|log| log.contains("\"event\":\"reward_drawn\""))
+            .unwrap();
+        let event: serde_json::Value =
+            serde_json::from_str(log.trim_start_matches("EVENT_JSON:")).unwrap();
+
+        let replay = serde_json::to_value(contract.replay_draw(U64(1))).unwrap();
+        assert_eq!(replay["ticket"], event["data"][0]["ticket"]);
+        assert_eq!(replay["caller"], accounts(3).to_string());
+        assert_eq!(replay["total_tickets"], "7");
+        assert_eq!(replay["ticket_holder"], winner.to_string());
+        assert_eq!(replay["winner"], winner.to_string());
+        assert_eq!(replay["skipped"], event["data"][0]["skipped"]);
+    }
+
+    #[test]
+    fn test_replay_draw_lists_skipped_holders() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(3));
+        testing_env!(get_context(accounts(2)).build());
+        contract.buy_ticket(U64(1), U64(4));
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(2));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.blacklist_account(accounts(1), "bot".to_string());
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.finalize_reward(U64(1), false), Some(accounts(2)));
+
+        let replay = serde_json::to_value(contract.replay_draw(U64(1))).unwrap();
+        assert_eq!(replay["ticket_holder"], accounts(1).to_string());
+        assert_eq!(replay["skipped"], serde_json::json!([accounts(1)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward does not exist")]
+    fn test_replay_draw_of_removed_reward() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .attached_deposit(1)
+            .build());
+        contract.finalize_reward(U64(1), false);
+        contract.remove_reward(U64(1));
+        contract.replay_draw(U64(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_add_operator_requires_one_yocto() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.add_operator(accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward cannot run longer than")]
+    fn test_create_reward_beyond_horizon() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(MAX_REWARD_DURATION + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot buy more than 1000 tickets per call")]
+    fn test_buy_ticket_amount_capped() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(MAX_TICKETS_PER_CALL + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward does not exist")]
+    fn test_buy_ticket_unknown_reward() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(7), U64(1));
+    }
+
+    #[test]
+    fn test_try_variants_return_outcomes() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(100), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(
+            contract.try_buy_ticket(U64(1), U64(1)),
+            ActionOutcome::Err {
+                error: "Points insufficient".to_string()
+            }
+        );
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(
+            contract.try_daily_claim_point(),
+            ActionOutcome::Ok {
+                value: U128(INIT_POINT + 10)
+            }
+        );
+        assert!(matches!(
+            contract.try_daily_claim_point(),
+            ActionOutcome::Err { .. }
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Operation already processed")]
+    fn test_adjust_points_rejects_repeated_operation() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        let operation_id = Some("credit-42".to_string());
+        contract.adjust_points(
+            accounts(1),
+            I128(5),
+            "bonus".to_string(),
+            operation_id.clone(),
+        );
+        assert!(contract.is_operation_processed(accounts(0), "credit-42".to_string()));
+        assert!(!contract.is_operation_processed(accounts(2), "credit-42".to_string()));
+
+        contract.adjust_points(accounts(1), I128(5), "bonus".to_string(), operation_id);
+    }
+
+    #[test]
+    fn test_operation_ids_expire() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.adjust_points(
+            accounts(1),
+            I128(5),
+            "bonus".to_string(),
+            Some("credit-42".to_string()),
+        );
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(OPERATION_ID_TTL * 1_000_000)
+            .build());
+        assert!(!contract.is_operation_processed(accounts(0), "credit-42".to_string()));
+        contract.adjust_points(
+            accounts(1),
+            I128(5),
+            "bonus".to_string(),
+            Some("credit-42".to_string()),
+        );
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward finalized")]
+    fn test_forced_finalize_does_not_redraw() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        assert_eq!(contract.finalize_reward(U64(1), true), Some(accounts(1)));
+        contract.finalize_reward(U64(1), true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward has ended")]
+    fn test_cancelled_reward_sells_no_tickets() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+        contract.cancel_reward(U64(1));
+
+        let reward = serde_json::to_value(contract.get_reward(U64(1))).unwrap();
+        assert_eq!(reward["status"], "cancelled");
+        assert_eq!(reward["no_winner"], false);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+    }
+
+    #[test]
+    fn test_repeat_winner_is_skipped_during_cooldown() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetRepeatWinnerCooldown {
+            cooldown_ms: U64(7 * ONE_DAY),
+        });
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+        contract.create_reward("Hoodie".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+        contract.buy_ticket(U64(2), U64(5));
+        testing_env!(get_context(accounts(2)).build());
+        contract.buy_ticket(U64(2), U64(1));
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.finalize_reward(U64(1), false), Some(accounts(1)));
+        assert_eq!(contract.finalize_reward(U64(2), false), Some(accounts(2)));
+    }
+
+    #[test]
+    fn test_raffle_without_eligible_winner_has_no_winner() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetRepeatWinnerCooldown {
+            cooldown_ms: U64(7 * ONE_DAY),
+        });
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+        contract.create_reward("Hoodie".to_string(), U128(2), U64(ONE_DAY));
+        contract.create_reward("Cap".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+        contract.buy_ticket(U64(2), U64(1));
+        testing_env!(get_context(accounts(2)).build());
+        contract.buy_ticket(U64(3), U64(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.blacklist_account(accounts(2), "bot".to_string());
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.finalize_reward(U64(1), false), Some(accounts(1)));
+
+        // The only holder won a raffle within the cooldown
+        assert_eq!(contract.finalize_reward(U64(2), false), None);
+        assert!(contract.internal_get_reward(2).has_no_winner());
+
+        // The only holder is blacklisted
+        assert_eq!(contract.finalize_reward(U64(3), false), None);
+        assert!(contract.internal_get_reward(3).has_no_winner());
+        let replay = serde_json::to_value(contract.replay_draw(U64(3))).unwrap();
+        assert!(replay["winner"].is_null());
+        assert_eq!(replay["skipped"], serde_json::json!([accounts(2)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Self-excluded until")]
+    fn test_self_exclusion_blocks_free_spins() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.self_exclude(U64(7 * ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        let estimate =
+            serde_json::to_value(contract.estimate_spin_wheel(accounts(1), true)).unwrap();
+        assert_eq!(estimate["would_succeed"], false);
+        contract.play_spin_wheel(true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Daily spending limit exceeded")]
+    fn test_daily_spending_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.set_daily_spending_limit(U128(5), U64(7 * ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.play_spin_wheel(false);
+        let limits = serde_json::to_value(contract.get_spending_limits(accounts(1))).unwrap();
+        assert_eq!(limits["spent_today"], "5");
+        contract.play_spin_wheel(false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Spending limit can only be tightened until it expires")]
+    fn test_daily_spending_limit_cannot_be_raised() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.set_daily_spending_limit(U128(5), U64(7 * ONE_DAY));
+        contract.set_daily_spending_limit(U128(2), U64(8 * ONE_DAY));
+        contract.set_daily_spending_limit(U128(10), U64(8 * ONE_DAY));
+    }
+
+    #[test]
+    fn test_simulate_spin_odds_cover_every_draw() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        let simulation = serde_json::to_value(contract.simulate_spin(accounts(1))).unwrap();
+        let outcomes: Vec<u64> = simulation["segments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|segment| segment["outcomes"].as_u64().unwrap())
+            .collect();
+        assert_eq!(outcomes.iter().sum::<u64>(), simulation["total_outcomes"]);
+        assert_eq!(
+            outcomes,
+            (0..outcomes.len())
+                .map(|index| {
+                    (0..232)
+                        .filter(|random_number| {
+                            contract.wheel_weights.draw(0, *random_number) == index
+                        })
+                        .count() as u64
+                })
+                .collect::<Vec<u64>>()
+        );
+        assert_eq!(simulation["affordable_spins"], "5");
+    }
+
+    #[test]
+    fn test_simulate_daily_claim_matches_claim() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let simulation = serde_json::to_value(contract.simulate_daily_claim(accounts(1))).unwrap();
+        assert!(simulation["error"].is_null());
+        assert_eq!(simulation["claim_streak"], 1);
+
+        let claimed = contract.daily_claim_point().0 - INIT_POINT;
+        assert_eq!(simulation["total_points"], claimed.to_string());
+
+        let simulation = serde_json::to_value(contract.simulate_daily_claim(accounts(1))).unwrap();
+        assert!(simulation["error"].is_string());
+    }
+
+    #[test]
+    fn test_admin_overview_lists_overdue_rewards() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+        contract.create_reward("Hoodie".to_string(), U128(2), U64(ONE_DAY));
+        contract.create_reward("Cap".to_string(), U128(2), U64(2 * ONE_DAY));
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.finalize_reward(U64(1), false);
+
+        let overview = serde_json::to_value(contract.get_admin_overview(None)).unwrap();
+        assert_eq!(
+            overview["overdue_rewards"]["items"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(overview["overdue_rewards"]["items"][0]["reward_id"], "2");
+        assert_eq!(overview["overdue_rewards"]["items"][0]["status"], "active");
+        assert!(overview["overdue_rewards"]["next_index"].is_null());
+        assert_eq!(overview["config"]["owner"], accounts(0).to_string());
+        assert_eq!(overview["operator_count"], "0");
+    }
+
+    #[test]
+    fn test_admin_overview_scans_rewards_in_pages() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        for _ in 0..MAX_PAGE_LIMIT + 1 {
+            contract.internal_create_reward("Merch".to_string(), 2, ONE_DAY);
+        }
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        let overview = serde_json::to_value(contract.get_admin_overview(None)).unwrap();
+        let page = &overview["overdue_rewards"];
+        assert_eq!(
+            page["items"].as_array().unwrap().len(),
+            MAX_PAGE_LIMIT as usize
+        );
+        assert_eq!(page["next_index"], (MAX_PAGE_LIMIT + 1).to_string());
+
+        let next_index = U64(MAX_PAGE_LIMIT + 1);
+        let overview = serde_json::to_value(contract.get_admin_overview(Some(next_index))).unwrap();
+        let page = &overview["overdue_rewards"];
+        assert_eq!(
+            page["items"][0]["reward_id"],
+            (MAX_PAGE_LIMIT + 1).to_string()
+        );
+        assert!(page["next_index"].is_null());
+    }
+
+    #[test]
+    #[should_panic(expected = "Self-excluded until")]
+    fn test_self_exclusion_blocks_near_lootboxes() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let lootbox_id = contract.create_lootbox(
+            "Near box".to_string(),
+            None,
+            Some(U128(1)),
+            vec![LootboxEntry {
+                prize: LootboxPrize::Points { amount: U128(5) },
+                weight: 1,
+            }],
+            None,
+        );
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.self_exclude(U64(7 * ONE_DAY));
+        contract.open_lootbox_with_near(lootbox_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Self-excluded until")]
+    fn test_self_exclusion_blocks_ft_spins() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_token_rate(token_id.clone(), Some(U128(100)));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.self_exclude(U64(7 * ONE_DAY));
+
+        testing_env!(get_context(token_id).build());
+        ft_transfer_call(&mut contract, 500, r#"{"action":"spin"}"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "Daily spending limit exceeded")]
+    fn test_predictions_count_against_spending_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+        contract.create_prediction_pool(U64(1), vec![U64(5)], U64(ONE_DAY / 2), 1_000);
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.set_daily_spending_limit(U128(10), U64(7 * ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.predict(U64(1), 1, U128(6));
+        contract.predict(U64(1), 1, U128(6));
+    }
+
+    #[test]
+    fn test_season_carry_over_haircut() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_season_carry_over(2_000);
+        contract.start_new_season();
+        contract.set_season_carry_over(MAX_BPS);
+        contract.start_new_season();
+
+        // Haircut is visible before settlement and applied once on settlement
+        let user = serde_json::to_value(contract.get_user(accounts(1))).unwrap();
+        assert_eq!(user["points"], "5");
+
+        let points = contract.adjust_points(accounts(1), I128(1), "settle".to_string(), None);
+        assert_eq!(points.0, 6);
+
+        let snapshot = contract.get_season_snapshot(accounts(1), 1).unwrap();
+        let snapshot = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(snapshot["points"], "5");
+    }
+
+    #[test]
+    fn test_season_carry_over_applies_to_stakes() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_staking_option(7, 0);
+
+        testing_env!(get_context(accounts(1)).build());
+        let stake_id = contract.stake_points(U128(20), 7);
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_season_carry_over(5_000);
+        contract.start_new_season();
+
+        let stakes = serde_json::to_value(contract.get_user_stakes(accounts(1))).unwrap();
+        assert_eq!(stakes[0]["amount"], "10");
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(7 * ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.unstake_points(stake_id).0, 10);
+        assert_eq!(contract.supply.staked, 0);
+        assert_eq!(contract.get_user(accounts(1)).points.0, 2 + 10);
+    }
+
+    #[test]
+    fn test_supply_stats() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+        contract.adjust_points(accounts(1), I128(-5), "exploit".to_string(), None);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(3));
+
+        let stats = serde_json::to_value(contract.get_supply_stats()).unwrap();
+        assert_eq!(stats["issued"], INIT_POINT.to_string());
+        assert_eq!(stats["burned"], "5");
+        assert_eq!(stats["spent_on_tickets"], "6");
+        assert_eq!(stats["outstanding"], (INIT_POINT - 11).to_string());
+    }
+
+    #[test]
+    fn test_stake_and_unstake_after_unlock() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_staking_option(7, 1_000);
+        contract.fund_staking_budget(U128(100));
+
+        testing_env!(get_context(accounts(1)).build());
+        let stake_id = contract.stake_points(U128(20), 7);
+        assert_eq!(contract.get_user_stakes(accounts(1)).len(), 1);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(7 * ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.unstake_points(stake_id).0, 22);
+
+        let user = serde_json::to_value(contract.get_user(accounts(1))).unwrap();
+        assert_eq!(user["points"], (INIT_POINT + 2).to_string());
+        assert!(contract.get_user_stakes(accounts(1)).is_empty());
+    }
+
+    #[test]
+    fn test_early_unstake_penalty() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_staking_option(7, 1_000);
+        contract.set_early_unstake_penalty(1_000);
+        contract.fund_staking_budget(U128(100));
+
+        testing_env!(get_context(accounts(1)).build());
+        let stake_id = contract.stake_points(U128(20), 7);
+        assert_eq!(contract.unstake_points(stake_id).0, 18);
+
+        let config = serde_json::to_value(contract.get_staking_config()).unwrap();
+        assert_eq!(config["emission_budget"], "100");
+    }
+
+    #[test]
+    #[should_panic(expected = "Paused")]
+    fn test_paused_feature_blocks_method() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.pause(PauseFeature::Claims);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.daily_claim_point();
+    }
+
+    #[test]
+    fn test_spin_kill_switch_leaves_claims_open() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.pause(PauseFeature::Spins);
+        let state = serde_json::to_value(contract.get_pause_state()).unwrap();
+        assert_eq!(state["spins"], true);
+        assert_eq!(state["all"], false);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.daily_claim_point().0, INIT_POINT + 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Paused")]
+    fn test_spin_kill_switch_blocks_spins() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.pause(PauseFeature::Spins);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.play_spin_wheel(true);
+    }
+
+    #[test]
+    fn test_pause_all_and_unpause() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_operator(accounts(2));
+
+        testing_env!(get_context(accounts(2)).build());
+        contract.pause(PauseFeature::All);
+        assert!(contract.pause_state.is_paused(PauseFeature::Spins));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.unpause(PauseFeature::All);
+        assert!(!contract.pause_state.is_paused(PauseFeature::Spins));
+
+        register(&mut contract, accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Account is blacklisted")]
+    fn test_blacklisted_account_cannot_spin() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.blacklist_account(accounts(1), "bot ring".to_string());
+        assert!(contract.is_blacklisted(accounts(1)));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.play_spin_wheel(false);
+    }
+
+    #[test]
+    fn test_blacklisted_account_skipped_in_draw() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(10));
+        testing_env!(get_context(accounts(2)).build());
+        contract.buy_ticket(U64(1), U64(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.blacklist_account(accounts(1), "bot ring".to_string());
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.finalize_reward(U64(1), false), Some(accounts(2)));
+
+        let reward = serde_json::to_value(contract.get_reward(U64(1))).unwrap();
+        assert_eq!(reward["winner"], accounts(2).to_string());
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        testing_env!(get_context(accounts(0)).build());
+
+        let mut old = ArkanaCoreContractV1 {
+            owner: accounts(0),
+            daily_claim_points: 10,
+            spin_wheel_price: 5,
+            users: UnorderedMap::new(StorageKey::Users),
+            rewards: UnorderedMap::new(StorageKey::Rewards),
+            last_reward_id: 0,
+            membership_contracts: HashSet::from([accounts(3)]),
+            spinwheel_wr: 3,
+        };
+        old.users.insert(
+            &accounts(1),
+            &UserV1 {
+                points: 40,
+                last_daily_claim: 0,
+                last_free_spinwheel: 0,
+            },
+        );
+        let mut tickets = TreeMap::new(StorageKey::Tickets { reward_id: 1 });
+        tickets.insert(&0, &accounts(1));
+        tickets.insert(&3, &accounts(2));
+        old.rewards.insert(
+            &1,
+            &RewardV1 {
+                title: "Legacy".to_string(),
+                price: 2,
+                ended_at: ONE_DAY,
+                total_tickets: 5,
+                winner: None,
+                tickets,
+            },
+        );
+        env::state_write(&old);
+
+        let mut contract = ArkanaCoreContract::migrate();
+        assert_eq!(contract.spinwheel_wr, 3);
+        assert!(contract.membership_contracts.contains(&accounts(3)));
+        assert!(contract.rewards.get(&1).is_none());
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        assert_eq!(contract.migrate_legacy_rewards(None).0, 0);
+        assert_eq!(contract.get_reward(U64(1)).title, "Legacy");
+        let tickets = contract.reward_tickets.get(&1).unwrap();
+        assert_eq!(tickets.tickets_of(&accounts(1)), 3);
+        assert_eq!(tickets.tickets_of(&accounts(2)), 2);
+        assert_eq!(tickets.purchase_of(3), 1);
+
+        let user = serde_json::to_value(contract.get_user(accounts(1))).unwrap();
+        assert_eq!(user["points"], "40");
+
+        let points = contract.adjust_points(accounts(1), I128(2), "migrated".to_string(), None);
+        assert_eq!(points.0, 42);
+        assert!(contract.legacy_users.get(&accounts(1)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward has not been migrated yet")]
+    fn test_legacy_reward_unavailable_until_migrated() {
+        testing_env!(get_context(accounts(0)).build());
+
+        let mut old = ArkanaCoreContractV1 {
+            owner: accounts(0),
+            daily_claim_points: 10,
+            spin_wheel_price: 5,
+            users: UnorderedMap::new(StorageKey::Users),
+            rewards: UnorderedMap::new(StorageKey::Rewards),
+            last_reward_id: 1,
+            membership_contracts: HashSet::new(),
+            spinwheel_wr: 0,
+        };
+        old.rewards.insert(
+            &1,
+            &RewardV1 {
+                title: "Legacy".to_string(),
+                price: 2,
+                ended_at: ONE_DAY,
+                total_tickets: 0,
+                winner: None,
+                tickets: TreeMap::new(StorageKey::Tickets { reward_id: 1 }),
+            },
+        );
+        env::state_write(&old);
+
+        let contract = ArkanaCoreContract::migrate();
+        contract.get_reward(U64(1));
+    }
+
+    #[test]
+    fn test_reward_v1_ticket_tree_becomes_ranges() {
+        testing_env!(get_context(accounts(0)).build());
+
+        let mut tickets = TreeMap::new(StorageKey::Tickets { reward_id: 1 });
+        tickets.insert(&0, &accounts(1));
+        tickets.insert(&3, &accounts(2));
+        tickets.insert(&7, &accounts(1));
+        let old = RewardV1 {
+            title: "Legacy".to_string(),
+            price: 2,
+            ended_at: ONE_DAY,
+            total_tickets: 9,
+            winner: Some(accounts(2)),
+            tickets,
+        };
+
+        let (reward, tickets) = old.into_current(1);
+        assert!(reward.status == RewardStatus::Finalized);
+        assert_eq!(reward.winner, Some(accounts(2)));
+        assert_eq!(reward.title(), "Legacy");
+        assert_eq!(tickets.total, 9);
+        assert_eq!(
+            tickets.purchases.to_vec(),
+            vec![(3, accounts(1)), (7, accounts(2)), (9, accounts(1))]
+        );
+        assert!(TreeMap::<u64, AccountId>::new(StorageKey::Tickets { reward_id: 1 }).is_empty());
+    }
+
+    #[test]
+    fn test_purchase_of_range_boundaries() {
+        testing_env!(get_context(accounts(0)).build());
+
+        let mut tickets = RewardTickets::new(1);
+        tickets.add(&accounts(1), 3);
+        tickets.add(&accounts(2), 4);
+        tickets.add(&accounts(1), 2);
+
+        // First ticket
+        assert_eq!(tickets.purchase_of(0), 0);
+        // Last ticket of a purchase and first ticket of the next one
+        assert_eq!(tickets.purchase_of(2), 0);
+        assert_eq!(tickets.purchase_of(3), 1);
+        assert_eq!(tickets.purchase_of(6), 1);
+        assert_eq!(tickets.purchase_of(7), 2);
+        // Last ticket
+        assert_eq!(tickets.purchase_of(8), 2);
+    }
+
+    /// Writes the contract out and reads it back like a new receipt would.
+    fn reload(contract: ArkanaCoreContract) -> ArkanaCoreContract {
+        env::state_write(&contract);
+        env::state_read().unwrap()
+    }
+
+    #[test]
+    fn test_users_survive_state_round_trip() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        let contract = reload(contract);
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT);
+        assert!(contract.user_ids.contains(&accounts(2)));
+        assert_eq!(contract.get_users(None, None).items.len(), 2);
+    }
+
+    #[test]
+    fn test_reward_title_survives_state_round_trip() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        let mut contract = reload(contract);
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(2));
+
+        let contract = reload(contract);
+        assert_eq!(contract.get_reward(U64(1)).title, "Merch");
+        assert_eq!(contract.reward_tickets.get(&1).unwrap().total, 2);
+    }
+
+    #[test]
+    fn test_membership_contracts_survive_state_round_trip() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_membership_nft_contract(accounts(3));
+
+        let contract = reload(contract);
+        assert!(contract.membership_contracts.contains(&accounts(3)));
+        assert!(contract.get_membership_contract(accounts(3)).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_upgrade_unauthorized() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.upgrade(vec![0, 97, 115, 109]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires guardian approval")]
+    fn test_upgrade_requires_guardian_approval() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.setup_guardians(vec![accounts(1), accounts(2)], 2);
+        contract.upgrade(vec![0, 97, 115, 109]);
+    }
+
+    #[test]
+    fn test_approved_upgrade_is_used_once() {
+        let mut contract = setup_contract();
+        let code = vec![0, 97, 115, 109];
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.setup_guardians(vec![accounts(1), accounts(2)], 2);
+        let proposal_id = contract
+            .propose_guarded_action(GuardedAction::ApproveUpgrade {
+                code_hash: near_sdk::bs58::encode(env::sha256_array(&code)).into_string(),
+            })
+            .unwrap();
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.confirm_guarded_action(proposal_id);
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        assert!(contract.confirm_guarded_action(proposal_id));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.upgrade(code);
+        assert!(contract.approved_upgrade.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough gas attached for the upgrade")]
+    fn test_upgrade_without_enough_gas() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .prepaid_gas(near_sdk::Gas(10_000_000_000_000))
+            .attached_deposit(1)
+            .build());
+        contract.upgrade(vec![0, 97, 115, 109]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Code hash is not approved, approve it first or use `upgrade`")]
+    fn test_upgrade_raw_requires_approval() {
+        let mut contract = setup_contract();
+
+        let mut context = get_context(accounts(0)).build();
+        context.input = vec![0, 97, 115, 109];
+        testing_env!(context);
+        contract.upgrade_raw();
+    }
+
+    #[test]
+    fn test_upgrade_raw_with_owner_approval() {
+        let mut contract = setup_contract();
+        let code = vec![0, 97, 115, 109];
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.approve_upgrade(near_sdk::bs58::encode(env::sha256_array(&code)).into_string());
+
+        let mut context = get_context(accounts(0)).build();
+        context.input = code;
+        testing_env!(context);
+        contract.upgrade_raw();
+        assert!(contract.approved_upgrade.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires guardian approval")]
+    fn test_approve_upgrade_after_guardian_setup() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.setup_guardians(vec![accounts(1), accounts(2)], 2);
+        contract.approve_upgrade("hash".to_string());
+    }
+
+    #[test]
+    fn test_guardian_transfer_ownership() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.setup_guardians(vec![accounts(1), accounts(2), accounts(3)], 2);
+
+        let proposal_id = contract
+            .propose_guarded_action(GuardedAction::TransferOwnership {
+                new_owner: accounts(4),
+            })
+            .unwrap();
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert!(!contract.confirm_guarded_action(proposal_id));
+        assert_eq!(contract.owner, accounts(0));
+
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        assert!(contract.confirm_guarded_action(proposal_id));
+        assert_eq!(contract.owner, accounts(4));
+        assert!(contract.get_guarded_actions(None, None).items.is_empty());
+    }
+
+    #[test]
+    fn test_guarded_action_without_guardians_executes_immediately() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        let proposal_id = contract.propose_guarded_action(GuardedAction::TransferOwnership {
+            new_owner: accounts(1),
+        });
+        assert!(proposal_id.is_none());
+        assert_eq!(contract.owner, accounts(1));
+    }
+
+    #[test]
+    fn test_timelocked_config_change() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetConfigTimelock {
+            delay_ms: U64(ONE_DAY),
+        });
+        let change_id =
+            contract.queue_config_change(ConfigChange::SetSpinWheelPrice { price: U128(8) });
+        assert_eq!(
+            contract.get_pending_config_changes(None, None).items.len(),
+            1
+        );
+        assert_eq!(contract.spin_wheel_price, 5);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.execute_config_change(change_id);
+        assert_eq!(contract.spin_wheel_price, 8);
+        assert!(contract
+            .get_pending_config_changes(None, None)
+            .items
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Config change is still timelocked")]
+    fn test_config_change_before_timelock() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetConfigTimelock {
+            delay_ms: U64(ONE_DAY),
+        });
+        let change_id =
+            contract.queue_config_change(ConfigChange::SetDailyClaimPoints { points: U128(20) });
+        contract.execute_config_change(change_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_config_change_requires_one_yocto() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.queue_config_change(ConfigChange::SetSpinWheelPrice { price: U128(8) });
+    }
+
+    #[test]
+    fn test_spin_wheel_uses_configured_segments() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetWheelSegments {
+            segments: vec![WheelSegment {
+                points: U128(4),
+                weight: 1,
+                boost_weight: 0,
+                resets_boost: true,
+                item: None,
+            }],
+        });
+
+        testing_env!(get_context(accounts(1)).build());
+        assert_eq!(contract.play_spin_wheel(false).0, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wheel must have a segment that resets the boost")]
+    fn test_wheel_without_resetting_segment() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetWheelSegments {
+            segments: vec![WheelSegment {
+                points: U128(4),
+                weight: 1,
+                boost_weight: 0,
+                resets_boost: false,
+                item: None,
+            }],
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "less than the minimum storage balance")]
+    fn test_register_requires_storage_deposit() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.register_account();
+    }
+
+    #[test]
+    fn test_storage_deposit_registers_and_withdraws() {
+        let mut contract = setup_contract();
+        let min = contract.storage_balance_bounds().min.0;
+
+        testing_env!(get_context(accounts(2)).attached_deposit(min * 3).build());
+        contract.storage_deposit(Some(accounts(1)), None);
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT);
+
+        let balance = contract.storage_balance_of(accounts(1)).unwrap();
+        assert_eq!(balance.total.0, min * 3);
+        assert_eq!(balance.available.0, min * 2);
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        let balance = contract.storage_withdraw(Some(U128(min)));
+        assert_eq!(balance.available.0, min);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient storage balance")]
+    fn test_ticket_requires_storage_balance() {
+        let mut contract = setup_contract();
+        let min = contract.storage_balance_bounds().min.0;
+
+        testing_env!(get_context(accounts(1)).attached_deposit(min).build());
+        contract.storage_deposit(None, Some(true));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+    }
+
+    #[test]
+    fn test_unregister_account_cleans_up() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_staking_option(7, 0);
+        contract.adjust_points(accounts(1), I128(5), "bonus".to_string(), None);
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.stake_points(U128(10), 7);
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert!(contract.unregister_account(true));
+
+        assert!(contract.storage_balance_of(accounts(1)).is_none());
+        assert!(contract.get_user_stakes(accounts(1)).is_empty());
+        assert!(contract
+            .get_user_history(accounts(1), None, None)
+            .items
+            .is_empty());
+        assert_eq!(contract.supply.staked, 0);
+        assert_eq!(contract.supply.burned, INIT_POINT + 5);
+        assert!(contract.get_users(None, None).items.is_empty());
+        assert!(!contract.unregister_account(true));
+    }
+
+    #[test]
+    #[should_panic(expected = "use force to forfeit them")]
+    fn test_unregister_without_force_requires_empty_account() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.storage_unregister(None);
+    }
+
+    #[test]
+    fn test_reregistering_does_not_grant_init_points() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert!(contract.unregister_account(true));
+
+        register(&mut contract, accounts(1));
+        assert_eq!(contract.get_user(accounts(1)).points.0, 0);
+        assert_eq!(contract.supply.issued, INIT_POINT);
+    }
+
+    fn list_badge(contract: &mut ArkanaCoreContract, seller_id: AccountId) -> U64 {
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.set_item(
+            "badge".to_string(),
+            Some(ItemDefinition {
+                name: "Badge".to_string(),
+                effect: ItemEffect::Collectible,
+                media: None,
+            }),
+        );
+        contract.internal_grant_item(&seller_id, &"badge".to_string(), 1);
+
+        testing_env!(get_context(seller_id).build());
+        contract.list_item("badge".to_string(), U64(1), U128(5))
+    }
+
+    #[test]
+    #[should_panic(expected = "Settle poll locks, predictions, bids and listings")]
+    fn test_unregister_with_open_listing() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        list_badge(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.unregister_account(true);
+    }
+
+    #[test]
+    fn test_unregister_after_cancelling_listing() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let listing_id = list_badge(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.cancel_listing(listing_id);
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert!(contract.unregister_account(true));
+    }
+
+    #[test]
+    fn test_rate_limit_resets_after_window() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_rate_limit(2, U64(60_000));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.play_spin_wheel(false);
+        contract.play_spin_wheel(false);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(60_000 * 1_000_000)
+            .build());
+        contract.play_spin_wheel(false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rate limit exceeded")]
+    fn test_rate_limit_blocks_excess_actions() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_rate_limit(2, U64(60_000));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.play_spin_wheel(false);
+        contract.play_spin_wheel(false);
+        contract.play_spin_wheel(false);
+    }
+
+    #[test]
+    fn test_dao_owner_actions_emit_events() {
+        let dao_id: AccountId = "community.sputnik-dao.near".parse().unwrap();
+        testing_env!(get_context(dao_id.clone()).build());
+        let mut contract = ArkanaCoreContract::new(dao_id.clone(), U128(10), U128(5));
+
+        // DAO function call proposals have a different signer than predecessor
+        testing_env!(get_context(dao_id).signer_account_id(accounts(3)).build());
+        contract.set_rate_limit(3, U64(60_000));
+
+        let logs = near_sdk::test_utils::get_logs();
+        let event: serde_json::Value =
+            serde_json::from_str(logs.last().unwrap().trim_start_matches("EVENT_JSON:")).unwrap();
+        assert_eq!(event["event"], "admin_action");
+        assert_eq!(event["data"][0]["method"], "set_rate_limit");
+        assert_eq!(event["data"][0]["actor_id"], "community.sputnik-dao.near");
+        assert_eq!(event["data"][0]["args"]["window_ms"], "60000");
+    }
+
+    #[test]
+    fn test_point_generator_daily_cap() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_point_generator(accounts(4), U128(10));
+
+        testing_env!(get_context(accounts(4)).build());
+        contract.generate_points(accounts(1), U128(6), None);
+
+        testing_env!(get_context(accounts(4))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(
+            contract.generate_points(accounts(1), U128(6), None).0,
+            INIT_POINT + 12
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Daily generation cap exceeded")]
+    fn test_point_generator_over_cap() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_point_generator(accounts(4), U128(10));
+
+        testing_env!(get_context(accounts(4)).build());
+        contract.generate_points(accounts(1), U128(6), None);
+        contract.generate_points(accounts(1), U128(6), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_generate_points_unauthorized() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(4)).build());
+        contract.generate_points(accounts(1), U128(6), None);
+    }
+
+    #[test]
+    fn test_membership_contract_multiplier_and_cap() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_membership_nft_contract(accounts(5));
+        contract.set_membership_contract_config(
+            accounts(5),
+            20_000,
+            U128(30),
+            vec![MembershipMethod::GeneratePoints],
+        );
+
+        testing_env!(get_context(accounts(5)).build());
+        assert_eq!(
+            contract.generate_points(accounts(1), U128(10), None).0,
+            INIT_POINT + 20
+        );
+
+        let config = serde_json::to_value(contract.get_membership_contract(accounts(5))).unwrap();
+        assert_eq!(config["issued_today"], "20");
+        assert_eq!(config["allowed_methods"][0], "generate_points");
+    }
+
+    #[test]
+    #[should_panic(expected = "Method not allowed")]
+    fn test_membership_contract_method_not_allowed() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_membership_nft_contract(accounts(5));
+        contract.set_membership_contract_config(accounts(5), 10_000, U128(0), vec![]);
+
+        testing_env!(get_context(accounts(5)).build());
+        contract.generate_points(accounts(1), U128(10), None);
+    }
+
+    #[test]
+    fn test_contract_metadata_lists_standards() {
+        let contract = setup_contract();
+
+        let metadata = serde_json::to_value(contract.contract_metadata()).unwrap();
+        assert_eq!(metadata["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata["state_version"], CURRENT_STATE_VERSION);
+        assert!(metadata["standards"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["standard"] == "nep145"));
+    }
+
+    #[test]
+    fn test_configurable_claim_cooldown() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetDailyClaimCooldown {
+            cooldown_ms: U64(ONE_DAY / 2),
+        });
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY / 2 * 1_000_000)
+            .build());
+        assert_eq!(contract.daily_claim_point().0, INIT_POINT + 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cooldown must be between")]
+    fn test_cooldown_bounds() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetFreeSpinCooldown {
+            cooldown_ms: U64(1_000),
+        });
+    }
+
+    #[test]
+    fn test_failed_withdrawal_rolls_back() {
+        let mut contract = setup_contract();
+        contract.treasury_balance = 1_000;
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_treasurer(accounts(2));
+
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        contract.withdraw_treasury(U128(400), accounts(2));
+        assert_eq!(contract.treasury_balance, 600);
+        assert_eq!(contract.get_pending_operations(None, None).items.len(), 1);
+
+        testing_env!(
+            get_context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_operation_resolved(U64(1)));
+        assert_eq!(contract.treasury_balance, 1_000);
+        assert!(contract.get_pending_operations(None, None).items.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Arithmetic overflow")]
+    fn test_ticket_price_overflow_is_rejected() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(u128::MAX / 2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(3));
+    }
+
+    #[test]
+    fn test_staked_nft_boosts_claims_and_spins() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_membership_nft_contract(accounts(5));
+        contract.set_membership_contract_config(
+            accounts(5),
+            10_000,
+            U128(0),
+            vec![MembershipMethod::StakeNft],
+        );
+        contract.set_membership_nft_boost(
+            accounts(5),
+            NftBoost {
+                claim_bonus_bps: 5_000,
+                extra_free_spins: 1,
+            },
+        );
+
+        testing_env!(get_context(accounts(5)).build());
+        contract.nft_on_transfer(accounts(1), accounts(1), "1".to_string(), "".to_string());
+        assert_eq!(contract.get_staked_nfts(accounts(1)).len(), 1);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.daily_claim_point().0, INIT_POINT + 15);
+        contract.play_spin_wheel(true);
+        contract.play_spin_wheel(true);
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.unstake_nft(accounts(5), "1".to_string());
+        assert!(contract.get_staked_nfts(accounts(1)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Method not allowed")]
+    fn test_nft_staking_requires_allowed_collection() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_membership_nft_contract(accounts(5));
+
+        testing_env!(get_context(accounts(5)).build());
+        contract.nft_on_transfer(accounts(1), accounts(1), "1".to_string(), "".to_string());
+    }
+
+    #[test]
+    fn test_verified_membership_is_cached_with_ttl() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_membership_nft_contract(accounts(4));
+        contract.add_membership_nft_contract(accounts(5));
+        contract.set_membership_nft_boost(
+            accounts(4),
+            NftBoost {
+                claim_bonus_bps: 10_000,
+                extra_free_spins: 0,
+            },
+        );
+
+        testing_env!(
+            get_context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![
+                near_sdk::PromiseResult::Successful(b"\"1\"".to_vec()),
+                near_sdk::PromiseResult::Successful(b"\"0\"".to_vec()),
+            ],
+        );
+        let collections =
+            contract.on_membership_verified(accounts(1), vec![accounts(4), accounts(5)]);
+        assert_eq!(collections, vec![accounts(4)]);
+        assert_eq!(contract.get_nft_boost(accounts(1)).claim_bonus_bps, 10_000);
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(MEMBERSHIP_CACHE_TTL * 1_000_000)
+            .build());
+        assert_eq!(contract.get_nft_boost(accounts(1)).claim_bonus_bps, 0);
+    }
+
+    // Calls `ft_on_transfer` for accounts(1), the token being the predecessor
+    fn ft_transfer_call(contract: &mut ArkanaCoreContract, amount: u128, msg: &str) -> u128 {
+        match contract.ft_on_transfer(accounts(1), U128(amount), msg.to_string()) {
+            near_sdk::PromiseOrValue::Value(unused) => unused.0,
+            near_sdk::PromiseOrValue::Promise(_) => panic!("Unexpected promise"),
+        }
+    }
+
+    #[test]
+    fn test_ft_payment_for_spin_refunds_overpayment() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_token_rate(token_id.clone(), Some(U128(100)));
+
+        testing_env!(get_context(token_id.clone()).build());
+        assert_eq!(
+            ft_transfer_call(&mut contract, 700, r#"{"action":"spin"}"#),
+            200
+        );
+
+        let tokens = serde_json::to_value(contract.get_accepted_tokens(None, None).items).unwrap();
+        assert_eq!(tokens[0]["collected"], "500");
+
+        testing_env!(get_context(accounts(3)).build());
+        assert_eq!(
+            ft_transfer_call(&mut contract, 700, r#"{"action":"spin"}"#),
+            700
+        );
+    }
+
+    #[test]
+    fn test_guarded_token_treasury_withdrawal() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_token_rate(token_id.clone(), Some(U128(100)));
+
+        testing_env!(get_context(token_id.clone()).build());
+        ft_transfer_call(&mut contract, 500, r#"{"action":"spin"}"#);
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.setup_guardians(vec![accounts(2), accounts(3)], 2);
+        let proposal_id = contract
+            .propose_guarded_action(GuardedAction::WithdrawTokenTreasury {
+                token_id,
+                amount: U128(500),
+                receiver_id: accounts(0),
+            })
+            .unwrap();
+
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        contract.confirm_guarded_action(proposal_id);
+        testing_env!(get_context(accounts(3)).attached_deposit(1).build());
+        assert!(contract.confirm_guarded_action(proposal_id));
+
+        let tokens = serde_json::to_value(contract.get_accepted_tokens(None, None).items).unwrap();
+        assert_eq!(tokens[0]["collected"], "0");
+    }
+
+    #[test]
+    #[should_panic(expected = "Account is blacklisted")]
+    fn test_ft_spin_rejects_blacklisted_account() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_token_rate(token_id.clone(), Some(U128(100)));
+        contract.blacklist_account(accounts(1), "bot".to_string());
+
+        testing_env!(get_context(token_id).build());
+        ft_transfer_call(&mut contract, 500, r#"{"action":"spin"}"#);
+    }
+
+    #[test]
+    fn test_ft_claim_booster() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_token_rate(token_id.clone(), Some(U128(1)));
+        contract.queue_config_change(ConfigChange::SetClaimBooster {
+            booster: ClaimBooster {
+                price: U128(20),
+                bonus_bps: 10_000,
+                duration_ms: U64(ONE_DAY),
+            },
+        });
+
+        testing_env!(get_context(token_id.clone())
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(
+            ft_transfer_call(&mut contract, 20, r#"{"action":"buy_claim_booster"}"#),
+            0
+        );
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        assert_eq!(contract.daily_claim_point().0, INIT_POINT + 20);
+    }
+
+    #[test]
+    fn test_onboard_account_registers_and_seeds_points() {
+        let mut contract = setup_contract();
+        let keypom_id: AccountId = "keypom.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_onboarder(keypom_id.clone(), U128(50), U128(100));
+
+        testing_env!(get_context(keypom_id.clone())
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.onboard_account(accounts(1));
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 50);
+
+        // A second claim for the same account is a no-op
+        contract.onboard_account(accounts(1));
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 50);
+
+        let onboarders = serde_json::to_value(contract.get_onboarders(None, None).items).unwrap();
+        assert_eq!(onboarders[0]["issued_today"], "50");
+    }
+
+    #[test]
+    fn test_onboarding_returning_account_grants_no_points() {
+        let mut contract = setup_contract();
+        let keypom_id: AccountId = "keypom.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.set_onboarder(keypom_id.clone(), U128(50), U128(100));
+
+        testing_env!(get_context(keypom_id.clone())
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.onboard_account(accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        assert!(contract.unregister_account(true));
+
+        testing_env!(get_context(keypom_id)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.onboard_account(accounts(1));
+        assert_eq!(contract.get_user(accounts(1)).points.0, 0);
+    }
+
+    #[test]
+    fn test_relayed_spin_uses_predecessor() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        // Delegate action: the user is predecessor, the relayer signs
+        testing_env!(get_context(accounts(1))
+            .signer_account_id(accounts(4))
+            .build());
+        let result = contract.play_spin_wheel(false);
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 5 + result.0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Relayed calls are disabled")]
+    fn test_require_direct_signer_blocks_relayed_calls() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetRequireDirectSigner { required: true });
+
+        testing_env!(get_context(accounts(1))
+            .signer_account_id(accounts(4))
+            .build());
+        contract.play_spin_wheel(false);
+    }
+
+    #[test]
+    fn test_linked_wallet_plays_for_primary() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.request_wallet_link(accounts(2));
+
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        contract.confirm_wallet_link(accounts(1));
+        assert_eq!(contract.get_linked_wallets(accounts(1)), vec![accounts(2)]);
+
+        let result = contract.play_spin_wheel(false);
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 5 + result.0
+        );
+
+        contract.unlink_wallet(accounts(2));
+        assert!(contract.get_primary_account(accounts(2)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending link for this wallet")]
+    fn test_wallet_link_requires_request() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        contract.confirm_wallet_link(accounts(1));
+    }
+
+    // Feeds a NEAR price of $5 (oracle format: 24 + 4 decimals)
+    fn set_near_usd_price(contract: &mut ArkanaCoreContract, timestamp_ms: u64) {
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_price_oracle(Some(PriceOracleConfig {
+            oracle_id: accounts(5),
+            asset_id: "wrap.near".to_string(),
+            max_price_age_ms: U64(60_000),
+        }));
+
+        let price_data = serde_json::json!({
+            "timestamp": (timestamp_ms * 1_000_000).to_string(),
+            "recency_duration_sec": 90,
+            "prices": [{
+                "asset_id": "wrap.near",
+                "price": { "multiplier": "50000", "decimals": 28 },
+            }],
+        });
+        testing_env!(
+            get_context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(
+                serde_json::to_vec(&price_data).unwrap()
+            )],
+        );
+        contract.on_near_usd_price();
+    }
+
+    #[test]
+    fn test_usd_priced_spin() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        set_near_usd_price(&mut contract, 0);
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetSpinWheelPriceUsd {
+            price_cents: U128(250),
+        });
+
+        testing_env!(get_context(accounts(1)).attached_deposit(ONE_NEAR).build());
+        contract.play_spin_wheel_with_near();
+        assert_eq!(contract.treasury_balance, ONE_NEAR / 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "NEAR price is stale")]
+    fn test_usd_priced_spin_rejects_stale_price() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        set_near_usd_price(&mut contract, 0);
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetSpinWheelPriceUsd {
+            price_cents: U128(250),
+        });
+
+        testing_env!(get_context(accounts(1))
+            .block_timestamp(60_001 * 1_000_000)
+            .attached_deposit(ONE_NEAR)
+            .build());
+        contract.play_spin_wheel_with_near();
+    }
+
+    #[test]
+    fn test_keeper_executes_due_task_for_bounty() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(ONE_NEAR).build());
+        let task_id =
+            contract.schedule_task(ScheduledAction::StartNewSeason, U64(ONE_DAY), U128(1_000));
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.execute_task(task_id);
+
+        assert_eq!(contract.current_season, 1);
+        assert!(contract.get_scheduled_tasks(None, None).items.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Task is not due yet")]
+    fn test_task_not_due() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(ONE_NEAR).build());
+        let task_id =
+            contract.schedule_task(ScheduledAction::StartNewSeason, U64(ONE_DAY), U128(1_000));
+
+        testing_env!(get_context(accounts(3)).build());
+        contract.execute_task(task_id);
+    }
+
+    #[test]
+    fn test_scheduled_config_change_is_queued() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(ONE_NEAR).build());
+        let task_id = contract.schedule_task(
+            ScheduledAction::ApplyConfigChange {
+                change: ConfigChange::SetSpinWheelPrice { price: U128(8) },
+            },
+            U64(ONE_DAY),
+            U128(1_000),
+        );
+        let pending = contract.get_pending_config_changes(None, None).items;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&pending[0]).unwrap()["executable_at"],
+            ONE_DAY.to_string()
+        );
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.execute_task(task_id);
+
+        assert_eq!(contract.spin_wheel_price, 8);
+        assert!(contract
+            .get_pending_config_changes(None, None)
+            .items
+            .is_empty());
+    }
+
+    #[test]
+    fn test_cancelling_task_cancels_its_config_change() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(ONE_NEAR).build());
+        let task_id = contract.schedule_task(
+            ScheduledAction::ApplyConfigChange {
+                change: ConfigChange::SetSpinWheelPrice { price: U128(8) },
+            },
+            U64(ONE_DAY),
+            U128(1_000),
+        );
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.cancel_task(task_id);
+
+        assert!(contract
+            .get_pending_config_changes(None, None)
+            .items
+            .is_empty());
+        assert!(contract.get_scheduled_tasks(None, None).items.is_empty());
+    }
+
+    #[test]
+    fn test_stale_finalize_task_is_dropped() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(2), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(ONE_NEAR).build());
+        let task_id = contract.schedule_task(
+            ScheduledAction::FinalizeReward { reward_id: U64(1) },
+            U64(ONE_DAY),
+            U128(1_000),
+        );
+        contract.cancel_reward(U64(1));
+
+        testing_env!(get_context(accounts(3))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.execute_task(task_id);
+
+        assert!(contract.get_scheduled_tasks(None, None).items.is_empty());
+        assert!(contract.internal_get_reward(1).status == RewardStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_social_profile_shows_in_user_listing() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        let social_data = serde_json::json!({
+            accounts(1).to_string(): {
+                "profile": { "name": "Bob", "image": { "ipfs_cid": "bafy" } },
+            },
+        });
+        testing_env!(
+            get_context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(
+                serde_json::to_vec(&social_data).unwrap()
+            )],
+        );
+        contract.on_social_profile(accounts(1));
+
+        let users = serde_json::to_value(contract.get_users(None, None).items).unwrap();
+        assert_eq!(users[0]["profile"]["name"], "Bob");
+        assert_eq!(
+            users[0]["profile"]["avatar_url"],
+            "https://ipfs.near.social/ipfs/bafy"
+        );
+        assert!(users[1]["profile"].is_null());
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.remove_social_profile();
+        assert!(contract.get_social_profile(accounts(1)).is_none());
+    }
+
+    #[test]
+    fn test_level_up_notifies_partner_hook() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_partner_hook(accounts(4), vec![HookEvent::LevelUp], U64(MAX_HOOK_GAS));
+        contract.adjust_points(
+            accounts(1),
+            I128(XP_PER_LEVEL as i128),
+            "quest".to_string(),
+            None,
+        );
+
+        assert_eq!(contract.get_user(accounts(1)).level, 1);
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"level_up\"")));
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, accounts(4));
+    }
+
+    #[test]
+    fn test_partner_completes_quest_once() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_quest("swap-1".to_string(), accounts(4), U128(40), U64(10));
+
+        testing_env!(get_context(accounts(4)).build());
+        let points =
+            contract.on_quest_completed(accounts(1), "swap-1".to_string(), "tx".to_string());
+        assert_eq!(points.0, INIT_POINT + 40);
+        assert!(contract.is_quest_completed(accounts(1), "swap-1".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Quest already completed")]
+    fn test_quest_completion_is_deduplicated() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_quest("swap-1".to_string(), accounts(4), U128(40), U64(0));
+
+        testing_env!(get_context(accounts(4)).build());
+        contract.on_quest_completed(accounts(1), "swap-1".to_string(), "tx".to_string());
+        contract.on_quest_completed(accounts(1), "swap-1".to_string(), "tx".to_string());
+    }
+
+    #[test]
+    fn test_first_win_mints_badge_and_failed_mint_is_retryable() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+        contract.set_badge_config(Some(BadgeConfig {
+            contract_id: accounts(4),
+            mint_deposit: U128(100),
+        }));
+        contract.set_badge(
+            Achievement::FirstWin,
+            Some(BadgeMetadata {
+                title: "First win".to_string(),
+                description: None,
+                media: None,
+            }),
+        );
+        contract.treasury_balance = 1_000;
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(1));
+
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(ONE_DAY * 1_000_000)
+            .build());
+        contract.finalize_reward(U64(1), false);
+        assert!(contract
+            .get_badge_status(accounts(1), Achievement::FirstWin)
+            .is_some());
+        assert_eq!(contract.treasury_balance, 900);
+
+        testing_env!(
+            get_context(accounts(0)).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_badge_minted(accounts(1), Achievement::FirstWin, U128(100)));
+        assert!(contract
+            .get_badge_status(accounts(1), Achievement::FirstWin)
+            .is_none());
+        assert_eq!(contract.treasury_balance, 1_000);
+
+        testing_env!(get_context(accounts(1)).build());
+        assert!(contract.claim_badge(Achievement::FirstWin));
+    }
+
+    #[test]
+    fn test_export_users_and_rewards() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(3));
+
+        let users = serde_json::to_value(contract.export_users(None, None).items).unwrap();
+        assert_eq!(users[0]["points"], (INIT_POINT - 3).to_string());
+        assert_eq!(users[0]["storage_deposit"], STORAGE_DEPOSIT.to_string());
+
+        let rewards = serde_json::to_value(contract.export_rewards(None, None).items).unwrap();
+        assert_eq!(rewards[0]["total_tickets"], "3");
+
+        let tickets =
+            serde_json::to_value(contract.export_reward_tickets(U64(1), None, None).items).unwrap();
+        assert_eq!(tickets[0]["account_id"], accounts(1).to_string());
+    }
+
+    #[test]
+    fn test_bridge_out_and_back_in() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_bridge_chain("evm-side".to_string());
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.bridge_out(U128(10), "evm-side".to_string(), "0xabc".to_string());
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT - 10);
+
+        testing_env!(get_context(accounts(0)).build());
+        let points = contract.bridge_in(
+            "evm-side".to_string(),
+            "0xburn".to_string(),
+            accounts(1),
+            U128(4),
+        );
+        assert_eq!(points.0, INIT_POINT - 6);
+        assert_eq!(contract.supply.bridged, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer already processed")]
+    fn test_bridge_in_replay_is_rejected() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_bridge_chain("evm-side".to_string());
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.bridge_out(U128(10), "evm-side".to_string(), "0xabc".to_string());
+
+        testing_env!(get_context(accounts(0)).build());
+        for _ in 0..2 {
+            contract.bridge_in(
+                "evm-side".to_string(),
+                "0xburn".to_string(),
+                accounts(1),
+                U128(4),
+            );
+        }
+    }
+
+    #[test]
+    fn test_ft_payment_for_tickets_fills_partially() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        let token_id: AccountId = "usdc.near".parse().unwrap();
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(5), U64(ONE_DAY));
+        contract.set_token_rate(token_id.clone(), Some(U128(100)));
+
+        // 1_250 covers two tickets of 500
+        testing_env!(get_context(token_id).build());
+        assert_eq!(
+            ft_transfer_call(
+                &mut contract,
+                1_250,
+                r#"{"action":"buy_ticket","reward_id":1,"amount":3}"#
+            ),
+            250
+        );
+        assert_eq!(contract.get_reward(U64(1)).total_tickets.0, 2);
+    }
+
+    #[test]
+    fn test_authorized_relayer_spins_for_user() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.authorize_spin_relayer(accounts(3), U64(2));
+
+        testing_env!(get_context(accounts(3)).build());
+        let result = contract.play_spin_wheel_for(accounts(1), false);
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 5 + result.0
+        );
+        let relayers = serde_json::to_value(contract.get_spin_relayers(accounts(1))).unwrap();
+        assert_eq!(relayers[0]["used_today"], "1");
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.revoke_spin_relayer(accounts(3));
+        assert!(contract.get_spin_relayers(accounts(1)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Relayer daily spin limit reached")]
+    fn test_relayer_daily_spin_limit() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1).build());
+        contract.authorize_spin_relayer(accounts(3), U64(1));
+
+        testing_env!(get_context(accounts(3)).build());
+        contract.play_spin_wheel_for(accounts(1), false);
+        contract.play_spin_wheel_for(accounts(1), false);
+    }
+
+    fn staked_balance_callback(
+        contract: &mut ArkanaCoreContract,
+        epoch_height: u64,
+        staked_balance: Balance,
+    ) -> U128 {
+        testing_env!(
+            get_context(accounts(0)).epoch_height(epoch_height).build(),
+            near_sdk::VMConfig::test(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(
+                serde_json::to_vec(&U128(staked_balance)).unwrap()
+            )],
+        );
+        contract.on_staked_balance(accounts(1))
+    }
+
+    #[test]
+    fn test_staked_near_accrues_points_per_epoch() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_staking_pool(Some(StakingPoolConfig {
+            pool_id: accounts(4),
+            points_per_near: U128(2),
+        }));
+
+        assert_eq!(
+            staked_balance_callback(&mut contract, 10, 100 * ONE_NEAR).0,
+            0
+        );
+        // Only the 100 NEAR staked since the checkpoint earn for 3 epochs
+        assert_eq!(
+            staked_balance_callback(&mut contract, 13, 500 * ONE_NEAR).0,
+            600
+        );
+        assert_eq!(contract.get_user(accounts(1)).points.0, INIT_POINT + 600);
+    }
+
+    #[test]
+    fn test_dao_proposed_reward_needs_approvals() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.add_reward_proposer(accounts(4));
+        contract.add_operator(accounts(2));
+        contract.set_reward_approval_threshold(2);
+
+        testing_env!(get_context(accounts(4))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        let proposal_id = contract.propose_reward("Merch".to_string(), U128(5), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(2)).build());
+        assert!(contract.approve_reward_proposal(proposal_id).is_none());
+
+        testing_env!(get_context(accounts(0)).build());
+        let reward_id = contract.approve_reward_proposal(proposal_id).unwrap();
+        assert_eq!(contract.get_reward(reward_id).price.0, 5);
+        assert!(contract.get_reward_proposals(None, None).items.is_empty());
+    }
+
+    #[test]
+    fn test_factory_instance_takes_protocol_fee() {
+        testing_env!(get_context(accounts(5)).build());
+        let mut contract = ArkanaCoreContract::new_instance(InstanceConfig {
+            owner: accounts(0),
+            daily_claim_points: U128(10),
+            spin_wheel_price: U128(5),
+            community_name: Some("Guild".to_string()),
+            wheel_segments: None,
+            registration_fee: Some(U128(1_000)),
+            protocol_fee: Some(ProtocolFee {
+                recipient: accounts(5),
+                fee_bps: 1_000,
+            }),
+            event_only_history: false,
+        });
+        assert_eq!(contract.factory_id, Some(accounts(5)));
+
+        register(&mut contract, accounts(1));
+        assert_eq!(contract.treasury_balance, 900);
+        assert_eq!(contract.protocol_fee_balance, 100);
+
+        testing_env!(get_context(accounts(5)).attached_deposit(1).build());
+        contract.withdraw_protocol_fees();
+        assert_eq!(contract.protocol_fee_balance, 0);
+    }
+
+    #[test]
+    fn test_dashboard_shows_open_rewards_and_tickets() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+        register(&mut contract, accounts(2));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(2));
+        testing_env!(get_context(accounts(2)).build());
+        contract.buy_ticket(U64(1), U64(1));
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(3));
+
+        let dashboard = serde_json::to_value(contract.get_dashboard(accounts(1))).unwrap();
+        assert_eq!(dashboard["user"]["points"], (INIT_POINT - 5).to_string());
+        assert_eq!(dashboard["rewards"][0]["user_tickets"], "5");
+        assert_eq!(dashboard["rewards"][0]["total_tickets"], "6");
+        assert_eq!(dashboard["can_spin_free"], false);
+        assert_eq!(dashboard["next_free_spin_at"], ONE_DAY.to_string());
+    }
+
+    #[test]
+    fn test_achievements_grant_one_time_bonus() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0))
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        contract.create_reward("Merch".to_string(), U128(1), U64(ONE_DAY));
+        contract.set_achievement_bonus(Achievement::TenRaffleEntries, U128(50));
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.buy_ticket(U64(1), U64(6));
+        contract.buy_ticket(U64(1), U64(4));
+        contract.buy_ticket(U64(1), U64(1));
+        assert_eq!(
+            contract.get_user(accounts(1)).points.0,
+            INIT_POINT - 11 + 50
+        );
+
+        let achievements = serde_json::to_value(contract.get_achievements(accounts(1))).unwrap();
+        let entries = achievements
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["achievement"] == "ten_raffle_entries")
+            .unwrap();
+        assert_eq!(entries["unlocked"], true);
+    }
+
+    #[test]
+    fn test_near_spin_and_treasury_withdrawal() {
+        let mut contract = setup_contract();
+        register(&mut contract, accounts(1));
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetSpinWheelPriceNear { price: U128(1_000) });
+        contract.add_treasurer(accounts(2));
+
+        testing_env!(get_context(accounts(1)).attached_deposit(1_500).build());
+        contract.play_spin_wheel_with_near();
+
+        let treasury = serde_json::to_value(contract.get_treasury()).unwrap();
+        assert_eq!(treasury["balance"], "1000");
+
+        testing_env!(get_context(accounts(2)).attached_deposit(1).build());
+        contract.withdraw_treasury(U128(400), accounts(2));
+        assert_eq!(contract.treasury_balance, 600);
+    }
+
+    #[test]
+    fn test_registration_fee_goes_to_treasury() {
+        let mut contract = setup_contract();
+
+        testing_env!(get_context(accounts(0)).attached_deposit(1).build());
+        contract.queue_config_change(ConfigChange::SetRegistrationFee { fee: U128(5_000) });
+
+        register(&mut contract, accounts(1));
+        assert_eq!(contract.treasury_balance, 5_000);
+    }
 }
@@ -0,0 +1,286 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId, Balance};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type LootboxId = u64;
+
+pub const MAX_LOOTBOX_PRIZES: usize = 16;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LootboxPrize {
+    Points { amount: U128 },
+    Tickets { reward_id: U64, amount: U64 },
+    Item { item_id: ItemId, amount: U64 },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct LootboxEntry {
+    pub prize: LootboxPrize,
+    pub weight: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Lootbox {
+    title: String,
+    // Zero when not sold for points or NEAR respectively
+    price: Points,
+    price_near: Balance,
+    entries: Vec<LootboxEntry>,
+    max_supply: Option<u64>,
+    opened: u64,
+}
+
+#[derive(Serialize)]
+pub struct LootboxOutput {
+    lootbox_id: U64,
+    title: String,
+    price: U128,
+    price_near: U128,
+    entries: Vec<LootboxEntry>,
+    max_supply: Option<U64>,
+    opened: U64,
+}
+
+impl ArkanaCoreContract {
+    /// Ticket prizes for rewards that have ended since the box was created,
+    /// or items no longer defined, can no longer be won.
+    fn internal_lootbox_prize_available(&self, prize: &LootboxPrize) -> bool {
+        match prize {
+            LootboxPrize::Points { .. } => true,
+            LootboxPrize::Item { item_id, .. } => self.items.get(item_id).is_some(),
+            LootboxPrize::Tickets { reward_id, .. } => self
+                .rewards
+                .get(&reward_id.0)
+                .map(|reward| reward.is_open(env::block_timestamp_ms()))
+                .unwrap_or(false),
+        }
+    }
+
+    fn internal_draw_lootbox_prize(&mut self, lootbox: &Lootbox) -> LootboxPrize {
+        let weights: Vec<u32> = lootbox
+            .entries
+            .iter()
+            .map(|entry| {
+                if self.internal_lootbox_prize_available(&entry.prize) {
+                    entry.weight as u32
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        let total_weights: u32 = weights.iter().sum();
+        assert!(total_weights > 0, "Lootbox has no prizes left");
+
+        let random_number = self.internal_random_number(RandomPurpose::Lootbox) % total_weights;
+
+        let mut cumulative_weight = 0;
+        for (entry, weight) in lootbox.entries.iter().zip(weights) {
+            cumulative_weight += weight;
+            if random_number < cumulative_weight {
+                return entry.prize.clone();
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Checks the box can be opened and counts the opening. Payment is taken
+    /// by the caller.
+    fn internal_take_lootbox(&mut self, lootbox_id: LootboxId) -> Lootbox {
+        self.assert_not_paused(PauseFeature::All);
+
+        let mut lootbox = self
+            .lootboxes
+            .get(&lootbox_id)
+            .expect("Lootbox does not exist");
+
+        if let Some(max_supply) = lootbox.max_supply {
+            assert!(lootbox.opened < max_supply, "Lootbox is sold out");
+        }
+
+        lootbox.opened += 1;
+        self.lootboxes.insert(&lootbox_id, &lootbox);
+
+        lootbox
+    }
+
+    fn internal_open_lootbox(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        lootbox_id: LootboxId,
+        lootbox: &Lootbox,
+    ) -> LootboxPrize {
+        let prize = self.internal_draw_lootbox_prize(lootbox);
+
+        match &prize {
+            LootboxPrize::Points { amount } => {
+                self.internal_issue_points(account_id, user, amount.0);
+            }
+            LootboxPrize::Tickets { reward_id, amount } => {
+                let initial_storage_usage = env::storage_usage();
+                self.internal_add_tickets(reward_id.0, account_id, amount.0);
+                self.internal_record_raffle_entries(account_id, user, amount.0);
+                self.internal_update_storage_usage(account_id, initial_storage_usage);
+            }
+            LootboxPrize::Item { item_id, amount } => {
+                let initial_storage_usage = env::storage_usage();
+                self.internal_grant_item(account_id, item_id, amount.0);
+                self.internal_update_storage_usage(account_id, initial_storage_usage);
+            }
+        }
+
+        ArkanaEvent::LootboxOpened(vec![LootboxOpenedData {
+            account_id: account_id.clone(),
+            lootbox_id: U64(lootbox_id),
+            prize: prize.clone(),
+        }])
+        .emit();
+
+        prize
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the box's storage, the rest is refunded.
+    #[payable]
+    pub fn create_lootbox(
+        &mut self,
+        title: String,
+        price: Option<U128>,
+        price_near: Option<U128>,
+        entries: Vec<LootboxEntry>,
+        max_supply: Option<U64>,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            !entries.is_empty() && entries.len() <= MAX_LOOTBOX_PRIZES,
+            "Lootbox must have between 1 and {} prizes",
+            MAX_LOOTBOX_PRIZES
+        );
+        assert!(
+            entries.iter().map(|e| e.weight as u32).sum::<u32>() > 0,
+            "Lootbox total weight cannot be zero"
+        );
+        for entry in entries.iter() {
+            match &entry.prize {
+                LootboxPrize::Points { .. } => {}
+                LootboxPrize::Tickets { reward_id, .. } => self.assert_reward_exists(reward_id.0),
+                LootboxPrize::Item { item_id, .. } => self.assert_item_exists(item_id),
+            }
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let lootbox_id = self.last_lootbox_id + 1;
+        self.last_lootbox_id = lootbox_id;
+
+        self.lootboxes.insert(
+            &lootbox_id,
+            &Lootbox {
+                title: title.clone(),
+                price: price.map(|p| p.0).unwrap_or(0),
+                price_near: price_near.map(|p| p.0).unwrap_or(0),
+                entries,
+                max_supply: max_supply.map(|s| s.0),
+                opened: 0,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_lootbox",
+            serde_json::json!({
+                "lootbox_id": U64(lootbox_id),
+                "title": title,
+                "price": price,
+                "price_near": price_near,
+                "max_supply": max_supply,
+            }),
+        );
+
+        U64(lootbox_id)
+    }
+
+    pub fn remove_lootbox(&mut self, lootbox_id: U64) {
+        self.assert_owner();
+
+        self.lootboxes
+            .remove(&lootbox_id.0)
+            .expect("Lootbox does not exist");
+
+        ArkanaEvent::admin_action(
+            "remove_lootbox",
+            serde_json::json!({ "lootbox_id": lootbox_id }),
+        );
+    }
+
+    pub fn open_lootbox(&mut self, lootbox_id: U64) -> LootboxPrize {
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let lootbox = self.internal_take_lootbox(lootbox_id.0);
+        assert!(lootbox.price > 0, "Lootbox is not sold for points");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+
+        if user.points < lootbox.price {
+            panic!("Points insufficient");
+        }
+        self.internal_spend_points(&account_id, &mut user, lootbox.price, SpendKind::Lootbox);
+        self.internal_feed_jackpot(&account_id, &mut user, lootbox.price);
+
+        let prize = self.internal_open_lootbox(&account_id, &mut user, lootbox_id.0, &lootbox);
+        self.internal_save_user(&account_id, &user);
+
+        prize
+    }
+
+    #[payable]
+    pub fn open_lootbox_with_near(&mut self, lootbox_id: U64) -> LootboxPrize {
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        self.assert_not_self_excluded(&account_id);
+
+        let lootbox = self.internal_take_lootbox(lootbox_id.0);
+        assert!(lootbox.price_near > 0, "Lootbox is not sold for NEAR");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+
+        self.internal_charge_near(lootbox.price_near, TreasurySource::Lootbox);
+
+        let prize = self.internal_open_lootbox(&account_id, &mut user, lootbox_id.0, &lootbox);
+        self.internal_save_user(&account_id, &user);
+
+        prize
+    }
+
+    // View Functions
+    pub fn get_lootboxes(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<LootboxOutput> {
+        paginate(self.lootboxes.iter(), from_index, limit).map(|(lootbox_id, lootbox)| {
+            LootboxOutput {
+                lootbox_id: U64(lootbox_id),
+                title: lootbox.title,
+                price: U128(lootbox.price),
+                price_near: U128(lootbox.price_near),
+                entries: lootbox.entries,
+                max_supply: lootbox.max_supply.map(U64),
+                opened: U64(lootbox.opened),
+            }
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::near_bindgen;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Always-on draw: every entry price goes into the pool, and an entry wins
+/// `payout_bps` of the pool with a `win_chance_bps` chance. A zero entry
+/// price disables the draw.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+pub struct LuckyDrawConfig {
+    pub entry_price: U128,
+    pub win_chance_bps: u16,
+    pub payout_bps: u16,
+}
+
+impl Default for LuckyDrawConfig {
+    fn default() -> Self {
+        Self {
+            entry_price: U128(0),
+            win_chance_bps: 0,
+            payout_bps: 0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LuckyDrawOutput {
+    #[serde(flatten)]
+    config: LuckyDrawConfig,
+    pool: U128,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_lucky_draw_config(&mut self, config: LuckyDrawConfig) {
+        self.assert_owner();
+
+        assert!(
+            config.win_chance_bps <= MAX_BPS && config.payout_bps <= MAX_BPS,
+            "Basis points cannot exceed {}",
+            MAX_BPS
+        );
+
+        self.lucky_draw_config = config;
+
+        ArkanaEvent::admin_action(
+            "set_lucky_draw_config",
+            serde_json::json!({ "config": config }),
+        );
+    }
+
+    /// Seeds the pool with newly issued points, e.g. after a big win.
+    pub fn fund_lucky_draw(&mut self, amount: U128) -> U128 {
+        self.assert_owner();
+
+        self.lucky_draw_pool = self.lucky_draw_pool.safe_add(amount.0);
+
+        ArkanaEvent::admin_action("fund_lucky_draw", serde_json::json!({ "amount": amount }));
+
+        U128(self.lucky_draw_pool)
+    }
+
+    /// Pays the entry price and draws right away. Returns the points won,
+    /// zero if the entry went into the pool. Uses the current block's
+    /// randomness, so pools should stay small.
+    pub fn enter_lucky_draw(&mut self) -> U128 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let config = self.lucky_draw_config;
+        assert!(config.entry_price.0 > 0, "Lucky draw is disabled");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+
+        self.internal_spend_points(
+            &account_id,
+            &mut user,
+            config.entry_price.0,
+            SpendKind::Game,
+        );
+        let contribution = self.internal_feed_jackpot(&account_id, &mut user, config.entry_price.0);
+        self.lucky_draw_pool = self
+            .lucky_draw_pool
+            .safe_add(config.entry_price.0 - contribution);
+
+        let roll = self.internal_random_number(RandomPurpose::LuckyDraw) % MAX_BPS as u32;
+        let prize = if roll < config.win_chance_bps as u32 {
+            apply_bps(self.lucky_draw_pool, config.payout_bps as u32)
+        } else {
+            0
+        };
+
+        if prize > 0 {
+            self.lucky_draw_pool -= prize;
+            self.internal_issue_points(&account_id, &mut user, prize);
+        }
+
+        self.internal_save_user(&account_id, &user);
+
+        ArkanaEvent::LuckyDrawEntered(vec![LuckyDrawEnteredData {
+            account_id,
+            prize: U128(prize),
+            pool: U128(self.lucky_draw_pool),
+        }])
+        .emit();
+
+        U128(prize)
+    }
+
+    // View Functions
+    pub fn get_lucky_draw(&self) -> LuckyDrawOutput {
+        LuckyDrawOutput {
+            config: self.lucky_draw_config,
+            pool: U128(self.lucky_draw_pool),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId, StorageUsage};
+use serde::Serialize;
+
+use crate::*;
+
+pub type ListingId = u64;
+
+pub const MAX_MARKETPLACE_FEE_BPS: u16 = 2_000;
+
+/// Items escrowed by the contract until they are bought or the listing is
+/// cancelled.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Listing {
+    seller: AccountId,
+    item_id: ItemId,
+    quantity: u64,
+    price_per_item: Points,
+    created_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct ListingOutput {
+    listing_id: U64,
+    seller: AccountId,
+    item_id: ItemId,
+    quantity: U64,
+    price_per_item: U128,
+    created_at: U64,
+}
+
+impl ArkanaCoreContract {
+    /// Attributes storage changes of a listing to its seller, unless they
+    /// have unregistered since.
+    fn internal_update_seller_storage_usage(
+        &mut self,
+        seller_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        if self.internal_find_user(seller_id).is_some() {
+            self.internal_update_storage_usage(seller_id, initial_storage_usage);
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Share of every sale that is burned instead of paid to the seller.
+    pub fn set_marketplace_fee(&mut self, fee_bps: u16) {
+        self.assert_owner();
+
+        assert!(
+            fee_bps <= MAX_MARKETPLACE_FEE_BPS,
+            "Fee cannot exceed {} bps",
+            MAX_MARKETPLACE_FEE_BPS
+        );
+
+        self.marketplace_fee_bps = fee_bps;
+
+        ArkanaEvent::admin_action(
+            "set_marketplace_fee",
+            serde_json::json!({ "fee_bps": fee_bps }),
+        );
+    }
+
+    /// Moves `quantity` items from the caller's inventory into a listing.
+    pub fn list_item(&mut self, item_id: ItemId, quantity: U64, price_per_item: U128) -> U64 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        assert!(quantity.0 > 0, "Quantity must be greater than zero");
+        assert!(price_per_item.0 > 0, "Price must be greater than zero");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_take_item(&account_id, &item_id, quantity.0);
+
+        let listing_id = self.last_listing_id + 1;
+        self.last_listing_id = listing_id;
+
+        self.internal_open_escrow(&account_id);
+        self.listings.insert(
+            &listing_id,
+            &Listing {
+                seller: account_id.clone(),
+                item_id: item_id.clone(),
+                quantity: quantity.0,
+                price_per_item: price_per_item.0,
+                created_at: env::block_timestamp_ms(),
+            },
+        );
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::ItemListed(vec![ItemListedData {
+            listing_id: U64(listing_id),
+            seller: account_id,
+            item_id,
+            quantity,
+            price_per_item,
+        }])
+        .emit();
+
+        U64(listing_id)
+    }
+
+    /// Returns the unsold items of a listing to the seller.
+    pub fn cancel_listing(&mut self, listing_id: U64) {
+        let account_id = env::predecessor_account_id();
+
+        let listing = self
+            .listings
+            .get(&listing_id.0)
+            .expect("Listing does not exist");
+        assert!(listing.seller == account_id, "Unauthorized");
+
+        let initial_storage_usage = env::storage_usage();
+
+        self.listings.remove(&listing_id.0);
+        self.internal_close_escrow(&account_id);
+        self.internal_grant_item(&account_id, &listing.item_id, listing.quantity);
+
+        self.internal_update_seller_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::ListingCancelled(vec![ListingCancelledData { listing_id }]).emit();
+    }
+
+    /// Buys `quantity` items off a listing. The seller is paid the price minus
+    /// the marketplace fee. Returns the points paid.
+    pub fn buy_listing(&mut self, listing_id: U64, quantity: U64) -> U128 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut listing = self
+            .listings
+            .get(&listing_id.0)
+            .expect("Listing does not exist");
+        assert!(listing.seller != account_id, "Cannot buy your own listing");
+        assert!(
+            quantity.0 > 0 && quantity.0 <= listing.quantity,
+            "Quantity must be between 1 and {}",
+            listing.quantity
+        );
+
+        let price = listing.price_per_item.safe_mul(Points::from(quantity.0));
+        let fee = apply_bps(price, self.marketplace_fee_bps as u32);
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        user.points = user.points.checked_sub(price).expect("Points insufficient");
+        self.internal_save_user(&account_id, &user);
+        self.internal_grant_item(&account_id, &listing.item_id, quantity.0);
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+        let initial_storage_usage = env::storage_usage();
+
+        listing.quantity -= quantity.0;
+        if listing.quantity == 0 {
+            self.listings.remove(&listing_id.0);
+            self.internal_close_escrow(&listing.seller);
+        } else {
+            self.listings.insert(&listing_id.0, &listing);
+        }
+
+        self.supply.burned = self.supply.burned.safe_add(fee);
+        self.internal_return_points(&listing.seller, price - fee);
+
+        self.internal_update_seller_storage_usage(&listing.seller, initial_storage_usage);
+
+        ArkanaEvent::ListingSold(vec![ListingSoldData {
+            listing_id,
+            buyer: account_id,
+            seller: listing.seller,
+            quantity,
+            price: U128(price),
+            fee: U128(fee),
+        }])
+        .emit();
+
+        U128(price)
+    }
+
+    // View Functions
+    pub fn get_marketplace_fee(&self) -> u16 {
+        self.marketplace_fee_bps
+    }
+
+    pub fn get_listings(&self, from_index: Option<U64>, limit: Option<u64>) -> Page<ListingOutput> {
+        paginate(self.listings.iter(), from_index, limit).map(|(listing_id, listing)| {
+            ListingOutput {
+                listing_id: U64(listing_id),
+                seller: listing.seller,
+                item_id: listing.item_id,
+                quantity: U64(listing.quantity),
+                price_per_item: U128(listing.price_per_item),
+                created_at: U64(listing.created_at),
+            }
+        })
+    }
+}
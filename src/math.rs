@@ -0,0 +1,37 @@
+pub const ERR_OVERFLOW: &str = "Arithmetic overflow";
+pub const ERR_UNDERFLOW: &str = "Arithmetic underflow";
+
+/// Arithmetic that fails with an explicit error instead of wrapping or
+/// depending on the build's overflow checks.
+pub trait SafeMath: Sized {
+    fn safe_add(self, rhs: Self) -> Self;
+    fn safe_sub(self, rhs: Self) -> Self;
+    fn safe_mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_safe_math {
+    ($($t:ty),*) => {
+        $(
+            impl SafeMath for $t {
+                fn safe_add(self, rhs: Self) -> Self {
+                    self.checked_add(rhs).expect(ERR_OVERFLOW)
+                }
+
+                fn safe_sub(self, rhs: Self) -> Self {
+                    self.checked_sub(rhs).expect(ERR_UNDERFLOW)
+                }
+
+                fn safe_mul(self, rhs: Self) -> Self {
+                    self.checked_mul(rhs).expect(ERR_OVERFLOW)
+                }
+            }
+        )*
+    };
+}
+
+impl_safe_math!(u32, u64, u128);
+
+/// `amount * bps / MAX_BPS`, failing on overflow instead of wrapping.
+pub fn apply_bps(amount: u128, bps: u32) -> u128 {
+    amount.safe_mul(bps as u128) / crate::MAX_BPS as u128
+}
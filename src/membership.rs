@@ -0,0 +1,215 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MembershipMethod {
+    GeneratePoints,
+    StakeNft,
+    VerifyHolding,
+}
+
+/// Benefits conferred by a whitelisted membership contract. Contracts
+/// whitelisted before configs existed get the default.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MembershipConfig {
+    multiplier_bps: u32,
+    // Zero means unlimited
+    daily_cap: Points,
+    allowed_methods: Vec<MembershipMethod>,
+    usage: DailyUsage,
+    nft_boost: NftBoost,
+}
+
+impl Default for MembershipConfig {
+    fn default() -> Self {
+        Self {
+            multiplier_bps: MAX_BPS as u32,
+            daily_cap: 0,
+            allowed_methods: vec![MembershipMethod::GeneratePoints],
+            usage: DailyUsage::default(),
+            nft_boost: NftBoost::default(),
+        }
+    }
+}
+
+impl MembershipConfig {
+    fn assert_allows(&self, method: MembershipMethod) {
+        assert!(
+            self.allowed_methods.contains(&method),
+            "Method not allowed for this membership contract"
+        );
+    }
+}
+
+#[derive(Serialize)]
+pub struct MembershipContractOutput {
+    contract_id: AccountId,
+    multiplier_bps: u32,
+    daily_cap: U128,
+    allowed_methods: Vec<MembershipMethod>,
+    issued_today: U128,
+    nft_boost: NftBoost,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_assert_membership_method(
+        &self,
+        contract_id: &AccountId,
+        method: MembershipMethod,
+    ) {
+        self.membership_configs
+            .get(contract_id)
+            .unwrap_or_default()
+            .assert_allows(method);
+    }
+
+    pub(crate) fn internal_membership_allows(
+        &self,
+        contract_id: &AccountId,
+        method: MembershipMethod,
+    ) -> bool {
+        self.membership_configs
+            .get(contract_id)
+            .unwrap_or_default()
+            .allowed_methods
+            .contains(&method)
+    }
+
+    pub(crate) fn internal_membership_nft_boost(&self, contract_id: &AccountId) -> NftBoost {
+        self.membership_configs
+            .get(contract_id)
+            .unwrap_or_default()
+            .nft_boost
+    }
+
+    /// Checks that `contract_id` may call `method` and returns `points` with
+    /// its multiplier applied, counted against its daily cap.
+    pub(crate) fn internal_apply_membership_config(
+        &mut self,
+        contract_id: &AccountId,
+        method: MembershipMethod,
+        points: Points,
+    ) -> Points {
+        let mut config = self.membership_configs.get(contract_id).unwrap_or_default();
+
+        config.assert_allows(method);
+
+        let points = apply_bps(points, config.multiplier_bps);
+
+        if config.daily_cap > 0 {
+            config.usage.consume(points, config.daily_cap);
+            self.membership_configs.insert(contract_id, &config);
+        }
+
+        points
+    }
+
+    fn internal_membership_contract_output(
+        &self,
+        contract_id: AccountId,
+    ) -> MembershipContractOutput {
+        let config = self
+            .membership_configs
+            .get(&contract_id)
+            .unwrap_or_default();
+
+        MembershipContractOutput {
+            contract_id,
+            multiplier_bps: config.multiplier_bps,
+            daily_cap: U128(config.daily_cap),
+            allowed_methods: config.allowed_methods,
+            issued_today: U128(config.usage.used_today()),
+            nft_boost: config.nft_boost,
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_membership_contract_config(
+        &mut self,
+        contract_id: AccountId,
+        multiplier_bps: u32,
+        daily_cap: U128,
+        allowed_methods: Vec<MembershipMethod>,
+    ) {
+        self.assert_owner();
+
+        assert!(
+            self.membership_contracts.contains(&contract_id),
+            "Membership contract is not whitelisted"
+        );
+
+        let config = self
+            .membership_configs
+            .get(&contract_id)
+            .unwrap_or_default();
+
+        self.membership_configs.insert(
+            &contract_id,
+            &MembershipConfig {
+                multiplier_bps,
+                daily_cap: daily_cap.0,
+                allowed_methods: allowed_methods.clone(),
+                ..config
+            },
+        );
+
+        ArkanaEvent::admin_action(
+            "set_membership_contract_config",
+            serde_json::json!({
+                "contract_id": contract_id,
+                "multiplier_bps": multiplier_bps,
+                "daily_cap": daily_cap,
+                "allowed_methods": allowed_methods,
+            }),
+        );
+    }
+
+    /// Sets what staking an NFT of `contract_id` confers. The collection also
+    /// needs `stake_nft` in its allowed methods.
+    pub fn set_membership_nft_boost(&mut self, contract_id: AccountId, boost: NftBoost) {
+        self.assert_owner();
+
+        assert!(
+            self.membership_contracts.contains(&contract_id),
+            "Membership contract is not whitelisted"
+        );
+
+        let mut config = self
+            .membership_configs
+            .get(&contract_id)
+            .unwrap_or_default();
+        config.nft_boost = boost;
+        self.membership_configs.insert(&contract_id, &config);
+
+        ArkanaEvent::admin_action(
+            "set_membership_nft_boost",
+            serde_json::json!({ "contract_id": contract_id, "boost": boost }),
+        );
+    }
+
+    // View Functions
+    pub fn get_membership_contracts(&self) -> Vec<MembershipContractOutput> {
+        self.membership_contracts
+            .iter()
+            .map(|contract_id| self.internal_membership_contract_output(contract_id))
+            .collect()
+    }
+
+    pub fn get_membership_contract(
+        &self,
+        contract_id: AccountId,
+    ) -> Option<MembershipContractOutput> {
+        if !self.membership_contracts.contains(&contract_id) {
+            return None;
+        }
+
+        Some(self.internal_membership_contract_output(contract_id))
+    }
+}
@@ -0,0 +1,59 @@
+use near_sdk::near_bindgen;
+use serde::Serialize;
+
+use crate::*;
+
+/// Standards the contract implements, for integrators to feature-detect.
+pub const STANDARDS: &[(&str, &str)] = &[
+    ("nep145", "1.0.0"),
+    ("nep297", "1.0.0"),
+    (EVENT_STANDARD, EVENT_STANDARD_VERSION),
+];
+
+#[derive(Serialize)]
+pub struct StandardOutput {
+    standard: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct BuildInfoOutput {
+    // Set by build.sh, missing for local builds
+    commit: Option<&'static str>,
+    rustc_profile: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct ContractMetadataOutput {
+    name: &'static str,
+    community_name: Option<String>,
+    version: &'static str,
+    state_version: u16,
+    standards: Vec<StandardOutput>,
+    build: BuildInfoOutput,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+    pub fn contract_metadata(&self) -> ContractMetadataOutput {
+        ContractMetadataOutput {
+            name: env!("CARGO_PKG_NAME"),
+            community_name: self.community_name.clone(),
+            version: env!("CARGO_PKG_VERSION"),
+            state_version: CURRENT_STATE_VERSION,
+            standards: STANDARDS
+                .iter()
+                .map(|(standard, version)| StandardOutput { standard, version })
+                .collect(),
+            build: BuildInfoOutput {
+                commit: option_env!("BUILD_COMMIT"),
+                rustc_profile: if cfg!(debug_assertions) {
+                    "debug"
+                } else {
+                    "release"
+                },
+            },
+        }
+    }
+}
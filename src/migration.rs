@@ -0,0 +1,293 @@
+use std::collections::HashSet;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LazyOption, TreeMap, UnorderedMap};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Gas, Promise};
+
+use crate::*;
+
+pub const STATE_VERSION_KEY: &[u8] = b"VERSION";
+// Bump together with a frozen copy of the released layout whenever
+// `ArkanaCoreContract` changes shape after a deployment.
+pub const CURRENT_STATE_VERSION: u16 = 2;
+
+const STATE_KEY: &[u8] = b"STATE";
+
+const GAS_FOR_UPGRADE: Gas = Gas(20_000_000_000_000);
+const MAX_LEGACY_REWARD_BATCH: u64 = 10;
+
+/// Layout of the contract state as originally deployed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ArkanaCoreContractV1 {
+    pub owner: AccountId,
+    pub daily_claim_points: u64,
+    pub spin_wheel_price: u64,
+    pub users: UnorderedMap<AccountId, UserV1>,
+    pub rewards: UnorderedMap<RewardId, RewardV1>,
+    pub last_reward_id: RewardId,
+    pub membership_contracts: HashSet<AccountId>,
+    pub spinwheel_wr: u8,
+}
+
+/// Layout of a user record as originally deployed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct UserV1 {
+    pub points: u64,
+    pub last_daily_claim: Timestamp,
+    pub last_free_spinwheel: Timestamp,
+}
+
+impl UserV1 {
+    pub(crate) fn into_current(self) -> User {
+        let mut activity = UserActivity::default();
+        activity.set_last_daily_claim(self.last_daily_claim);
+        activity.set_last_free_spin(self.last_free_spinwheel);
+
+        User {
+            points: self.points.into(),
+            xp: 0,
+            season_xp: 0,
+            season: 0,
+            activity,
+            achievements: 0,
+            tickets_bought: 0,
+            odds_boost: 0,
+            milestones: 0,
+        }
+    }
+}
+
+/// Layout of a reward record as originally deployed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RewardV1 {
+    pub title: String,
+    pub price: u64,
+    pub ended_at: Timestamp,
+    pub total_tickets: u64,
+    pub winner: Option<AccountId>,
+    pub tickets: TreeMap<u64, AccountId>,
+}
+
+impl RewardV1 {
+    /// Rewrites the ticket tree keyed by first ticket as cumulative ranges.
+    pub(crate) fn into_current(mut self, reward_id: RewardId) -> (Reward, RewardTickets) {
+        let starts: Vec<(u64, AccountId)> = self.tickets.iter().collect();
+        let mut tickets = RewardTickets::new(reward_id);
+        for (index, (start, account_id)) in starts.iter().enumerate() {
+            let end = starts
+                .get(index + 1)
+                .map(|(next, _)| *next)
+                .unwrap_or(self.total_tickets);
+            tickets.add(account_id, end - start);
+        }
+        self.tickets.clear();
+
+        let reward = Reward {
+            title: LazyOption::new(StorageKey::RewardTitle { reward_id }, Some(&self.title)),
+            price: self.price.into(),
+            ended_at: self.ended_at,
+            status: if self.winner.is_some() {
+                RewardStatus::Finalized
+            } else {
+                RewardStatus::Active
+            },
+            winner: self.winner,
+            draw: None,
+        };
+        (reward, tickets)
+    }
+}
+
+/// Every layout the contract state has been stored in. `migrate` decodes the
+/// stored state into its variant and upgrades it step by step, so a new
+/// layout only needs a variant and a conversion from the previous one.
+pub enum VersionedContract {
+    V1(Box<ArkanaCoreContractV1>),
+    Current(Box<ArkanaCoreContract>),
+}
+
+impl VersionedContract {
+    /// Decodes the stored state according to the version tag written next to it.
+    /// States written before versioning was introduced carry no tag.
+    fn read() -> Self {
+        let version = env::storage_read(STATE_VERSION_KEY)
+            .map(|bytes| u16::try_from_slice(&bytes).expect("Invalid state version"))
+            .unwrap_or(1);
+        let state = env::storage_read(STATE_KEY).expect("Contract is not initialized");
+
+        match version {
+            1 => Self::V1(Box::new(
+                ArkanaCoreContractV1::try_from_slice(&state).expect("Failed to decode V1 state"),
+            )),
+            CURRENT_STATE_VERSION => Self::Current(Box::new(
+                ArkanaCoreContract::try_from_slice(&state).expect("Failed to decode state"),
+            )),
+            _ if version > CURRENT_STATE_VERSION => panic!(
+                "State version {} is newer than this code, refusing to downgrade",
+                version
+            ),
+            _ => panic!("Unknown state version {}", version),
+        }
+    }
+
+    fn into_current(self) -> ArkanaCoreContract {
+        match self {
+            Self::V1(old) => ArkanaCoreContract::from_v1(*old),
+            Self::Current(contract) => *contract,
+        }
+    }
+}
+
+pub(crate) fn write_state_version() {
+    env::storage_write(
+        STATE_VERSION_KEY,
+        &CURRENT_STATE_VERSION.try_to_vec().unwrap(),
+    );
+}
+
+impl ArkanaCoreContract {
+    fn from_v1(old: ArkanaCoreContractV1) -> Self {
+        let mut contract = Self::new(
+            old.owner,
+            U128(old.daily_claim_points.into()),
+            U128(old.spin_wheel_price.into()),
+        );
+
+        // Users keep their V1 layout until first touched, see `internal_find_user`
+        contract.legacy_users = old.users;
+        // Rewards carry whole ticket trees, so the owner converts them in
+        // batches with `migrate_legacy_rewards`
+        contract.legacy_rewards = old.rewards;
+        contract.last_reward_id = old.last_reward_id;
+        contract
+            .membership_contracts
+            .extend(old.membership_contracts);
+        contract.spinwheel_wr = old.spinwheel_wr;
+
+        contract
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let contract = VersionedContract::read().into_current();
+
+        write_state_version();
+
+        contract
+    }
+
+    /// Deploys `code` to this account and runs `migrate` in the same batch, so
+    /// a failing migration reverts the deployment as well. Once guardians are
+    /// set up the code must first be approved with `ApproveUpgrade`.
+    #[payable]
+    pub fn upgrade(&mut self, #[serializer(borsh)] code: Vec<u8>) -> Promise {
+        assert_one_yocto();
+
+        self.internal_upgrade(code, false)
+    }
+
+    /// Same as `upgrade` but takes the wasm as the raw call input, which is
+    /// how Sputnik DAO `UpgradeRemote` proposals pass code. Those proposals
+    /// attach no deposit, so unlike `upgrade` this does not require one
+    /// yoctoNEAR. In exchange the code hash must always be approved first,
+    /// through `approve_upgrade` or the guardians' `ApproveUpgrade`.
+    pub fn upgrade_raw(&mut self) -> Promise {
+        self.internal_upgrade(env::input().expect("Code is required"), true)
+    }
+
+    /// Approves the code hash for the next `upgrade_raw` while no guardians
+    /// are set up. Afterwards approvals go through `ApproveUpgrade`.
+    #[payable]
+    pub fn approve_upgrade(&mut self, code_hash: String) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(self.guardians.is_empty(), "Requires guardian approval");
+
+        ArkanaEvent::admin_action(
+            "approve_upgrade",
+            serde_json::json!({ "code_hash": &code_hash }),
+        );
+
+        self.approved_upgrade = Some(code_hash);
+    }
+
+    /// Converts up to `limit` rewards left in the V1 layout, ticket trees
+    /// included. Returns how many are still waiting.
+    #[payable]
+    pub fn migrate_legacy_rewards(&mut self, limit: Option<U64>) -> U64 {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        let limit = limit.map(|v| v.0).unwrap_or(MAX_LEGACY_REWARD_BATCH);
+        assert!(
+            limit <= MAX_LEGACY_REWARD_BATCH,
+            "Limit cannot exceed {}",
+            MAX_LEGACY_REWARD_BATCH
+        );
+
+        for _ in 0..limit {
+            let reward_id = match self.legacy_rewards.keys().next() {
+                Some(reward_id) => reward_id,
+                None => break,
+            };
+            let (reward, tickets) = self
+                .legacy_rewards
+                .remove(&reward_id)
+                .unwrap()
+                .into_current(reward_id);
+            self.rewards.insert(&reward_id, &reward);
+            self.reward_tickets.insert(&reward_id, &tickets);
+        }
+
+        U64(self.legacy_rewards.len())
+    }
+
+    // View Functions
+    pub fn get_state_version(&self) -> u16 {
+        CURRENT_STATE_VERSION
+    }
+}
+
+impl ArkanaCoreContract {
+    fn internal_upgrade(&mut self, code: Vec<u8>, requires_approval: bool) -> Promise {
+        self.assert_owner();
+
+        assert!(!code.is_empty(), "Code is required");
+
+        let code_hash = near_sdk::bs58::encode(env::sha256_array(&code)).into_string();
+        let approved = self.approved_upgrade.take().as_ref() == Some(&code_hash);
+        if requires_approval {
+            assert!(
+                approved,
+                "Code hash is not approved, approve it first or use `upgrade`"
+            );
+        } else if !self.guardians.is_empty() {
+            assert!(approved, "Requires guardian approval");
+        }
+
+        let gas_left = env::prepaid_gas() - env::used_gas();
+        assert!(
+            gas_left > GAS_FOR_UPGRADE,
+            "Not enough gas attached for the upgrade"
+        );
+        let gas_for_migrate = gas_left - GAS_FOR_UPGRADE;
+
+        ArkanaEvent::ContractUpgraded(vec![ContractUpgradedData {
+            code_hash,
+            account_id: env::predecessor_account_id(),
+        }])
+        .emit();
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call("migrate".to_string(), vec![], 0, gas_for_migrate)
+    }
+}
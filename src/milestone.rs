@@ -0,0 +1,133 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+// Reached milestones are tracked as bits of `User::milestones`
+pub const MAX_MILESTONES: usize = 64;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MilestoneReward {
+    Points { amount: U128 },
+    Tickets { reward_id: U64, amount: U64 },
+    Item { item_id: ItemId, amount: U64 },
+}
+
+/// Bonus granted once when a user's lifetime earned points (XP) reach
+/// `threshold`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct Milestone {
+    pub threshold: U64,
+    pub reward: MilestoneReward,
+}
+
+#[derive(Serialize)]
+pub struct MilestoneOutput {
+    #[serde(flatten)]
+    milestone: Milestone,
+    reached: Option<bool>,
+}
+
+impl ArkanaCoreContract {
+    /// Grants every milestone the user's XP has reached for the first time.
+    /// Called whenever points are issued, storage is accounted by callers
+    /// that measure it.
+    pub(crate) fn internal_reach_milestones(&mut self, account_id: &AccountId, user: &mut User) {
+        for index in 0..self.milestones.len() {
+            let bit = 1 << index;
+            let milestone = &self.milestones[index];
+            if user.milestones & bit != 0 || user.xp < milestone.threshold.0 {
+                continue;
+            }
+
+            user.milestones |= bit;
+            let milestone = milestone.clone();
+
+            match &milestone.reward {
+                MilestoneReward::Points { amount } => {
+                    self.internal_issue_points(account_id, user, amount.0);
+                }
+                MilestoneReward::Tickets { reward_id, amount } => {
+                    // Skipped for rewards that have ended since
+                    if let Some(reward) = self.rewards.get(&reward_id.0) {
+                        if reward.is_open(env::block_timestamp_ms()) {
+                            self.internal_add_tickets(reward_id.0, account_id, amount.0);
+                            self.internal_record_raffle_entries(account_id, user, amount.0);
+                        }
+                    }
+                }
+                MilestoneReward::Item { item_id, amount } => {
+                    if self.items.get(item_id).is_some() {
+                        self.internal_grant_item(account_id, item_id, amount.0);
+                    }
+                }
+            }
+
+            ArkanaEvent::MilestoneReached(vec![MilestoneReachedData {
+                account_id: account_id.clone(),
+                threshold: milestone.threshold,
+                reward: milestone.reward,
+            }])
+            .emit();
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Milestones by ascending threshold. A user's reached flags follow the
+    /// position in the list, so existing milestones should only be edited
+    /// in place or appended to.
+    pub fn set_milestones(&mut self, milestones: Vec<Milestone>) {
+        self.assert_owner();
+
+        assert!(
+            milestones.len() <= MAX_MILESTONES,
+            "Cannot have more than {} milestones",
+            MAX_MILESTONES
+        );
+        assert!(
+            milestones
+                .windows(2)
+                .all(|pair| pair[0].threshold.0 < pair[1].threshold.0),
+            "Thresholds must be ascending"
+        );
+        for milestone in milestones.iter() {
+            match &milestone.reward {
+                MilestoneReward::Points { .. } => {}
+                MilestoneReward::Tickets { reward_id, .. } => {
+                    self.assert_reward_exists(reward_id.0)
+                }
+                MilestoneReward::Item { item_id, .. } => self.assert_item_exists(item_id),
+            }
+        }
+
+        self.milestones = milestones.clone();
+
+        ArkanaEvent::admin_action(
+            "set_milestones",
+            serde_json::json!({ "milestones": milestones }),
+        );
+    }
+
+    // View Functions
+
+    /// Every milestone, with whether `account_id` reached it when given.
+    pub fn get_milestones(&self, account_id: Option<AccountId>) -> Vec<MilestoneOutput> {
+        let user = account_id.and_then(|account_id| self.internal_find_user(&account_id));
+
+        self.milestones
+            .iter()
+            .enumerate()
+            .map(|(index, milestone)| MilestoneOutput {
+                milestone: milestone.clone(),
+                reached: user
+                    .as_ref()
+                    .map(|user| user.milestones & (1 << index) != 0),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,133 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::{env, near_bindgen};
+use serde::Serialize;
+
+use crate::*;
+
+pub type MultiplierEventId = u64;
+
+pub const MAX_MULTIPLIER_EVENTS: usize = 16;
+pub const MAX_EARNINGS_MULTIPLIER_BPS: u32 = 10 * MAX_BPS as u32;
+
+/// Multiplies claim, spin and quest earnings between `starts_at` and
+/// `ends_at`, e.g. 20_000 bps for a "2x weekend".
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone)]
+pub struct MultiplierEvent {
+    event_id: U64,
+    title: String,
+    starts_at: U64,
+    ends_at: U64,
+    multiplier_bps: u32,
+}
+
+impl MultiplierEvent {
+    fn is_active(&self, timestamp: Timestamp) -> bool {
+        self.starts_at.0 <= timestamp && timestamp < self.ends_at.0
+    }
+}
+
+impl ArkanaCoreContract {
+    /// Highest multiplier of the running events, overlapping events do not
+    /// stack.
+    fn internal_active_multiplier_event(&self) -> Option<&MultiplierEvent> {
+        let current_timestamp = env::block_timestamp_ms();
+
+        self.multiplier_events
+            .iter()
+            .filter(|event| event.is_active(current_timestamp))
+            .max_by_key(|event| event.multiplier_bps)
+    }
+
+    pub(crate) fn internal_apply_earnings_multiplier(&self, points: Points) -> Points {
+        match self.internal_active_multiplier_event() {
+            Some(event) => apply_bps(points, event.multiplier_bps),
+            None => points,
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Schedules a multiplier event, dropping events that have ended.
+    pub fn schedule_multiplier_event(
+        &mut self,
+        title: String,
+        starts_at: U64,
+        ends_at: U64,
+        multiplier_bps: u32,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            (MAX_BPS as u32..=MAX_EARNINGS_MULTIPLIER_BPS).contains(&multiplier_bps),
+            "Multiplier must be between {} and {} bps",
+            MAX_BPS,
+            MAX_EARNINGS_MULTIPLIER_BPS
+        );
+        let current_timestamp = env::block_timestamp_ms();
+        assert!(
+            starts_at.0 < ends_at.0 && ends_at.0 > current_timestamp,
+            "Event must end in the future"
+        );
+
+        self.multiplier_events
+            .retain(|event| event.ends_at.0 > current_timestamp);
+        assert!(
+            self.multiplier_events.len() < MAX_MULTIPLIER_EVENTS,
+            "Cannot schedule more than {} events",
+            MAX_MULTIPLIER_EVENTS
+        );
+
+        let event_id = self.last_multiplier_event_id + 1;
+        self.last_multiplier_event_id = event_id;
+
+        let event = MultiplierEvent {
+            event_id: U64(event_id),
+            title,
+            starts_at,
+            ends_at,
+            multiplier_bps,
+        };
+        self.multiplier_events.push(event.clone());
+
+        ArkanaEvent::admin_action(
+            "schedule_multiplier_event",
+            serde_json::json!({ "event": event }),
+        );
+
+        U64(event_id)
+    }
+
+    pub fn cancel_multiplier_event(&mut self, event_id: U64) {
+        self.assert_owner();
+
+        let index = self
+            .multiplier_events
+            .iter()
+            .position(|event| event.event_id == event_id)
+            .expect("Multiplier event does not exist");
+        self.multiplier_events.remove(index);
+
+        ArkanaEvent::admin_action(
+            "cancel_multiplier_event",
+            serde_json::json!({ "event_id": event_id }),
+        );
+    }
+
+    // View Functions
+    pub fn get_multiplier_events(&self) -> Vec<MultiplierEvent> {
+        let current_timestamp = env::block_timestamp_ms();
+
+        self.multiplier_events
+            .iter()
+            .filter(|event| event.ends_at.0 > current_timestamp)
+            .cloned()
+            .collect()
+    }
+
+    /// The event multiplying earnings right now, for banners.
+    pub fn get_active_multiplier_event(&self) -> Option<MultiplierEvent> {
+        self.internal_active_multiplier_event().cloned()
+    }
+}
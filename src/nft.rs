@@ -0,0 +1,204 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, near_bindgen, AccountId, Gas, Promise, PromiseOrValue,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type TokenId = String;
+
+pub const MAX_STAKED_NFTS: u64 = 10;
+
+const GAS_FOR_NFT_TRANSFER: Gas = Gas(15_000_000_000_000);
+
+#[allow(dead_code)]
+#[ext_contract(ext_nft)]
+trait NonFungibleToken {
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128;
+}
+
+/// Benefits of staking an NFT of a membership collection, or of a verified
+/// holding (see `verify_membership`). A user gets the best boost among their
+/// collections, boosts do not stack.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct NftBoost {
+    pub claim_bonus_bps: u32,
+    pub extra_free_spins: u32,
+}
+
+#[derive(Serialize)]
+pub struct StakedNftOutput {
+    contract_id: AccountId,
+    token_id: TokenId,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_nft_boost(&self, account_id: &AccountId) -> NftBoost {
+        let staked_collections = self
+            .staked_nfts
+            .get(account_id)
+            .map(|staked_nfts| {
+                staked_nfts
+                    .iter()
+                    .map(|(contract_id, _)| contract_id)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        staked_collections
+            .into_iter()
+            .chain(self.internal_verified_collections(account_id))
+            .filter(|contract_id| self.membership_contracts.contains(contract_id))
+            .map(|contract_id| self.internal_membership_nft_boost(&contract_id))
+            .fold(NftBoost::default(), |best, boost| NftBoost {
+                claim_bonus_bps: best.claim_bonus_bps.max(boost.claim_bonus_bps),
+                extra_free_spins: best.extra_free_spins.max(boost.extra_free_spins),
+            })
+    }
+
+    pub(crate) fn internal_has_staked_nfts(&self, account_id: &AccountId) -> bool {
+        self.staked_nfts.get(account_id).is_some()
+    }
+
+    pub(crate) fn internal_add_staked_nft(
+        &mut self,
+        account_id: &AccountId,
+        contract_id: AccountId,
+        token_id: TokenId,
+    ) {
+        let mut staked_nfts = self.staked_nfts.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::UserStakedNfts {
+                account_id_hash: env::sha256_array(account_id.as_bytes()),
+            })
+        });
+
+        staked_nfts.insert(&(contract_id, token_id));
+        self.staked_nfts.insert(account_id, &staked_nfts);
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// NEP-171 receiver. Stakes the NFT for `previous_owner_id` when the
+    /// sending collection is allowed to `stake_nft`. Panicking returns it.
+    pub fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        // The NFT is always staked for its previous owner, whoever sent it
+        let _ = (sender_id, msg);
+
+        self.assert_not_paused(PauseFeature::Staking);
+        self.assert_not_blacklisted(&previous_owner_id);
+
+        let contract_id = env::predecessor_account_id();
+        assert!(
+            self.membership_contracts.contains(&contract_id),
+            "Unauthorized"
+        );
+        self.internal_assert_membership_method(&contract_id, MembershipMethod::StakeNft);
+
+        // Only registered users can stake
+        self.internal_get_user(&previous_owner_id);
+        let staked_count = self
+            .staked_nfts
+            .get(&previous_owner_id)
+            .map(|staked_nfts| staked_nfts.len())
+            .unwrap_or(0);
+        assert!(
+            staked_count < MAX_STAKED_NFTS,
+            "Cannot stake more than {} NFTs",
+            MAX_STAKED_NFTS
+        );
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_add_staked_nft(&previous_owner_id, contract_id.clone(), token_id.clone());
+        self.internal_update_storage_usage(&previous_owner_id, initial_storage_usage);
+
+        ArkanaEvent::NftStaked(vec![NftStakeData {
+            account_id: previous_owner_id,
+            contract_id,
+            token_id,
+        }])
+        .emit();
+
+        PromiseOrValue::Value(false)
+    }
+
+    /// Sends a staked NFT back to its owner. The stake is restored if the
+    /// transfer fails.
+    #[payable]
+    pub fn unstake_nft(&mut self, contract_id: AccountId, token_id: TokenId) -> Promise {
+        assert_one_yocto();
+
+        let account_id = env::predecessor_account_id();
+        let mut staked_nfts = self
+            .staked_nfts
+            .get(&account_id)
+            .expect("NFT is not staked");
+
+        let initial_storage_usage = env::storage_usage();
+
+        assert!(
+            staked_nfts.remove(&(contract_id.clone(), token_id.clone())),
+            "NFT is not staked"
+        );
+        if staked_nfts.is_empty() {
+            self.staked_nfts.remove(&account_id);
+        } else {
+            self.staked_nfts.insert(&account_id, &staked_nfts);
+        }
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::NftUnstaked(vec![NftStakeData {
+            account_id: account_id.clone(),
+            contract_id: contract_id.clone(),
+            token_id: token_id.clone(),
+        }])
+        .emit();
+
+        self.internal_track_operation(
+            ext_nft::ext(contract_id.clone())
+                .with_attached_deposit(1)
+                .with_static_gas(GAS_FOR_NFT_TRANSFER)
+                .nft_transfer(account_id.clone(), token_id.clone(), None, None),
+            PendingOperation::NftWithdrawal {
+                account_id,
+                contract_id,
+                token_id,
+            },
+        )
+    }
+
+    // View Functions
+    pub fn get_staked_nfts(&self, account_id: AccountId) -> Vec<StakedNftOutput> {
+        match self.staked_nfts.get(&account_id) {
+            Some(staked_nfts) => staked_nfts
+                .iter()
+                .map(|(contract_id, token_id)| StakedNftOutput {
+                    contract_id,
+                    token_id,
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn get_nft_boost(&self, account_id: AccountId) -> NftBoost {
+        self.internal_nft_boost(&account_id)
+    }
+}
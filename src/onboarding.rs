@@ -0,0 +1,134 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+/// A linkdrop/Keypom contract allowed to register accounts on their behalf
+/// and seed them with `welcome_points`, at most `daily_cap` points a day.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Onboarder {
+    welcome_points: Points,
+    daily_cap: Points,
+    usage: DailyUsage,
+}
+
+#[derive(Serialize)]
+pub struct OnboarderOutput {
+    account_id: AccountId,
+    welcome_points: U128,
+    daily_cap: U128,
+    issued_today: U128,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[payable]
+    pub fn set_onboarder(&mut self, account_id: AccountId, welcome_points: U128, daily_cap: U128) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        let usage = self
+            .onboarders
+            .get(&account_id)
+            .map(|onboarder| onboarder.usage)
+            .unwrap_or_default();
+
+        self.onboarders.insert(
+            &account_id,
+            &Onboarder {
+                welcome_points: welcome_points.0,
+                daily_cap: daily_cap.0,
+                usage,
+            },
+        );
+
+        ArkanaEvent::admin_action(
+            "set_onboarder",
+            serde_json::json!({
+                "account_id": account_id,
+                "welcome_points": welcome_points,
+                "daily_cap": daily_cap,
+            }),
+        );
+    }
+
+    #[payable]
+    pub fn remove_onboarder(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.onboarders
+            .remove(&account_id)
+            .expect("Onboarder does not exist");
+
+        ArkanaEvent::admin_action(
+            "remove_onboarder",
+            serde_json::json!({ "account_id": account_id }),
+        );
+    }
+
+    /// Registers `account_id` paid by the attached deposit, like
+    /// `storage_deposit` with `registration_only`, and credits the welcome
+    /// points. Already registered accounts get nothing and the deposit back,
+    /// so a claim never fails because of a prior registration. Accounts that
+    /// unregistered before are registered again without welcome points.
+    #[payable]
+    pub fn onboard_account(&mut self, account_id: AccountId) -> StorageBalance {
+        self.assert_not_paused(PauseFeature::All);
+
+        let onboarder_id = env::predecessor_account_id();
+        let mut onboarder = self.onboarders.get(&onboarder_id).expect("Unauthorized");
+
+        self.assert_not_blacklisted(&account_id);
+
+        if self.internal_find_user(&account_id).is_some() {
+            return self.storage_deposit(Some(account_id), Some(true));
+        }
+
+        let returning = self.former_users.contains(&account_id);
+        self.storage_deposit(Some(account_id.clone()), Some(true));
+
+        let welcome_points = if returning {
+            0
+        } else {
+            onboarder.welcome_points
+        };
+        if welcome_points > 0 {
+            onboarder.usage.consume(welcome_points, onboarder.daily_cap);
+            self.onboarders.insert(&onboarder_id, &onboarder);
+
+            let mut user = self.internal_get_user(&account_id);
+            self.internal_issue_points(&account_id, &mut user, welcome_points);
+            self.internal_save_user(&account_id, &user);
+        }
+
+        ArkanaEvent::AccountOnboarded(vec![AccountOnboardedData {
+            account_id: account_id.clone(),
+            onboarder_id,
+            welcome_points: U128(welcome_points),
+        }])
+        .emit();
+
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    // View Functions
+    pub fn get_onboarders(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<OnboarderOutput> {
+        paginate(self.onboarders.iter(), from_index, limit).map(|(account_id, onboarder)| {
+            OnboarderOutput {
+                account_id,
+                welcome_points: U128(onboarder.welcome_points),
+                daily_cap: U128(onboarder.daily_cap),
+                issued_today: U128(onboarder.usage.used_today()),
+            }
+        })
+    }
+}
@@ -0,0 +1,187 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, ext_contract, near_bindgen, AccountId, Balance, Gas, Promise, PromiseResult};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+const GAS_FOR_GET_PRICE_DATA: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_ON_PRICE_DATA: Gas = Gas(10_000_000_000_000);
+
+// Interface of the NEAR price oracle (priceoracle.near)
+#[derive(Deserialize)]
+pub struct OraclePrice {
+    multiplier: U128,
+    decimals: u8,
+}
+
+#[derive(Deserialize)]
+pub struct AssetOptionalPrice {
+    asset_id: String,
+    price: Option<OraclePrice>,
+}
+
+#[derive(Deserialize)]
+pub struct PriceData {
+    // Nanoseconds
+    timestamp: U64,
+    prices: Vec<AssetOptionalPrice>,
+}
+
+#[allow(dead_code)]
+#[ext_contract(ext_oracle)]
+trait PriceOracle {
+    fn get_price_data(&self, asset_ids: Option<Vec<String>>) -> PriceData;
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct PriceOracleConfig {
+    pub oracle_id: AccountId,
+    // Asset id of wrapped NEAR on the oracle, e.g. "wrap.near"
+    pub asset_id: String,
+    pub max_price_age_ms: U64,
+}
+
+/// USD value of one yoctoNEAR is `multiplier / 10^decimals`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct NearUsdPrice {
+    multiplier: Balance,
+    decimals: u8,
+    updated_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct NearUsdPriceOutput {
+    multiplier: U128,
+    decimals: u8,
+    updated_at: U64,
+    is_stale: bool,
+}
+
+impl ArkanaCoreContract {
+    fn internal_is_price_stale(&self, price: &NearUsdPrice) -> bool {
+        let max_price_age_ms = match &self.price_oracle {
+            Some(oracle) => oracle.max_price_age_ms.0,
+            None => return true,
+        };
+
+        env::block_timestamp_ms().saturating_sub(price.updated_at) > max_price_age_ms
+    }
+
+    /// Converts a USD amount in cents to yoctoNEAR at the cached oracle price.
+    pub(crate) fn internal_usd_cents_to_near(&self, cents: Balance) -> Balance {
+        let price = self
+            .near_usd_price
+            .as_ref()
+            .expect("NEAR price is not available, call refresh_near_usd_price");
+        assert!(
+            !self.internal_is_price_stale(price),
+            "NEAR price is stale, call refresh_near_usd_price"
+        );
+
+        // yocto = cents / 100 / (multiplier / 10^decimals), rounded up
+        let numerator = cents.safe_mul(10u128.pow(price.decimals as u32));
+        let denominator = price.multiplier.safe_mul(100);
+        numerator.div_ceil(denominator)
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_price_oracle(&mut self, config: Option<PriceOracleConfig>) {
+        self.assert_owner();
+
+        self.price_oracle = config.clone();
+        self.near_usd_price = None;
+
+        ArkanaEvent::admin_action("set_price_oracle", serde_json::json!({ "config": config }));
+    }
+
+    /// USD prices take precedence over fixed NEAR prices. `None` removes it.
+    pub fn set_reward_usd_price(&mut self, reward_id: U64, price_cents: Option<U128>) {
+        self.assert_owner();
+
+        self.assert_reward_exists(reward_id.0);
+
+        match price_cents {
+            Some(price) if price.0 > 0 => {
+                self.reward_usd_prices.insert(&reward_id.0, &price.0);
+            }
+            _ => {
+                self.reward_usd_prices.remove(&reward_id.0);
+            }
+        }
+
+        ArkanaEvent::admin_action(
+            "set_reward_usd_price",
+            serde_json::json!({ "reward_id": reward_id, "price_cents": price_cents }),
+        );
+    }
+
+    /// Fetches the current NEAR/USD price. Callable by anyone, clients batch
+    /// it before a purchase when `get_near_usd_price` reports a stale price.
+    pub fn refresh_near_usd_price(&mut self) -> Promise {
+        let oracle = self.price_oracle.clone().expect("Price oracle is not set");
+
+        ext_oracle::ext(oracle.oracle_id)
+            .with_static_gas(GAS_FOR_GET_PRICE_DATA)
+            .get_price_data(Some(vec![oracle.asset_id]))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_PRICE_DATA)
+                    .on_near_usd_price(),
+            )
+    }
+
+    #[private]
+    pub fn on_near_usd_price(&mut self) -> U128 {
+        let data: PriceData = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                serde_json::from_slice(&value).expect("Invalid oracle response")
+            }
+            _ => panic!("Oracle call failed"),
+        };
+
+        let oracle = self.price_oracle.as_ref().expect("Price oracle is not set");
+
+        let price = data
+            .prices
+            .into_iter()
+            .find(|asset| asset.asset_id == oracle.asset_id)
+            .and_then(|asset| asset.price)
+            .expect("Oracle has no price for the asset");
+        assert!(price.multiplier.0 > 0, "Invalid oracle price");
+
+        let updated_at = data.timestamp.0 / 1_000_000;
+        if let Some(cached) = &self.near_usd_price {
+            // Results can arrive out of order, never go back to an older price
+            if cached.updated_at >= updated_at {
+                return U128(cached.multiplier);
+            }
+        }
+
+        self.near_usd_price = Some(NearUsdPrice {
+            multiplier: price.multiplier.0,
+            decimals: price.decimals,
+            updated_at,
+        });
+
+        price.multiplier
+    }
+
+    // View Functions
+    pub fn get_price_oracle(&self) -> Option<PriceOracleConfig> {
+        self.price_oracle.clone()
+    }
+
+    pub fn get_near_usd_price(&self) -> Option<NearUsdPriceOutput> {
+        self.near_usd_price
+            .as_ref()
+            .map(|price| NearUsdPriceOutput {
+                multiplier: U128(price.multiplier),
+                decimals: price.decimals,
+                updated_at: U64(price.updated_at),
+                is_stale: self.internal_is_price_stale(price),
+            })
+    }
+}
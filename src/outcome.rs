@@ -0,0 +1,68 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+/// Result of a `try_*` call. Failed checks come back as `Err` instead of
+/// failing the receipt, so calling contracts can handle them in their
+/// callback.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ActionOutcome<T> {
+    Ok { value: T },
+    Err { error: String },
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_try_player_id(&self) -> Result<AccountId, String> {
+        let predecessor_id = env::predecessor_account_id();
+
+        if self.require_direct_signer && env::signer_account_id() != predecessor_id {
+            return Err("Relayed calls are disabled".to_string());
+        }
+
+        Ok(self.internal_resolve_wallet(predecessor_id))
+    }
+
+    /// Runs `call` once the dry run passes. Only failures the dry run
+    /// covers are returned, anything else still panics.
+    fn internal_try<T>(
+        &mut self,
+        estimate: impl FnOnce(&Self, AccountId) -> EstimateOutput,
+        call: impl FnOnce(&mut Self) -> T,
+    ) -> ActionOutcome<T> {
+        let checked = self
+            .internal_try_player_id()
+            .and_then(|account_id| estimate(self, account_id).into_result());
+
+        match checked {
+            Ok(()) => ActionOutcome::Ok { value: call(self) },
+            Err(error) => ActionOutcome::Err { error },
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn try_buy_ticket(&mut self, reward_id: U64, amount: U64) -> ActionOutcome<(U64, U64)> {
+        self.internal_try(
+            |contract, account_id| contract.estimate_buy_ticket(account_id, reward_id, amount),
+            |contract| contract.buy_ticket(reward_id, amount),
+        )
+    }
+
+    pub fn try_play_spin_wheel(&mut self, is_free: bool) -> ActionOutcome<U128> {
+        self.internal_try(
+            |contract, account_id| contract.estimate_spin_wheel(account_id, is_free),
+            |contract| contract.play_spin_wheel(is_free),
+        )
+    }
+
+    pub fn try_daily_claim_point(&mut self) -> ActionOutcome<U128> {
+        self.internal_try(
+            |contract, account_id| contract.estimate_daily_claim(account_id),
+            |contract| contract.daily_claim_point(),
+        )
+    }
+}
@@ -0,0 +1,44 @@
+use near_sdk::json_types::U64;
+use serde::Serialize;
+
+pub const DEFAULT_PAGE_LIMIT: u64 = 50;
+pub const MAX_PAGE_LIMIT: u64 = 100;
+
+/// One page of a paginated view.
+#[derive(Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    // Pass as `from_index` to fetch the next page, None on the last page
+    pub next_index: Option<U64>,
+}
+
+impl<T> Page<T> {
+    pub(crate) fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_index: self.next_index,
+        }
+    }
+}
+
+/// Takes the page starting at `from_index`. Larger limits are capped at
+/// `MAX_PAGE_LIMIT` so a view stays within RPC gas however large the
+/// collection gets.
+pub(crate) fn paginate<I: ExactSizeIterator>(
+    items: I,
+    from_index: Option<U64>,
+    limit: Option<u64>,
+) -> Page<I::Item> {
+    let len = items.len() as u64;
+    let start = from_index.map(|v| v.0).unwrap_or(0).min(len);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+    let end = start.saturating_add(limit).min(len);
+
+    Page {
+        items: items
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect(),
+        next_index: (end < len).then_some(U64(end)),
+    }
+}
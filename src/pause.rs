@@ -0,0 +1,92 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseFeature {
+    All,
+    // Wheel kill switch, covers free, paid and NEAR spins only
+    Spins,
+    Tickets,
+    Claims,
+    Staking,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Default, Clone)]
+pub struct PauseState {
+    all: bool,
+    spins: bool,
+    tickets: bool,
+    claims: bool,
+    staking: bool,
+}
+
+impl PauseState {
+    fn flag_mut(&mut self, feature: PauseFeature) -> &mut bool {
+        match feature {
+            PauseFeature::All => &mut self.all,
+            PauseFeature::Spins => &mut self.spins,
+            PauseFeature::Tickets => &mut self.tickets,
+            PauseFeature::Claims => &mut self.claims,
+            PauseFeature::Staking => &mut self.staking,
+        }
+    }
+
+    pub fn is_paused(&self, feature: PauseFeature) -> bool {
+        self.all
+            || match feature {
+                PauseFeature::All => false,
+                PauseFeature::Spins => self.spins,
+                PauseFeature::Tickets => self.tickets,
+                PauseFeature::Claims => self.claims,
+                PauseFeature::Staking => self.staking,
+            }
+    }
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn assert_not_paused(&self, feature: PauseFeature) {
+        assert!(!self.pause_state.is_paused(feature), "Paused");
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Halts a feature, or every user-facing mutating method with `all`.
+    /// Operators can pause to react quickly, only the owner can unpause.
+    pub fn pause(&mut self, feature: PauseFeature) {
+        self.assert_owner_or_operator();
+
+        let flag = self.pause_state.flag_mut(feature);
+        if !*flag {
+            *flag = true;
+            ArkanaEvent::Paused(vec![PauseData {
+                feature,
+                account_id: env::predecessor_account_id(),
+            }])
+            .emit();
+        }
+    }
+
+    pub fn unpause(&mut self, feature: PauseFeature) {
+        self.assert_owner();
+
+        let flag = self.pause_state.flag_mut(feature);
+        if *flag {
+            *flag = false;
+            ArkanaEvent::Unpaused(vec![PauseData {
+                feature,
+                account_id: env::predecessor_account_id(),
+            }])
+            .emit();
+        }
+    }
+
+    // View Functions
+    pub fn get_pause_state(&self) -> PauseState {
+        self.pause_state.clone()
+    }
+}
@@ -0,0 +1,152 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, is_promise_success, near_bindgen, AccountId, Gas, Promise};
+use serde::Serialize;
+
+use crate::*;
+
+pub type OperationId = u64;
+
+const GAS_FOR_ON_OPERATION_RESOLVED: Gas = Gas(10_000_000_000_000);
+
+/// An outgoing promise whose state effects were applied up front and have
+/// to be rolled back if the promise fails.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PendingOperation {
+    TreasuryWithdrawal {
+        receiver_id: AccountId,
+        amount: U128,
+    },
+    StorageWithdrawal {
+        account_id: AccountId,
+        amount: U128,
+    },
+    NftWithdrawal {
+        account_id: AccountId,
+        contract_id: AccountId,
+        token_id: TokenId,
+    },
+    TokenWithdrawal {
+        token_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    },
+    ProtocolFeeWithdrawal {
+        receiver_id: AccountId,
+        amount: U128,
+    },
+}
+
+#[derive(Serialize)]
+pub struct PendingOperationOutput {
+    operation_id: U64,
+    operation: PendingOperation,
+}
+
+impl ArkanaCoreContract {
+    /// Records `operation` and chains the callback that settles it once
+    /// `promise` resolves. Callers must have applied the operation's effects.
+    pub(crate) fn internal_track_operation(
+        &mut self,
+        promise: Promise,
+        operation: PendingOperation,
+    ) -> Promise {
+        let operation_id = self.last_operation_id + 1;
+        self.last_operation_id = operation_id;
+        self.pending_operations.insert(&operation_id, &operation);
+
+        promise.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_OPERATION_RESOLVED)
+                .on_operation_resolved(U64(operation_id)),
+        )
+    }
+
+    fn internal_commit_operation(&mut self, operation: PendingOperation) {
+        match operation {
+            PendingOperation::TreasuryWithdrawal {
+                receiver_id,
+                amount,
+            } => {
+                ArkanaEvent::TreasuryWithdrawal(vec![TreasuryWithdrawalData {
+                    receiver_id,
+                    amount,
+                }])
+                .emit();
+            }
+            PendingOperation::StorageWithdrawal { .. }
+            | PendingOperation::NftWithdrawal { .. }
+            | PendingOperation::TokenWithdrawal { .. }
+            | PendingOperation::ProtocolFeeWithdrawal { .. } => {}
+        }
+    }
+
+    fn internal_rollback_operation(&mut self, operation: PendingOperation) {
+        match operation {
+            PendingOperation::TreasuryWithdrawal { amount, .. } => {
+                self.treasury_balance += amount.0;
+            }
+            PendingOperation::StorageWithdrawal { account_id, amount } => {
+                match self.storage_accounts.get(&account_id) {
+                    Some(mut storage_account) => {
+                        storage_account.deposit += amount.0;
+                        self.storage_accounts.insert(&account_id, &storage_account);
+                    }
+                    // The account was unregistered meanwhile, keep the funds
+                    // recoverable instead of stranding them
+                    None => self.treasury_balance += amount.0,
+                }
+            }
+            PendingOperation::NftWithdrawal {
+                account_id,
+                contract_id,
+                token_id,
+            } => {
+                self.internal_add_staked_nft(&account_id, contract_id, token_id);
+            }
+            PendingOperation::TokenWithdrawal {
+                token_id, amount, ..
+            } => {
+                self.internal_restore_token_balance(&token_id, amount.0);
+            }
+            PendingOperation::ProtocolFeeWithdrawal { amount, .. } => {
+                self.protocol_fee_balance += amount.0;
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[private]
+    pub fn on_operation_resolved(&mut self, operation_id: U64) -> bool {
+        let operation = self
+            .pending_operations
+            .remove(&operation_id.0)
+            .expect("Operation does not exist");
+
+        let is_success = is_promise_success();
+        if is_success {
+            self.internal_commit_operation(operation);
+        } else {
+            self.internal_rollback_operation(operation);
+        }
+
+        is_success
+    }
+
+    // View Functions
+    pub fn get_pending_operations(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<PendingOperationOutput> {
+        paginate(self.pending_operations.iter(), from_index, limit).map(
+            |(operation_id, operation)| PendingOperationOutput {
+                operation_id: U64(operation_id),
+                operation,
+            },
+        )
+    }
+}
@@ -0,0 +1,153 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::{env, ext_contract, near_bindgen, AccountId, Gas, Promise, PromiseResult};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const PERSONHOOD_CACHE_TTL: u64 = 7 * ONE_DAY;
+
+const GAS_FOR_IS_HUMAN: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_ON_PERSONHOOD_VERIFIED: Gas = Gas(10_000_000_000_000);
+
+// Interface of an SBT registry such as i-am-human (registry.i-am-human.near),
+// listing the issuers and tokens proving personhood, empty if none
+#[allow(dead_code)]
+#[ext_contract(ext_personhood_registry)]
+trait PersonhoodRegistry {
+    fn is_human(&self, account: AccountId) -> Vec<(AccountId, Vec<u64>)>;
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct PersonhoodConfig {
+    pub registry_id: AccountId,
+    pub required_for_claims: bool,
+    pub required_for_free_spins: bool,
+}
+
+#[derive(Serialize)]
+pub struct PersonhoodOutput {
+    is_human: bool,
+    verified_at: U64,
+    expires_at: U64,
+}
+
+impl ArkanaCoreContract {
+    fn internal_is_verified_human(&self, account_id: &AccountId) -> bool {
+        self.personhood_verifications
+            .get(account_id)
+            .is_some_and(|verified_at| {
+                env::block_timestamp_ms() < verified_at + PERSONHOOD_CACHE_TTL
+            })
+    }
+
+    /// Panics unless the account has a fresh personhood verification, when
+    /// the configured gate applies.
+    /// Whether a claim (or a free spin) by `account_id` is blocked on proof
+    /// of personhood.
+    pub(crate) fn internal_needs_personhood(&self, account_id: &AccountId, is_claim: bool) -> bool {
+        let required = match &self.personhood_config {
+            Some(config) if is_claim => config.required_for_claims,
+            Some(config) => config.required_for_free_spins,
+            None => false,
+        };
+
+        required && !self.internal_is_verified_human(account_id)
+    }
+
+    pub(crate) fn assert_human_if_required(&self, account_id: &AccountId, is_claim: bool) {
+        assert!(
+            !self.internal_needs_personhood(account_id, is_claim),
+            "Proof of personhood required, call verify_personhood first"
+        );
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Sets the registry and which free rewards need a verified human, None
+    /// turns the gate off.
+    pub fn set_personhood_config(&mut self, config: Option<PersonhoodConfig>) {
+        self.assert_owner();
+
+        self.personhood_config = config.clone();
+
+        ArkanaEvent::admin_action(
+            "set_personhood_config",
+            serde_json::json!({ "config": config }),
+        );
+    }
+
+    /// Asks the registry whether `account_id` is a verified human and caches
+    /// a positive answer for `PERSONHOOD_CACHE_TTL`. Callable by anyone.
+    pub fn verify_personhood(&mut self, account_id: AccountId) -> Promise {
+        assert!(
+            self.internal_find_user(&account_id).is_some(),
+            "User does not exist"
+        );
+
+        let config = self
+            .personhood_config
+            .as_ref()
+            .expect("Proof of personhood is not configured");
+
+        ext_personhood_registry::ext(config.registry_id.clone())
+            .with_static_gas(GAS_FOR_IS_HUMAN)
+            .is_human(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_PERSONHOOD_VERIFIED)
+                    .on_personhood_verified(account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_personhood_verified(&mut self, account_id: AccountId) -> bool {
+        // Failed or malformed answers count as not verified
+        let is_human = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                serde_json::from_slice::<Vec<(AccountId, Vec<u64>)>>(&value)
+                    .map(|proofs| !proofs.is_empty())
+                    .unwrap_or(false)
+            }
+            _ => false,
+        };
+
+        // The account may have unregistered while the call was in flight
+        if self.internal_find_user(&account_id).is_none() {
+            return false;
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        if is_human {
+            self.personhood_verifications
+                .insert(&account_id, &env::block_timestamp_ms());
+        } else {
+            self.personhood_verifications.remove(&account_id);
+        }
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PersonhoodVerified(vec![PersonhoodVerifiedData {
+            account_id,
+            is_human,
+        }])
+        .emit();
+
+        is_human
+    }
+
+    // View Functions
+    pub fn get_personhood_config(&self) -> Option<PersonhoodConfig> {
+        self.personhood_config.clone()
+    }
+
+    pub fn get_personhood(&self, account_id: AccountId) -> Option<PersonhoodOutput> {
+        self.personhood_verifications
+            .get(&account_id)
+            .map(|verified_at| PersonhoodOutput {
+                is_human: self.internal_is_verified_human(&account_id),
+                verified_at: U64(verified_at),
+                expires_at: U64(verified_at + PERSONHOOD_CACHE_TTL),
+            })
+    }
+}
@@ -0,0 +1,246 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type PollId = u64;
+
+pub const MAX_POLL_CANDIDATES: usize = 8;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PollMode {
+    // Votes are burned
+    Burn,
+    // Votes are locked until the poll closes, then withdrawn
+    Lock,
+}
+
+/// A prize the next raffle could be for. The winner is created as a reward
+/// running for `duration_ms` from when the poll is finalized.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct PollCandidate {
+    pub title: String,
+    pub price: U128,
+    pub duration_ms: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Poll {
+    candidates: Vec<PollCandidate>,
+    votes: Vec<Points>,
+    mode: PollMode,
+    closes_at: Timestamp,
+    finalized: bool,
+    reward_id: Option<RewardId>,
+}
+
+#[derive(Serialize)]
+pub struct PollOutput {
+    poll_id: U64,
+    candidates: Vec<PollCandidate>,
+    votes: Vec<U128>,
+    mode: PollMode,
+    closes_at: U64,
+    finalized: bool,
+    reward_id: Option<U64>,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the poll's storage, the rest is refunded.
+    #[payable]
+    pub fn create_poll(
+        &mut self,
+        candidates: Vec<PollCandidate>,
+        mode: PollMode,
+        closes_at: U64,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            candidates.len() >= 2 && candidates.len() <= MAX_POLL_CANDIDATES,
+            "Poll must have between 2 and {} candidates",
+            MAX_POLL_CANDIDATES
+        );
+        // The winner's reward starts when the poll gets finalized
+        for candidate in candidates.iter() {
+            assert_valid_reward(
+                &candidate.title,
+                candidate.price.0,
+                closes_at.0.safe_add(candidate.duration_ms.0),
+                closes_at.0,
+            );
+        }
+        assert!(
+            closes_at.0 > env::block_timestamp_ms(),
+            "Poll must close in the future"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let poll_id = self.last_poll_id + 1;
+        self.last_poll_id = poll_id;
+
+        self.polls.insert(
+            &poll_id,
+            &Poll {
+                votes: vec![0; candidates.len()],
+                candidates: candidates.clone(),
+                mode,
+                closes_at: closes_at.0,
+                finalized: false,
+                reward_id: None,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_poll",
+            serde_json::json!({
+                "poll_id": U64(poll_id),
+                "candidates": candidates,
+                "mode": mode,
+                "closes_at": closes_at,
+            }),
+        );
+
+        U64(poll_id)
+    }
+
+    /// Puts `amount` points behind a candidate, burned or locked depending
+    /// on the poll. Votes can be split across candidates.
+    pub fn vote_poll(&mut self, poll_id: U64, candidate: u8, amount: U128) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut poll = self.polls.get(&poll_id.0).expect("Poll does not exist");
+        assert!(env::block_timestamp_ms() < poll.closes_at, "Poll is closed");
+        assert!(
+            (candidate as usize) < poll.candidates.len(),
+            "Invalid candidate"
+        );
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        match poll.mode {
+            PollMode::Burn => self.internal_burn_points(&mut user, amount.0),
+            PollMode::Lock => {
+                user.points = user
+                    .points
+                    .checked_sub(amount.0)
+                    .expect("Points insufficient");
+
+                let key = (poll_id.0, account_id.clone());
+                let locked = self.poll_locks.get(&key).unwrap_or(0);
+                if locked == 0 {
+                    self.internal_open_escrow(&account_id);
+                }
+                self.poll_locks.insert(&key, &locked.safe_add(amount.0));
+            }
+        }
+
+        poll.votes[candidate as usize] = poll.votes[candidate as usize].safe_add(amount.0);
+        self.polls.insert(&poll_id.0, &poll);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PollVoted(vec![PollVotedData {
+            poll_id,
+            account_id,
+            candidate,
+            amount,
+        }])
+        .emit();
+    }
+
+    /// Creates the reward for the candidate with the most votes, ties going
+    /// to the earlier candidate. Callable by anyone once the poll closed.
+    /// Returns the reward id, `None` if nobody voted.
+    pub fn finalize_poll(&mut self, poll_id: U64) -> Option<U64> {
+        let mut poll = self.polls.get(&poll_id.0).expect("Poll does not exist");
+        assert!(!poll.finalized, "Poll already finalized");
+
+        let current_timestamp = env::block_timestamp_ms();
+        assert!(current_timestamp >= poll.closes_at, "Poll is still open");
+
+        let winner = poll
+            .votes
+            .iter()
+            .enumerate()
+            .filter(|(_, votes)| **votes > 0)
+            .max_by(|(a_index, a), (b_index, b)| a.cmp(b).then(b_index.cmp(a_index)))
+            .map(|(index, _)| index);
+
+        poll.reward_id = winner.map(|index| {
+            let candidate = poll.candidates[index].clone();
+            self.internal_create_reward(
+                candidate.title,
+                candidate.price.0,
+                current_timestamp.safe_add(candidate.duration_ms.0),
+            )
+        });
+        poll.finalized = true;
+        self.polls.insert(&poll_id.0, &poll);
+
+        ArkanaEvent::PollFinalized(vec![PollFinalizedData {
+            poll_id,
+            candidate: winner.map(|index| index as u8),
+            reward_id: poll.reward_id.map(U64),
+        }])
+        .emit();
+
+        poll.reward_id.map(U64)
+    }
+
+    /// Returns the points the caller locked in a closed poll.
+    pub fn withdraw_poll_lock(&mut self, poll_id: U64) -> U128 {
+        let account_id = self.internal_player_id();
+
+        let poll = self.polls.get(&poll_id.0).expect("Poll does not exist");
+        assert!(
+            env::block_timestamp_ms() >= poll.closes_at,
+            "Poll is still open"
+        );
+
+        let key = (poll_id.0, account_id.clone());
+        let locked = self.poll_locks.get(&key).expect("No points locked");
+
+        let mut user = self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        self.poll_locks.remove(&key);
+        self.internal_close_escrow(&account_id);
+        user.points = user.points.safe_add(locked);
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        U128(locked)
+    }
+
+    // View Functions
+    pub fn get_polls(&self, from_index: Option<U64>, limit: Option<u64>) -> Page<PollOutput> {
+        paginate(self.polls.iter(), from_index, limit).map(|(poll_id, poll)| PollOutput {
+            poll_id: U64(poll_id),
+            candidates: poll.candidates,
+            votes: poll.votes.into_iter().map(U128).collect(),
+            mode: poll.mode,
+            closes_at: U64(poll.closes_at),
+            finalized: poll.finalized,
+            reward_id: poll.reward_id.map(U64),
+        })
+    }
+
+    pub fn get_poll_lock(&self, poll_id: U64, account_id: AccountId) -> U128 {
+        U128(self.poll_locks.get(&(poll_id.0, account_id)).unwrap_or(0))
+    }
+}
@@ -0,0 +1,277 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub const MAX_PREDICTION_BRACKETS: usize = 8;
+pub const MAX_PREDICTION_FEE_BPS: u16 = 2_000;
+
+/// Pari-mutuel pool on how many tickets a reward sells. `brackets` are
+/// ascending upper bounds, outcome `i` covers the tickets up to
+/// `brackets[i]` and the last outcome everything above.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PredictionPool {
+    brackets: Vec<u64>,
+    stakes: Vec<Points>,
+    closes_at: Timestamp,
+    // Burned from the pool on resolution
+    fee_bps: u16,
+    outcome: Option<u8>,
+}
+
+impl PredictionPool {
+    fn total_staked(&self) -> Points {
+        self.stakes.iter().sum()
+    }
+
+    fn outcome_of(&self, total_tickets: u64) -> u8 {
+        self.brackets
+            .iter()
+            .position(|upper_bound| total_tickets <= *upper_bound)
+            .unwrap_or(self.brackets.len()) as u8
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Prediction {
+    outcome: u8,
+    amount: Points,
+}
+
+#[derive(Serialize)]
+pub struct PredictionPoolOutput {
+    reward_id: U64,
+    brackets: Vec<U64>,
+    stakes: Vec<U128>,
+    closes_at: U64,
+    fee_bps: u16,
+    outcome: Option<u8>,
+}
+
+#[derive(Serialize)]
+pub struct PredictionOutput {
+    outcome: u8,
+    amount: U128,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Opens predictions on a reward's ticket sales until `closes_at`, which
+    /// has to be before the reward ends.
+    #[payable]
+    pub fn create_prediction_pool(
+        &mut self,
+        reward_id: U64,
+        brackets: Vec<U64>,
+        closes_at: U64,
+        fee_bps: u16,
+    ) {
+        self.assert_owner();
+
+        let reward = self.internal_get_reward(reward_id.0);
+        assert!(
+            self.prediction_pools.get(&reward_id.0).is_none(),
+            "Prediction pool already exists"
+        );
+        assert!(
+            env::block_timestamp_ms() < closes_at.0 && closes_at.0 <= reward.ended_at,
+            "Predictions must close before the reward ends"
+        );
+        assert!(
+            !brackets.is_empty() && brackets.len() < MAX_PREDICTION_BRACKETS,
+            "Pool must have between 1 and {} brackets",
+            MAX_PREDICTION_BRACKETS - 1
+        );
+        assert!(
+            brackets.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "Brackets must be ascending"
+        );
+        assert!(
+            fee_bps <= MAX_PREDICTION_FEE_BPS,
+            "Fee cannot exceed {} bps",
+            MAX_PREDICTION_FEE_BPS
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        self.prediction_pools.insert(
+            &reward_id.0,
+            &PredictionPool {
+                stakes: vec![0; brackets.len() + 1],
+                brackets: brackets.iter().map(|b| b.0).collect(),
+                closes_at: closes_at.0,
+                fee_bps,
+                outcome: None,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_prediction_pool",
+            serde_json::json!({
+                "reward_id": reward_id,
+                "brackets": brackets,
+                "closes_at": closes_at,
+                "fee_bps": fee_bps,
+            }),
+        );
+    }
+
+    /// Stakes points on the bracket a reward's ticket sales end up in.
+    /// Adding to an existing prediction has to keep the same outcome.
+    pub fn predict(&mut self, reward_id: U64, outcome: u8, amount: U128) {
+        self.assert_not_paused(PauseFeature::All);
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut pool = self
+            .prediction_pools
+            .get(&reward_id.0)
+            .expect("Prediction pool does not exist");
+        assert!(
+            env::block_timestamp_ms() < pool.closes_at,
+            "Predictions are closed"
+        );
+        assert!(
+            (outcome as usize) < pool.stakes.len(),
+            "Outcome does not exist"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        if user.points < amount.0 {
+            panic!("Points insufficient");
+        }
+        self.internal_spend_points(&account_id, &mut user, amount.0, SpendKind::Game);
+
+        let key = (reward_id.0, account_id.clone());
+        let mut prediction = self.predictions.get(&key).unwrap_or_else(|| {
+            self.internal_open_escrow(&account_id);
+            Prediction { outcome, amount: 0 }
+        });
+        assert_eq!(
+            prediction.outcome, outcome,
+            "Already predicted another outcome"
+        );
+        prediction.amount = prediction.amount.safe_add(amount.0);
+
+        pool.stakes[outcome as usize] = pool.stakes[outcome as usize].safe_add(amount.0);
+
+        self.predictions.insert(&key, &prediction);
+        self.prediction_pools.insert(&reward_id.0, &pool);
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PredictionPlaced(vec![PredictionPlacedData {
+            account_id,
+            reward_id,
+            outcome,
+            amount,
+        }])
+        .emit();
+    }
+
+    /// Settles the pool from the final ticket count once the reward has
+    /// ended. Callable by anyone.
+    pub fn resolve_prediction_pool(&mut self, reward_id: U64) -> u8 {
+        let mut pool = self
+            .prediction_pools
+            .get(&reward_id.0)
+            .expect("Prediction pool does not exist");
+        assert!(pool.outcome.is_none(), "Prediction pool already resolved");
+
+        let reward = self.internal_get_reward(reward_id.0);
+        assert!(
+            env::block_timestamp_ms() >= reward.ended_at,
+            "Reward has not ended"
+        );
+
+        let total_tickets = self.reward_tickets.get(&reward_id.0).unwrap().total;
+        let outcome = pool.outcome_of(total_tickets);
+        pool.outcome = Some(outcome);
+
+        self.prediction_pools.insert(&reward_id.0, &pool);
+
+        ArkanaEvent::PredictionPoolResolved(vec![PredictionPoolResolvedData {
+            reward_id,
+            outcome,
+            total_tickets: U64(total_tickets),
+        }])
+        .emit();
+
+        outcome
+    }
+
+    /// Pays out the caller's share of a resolved pool. Returns the points
+    /// received, zero for a losing prediction.
+    pub fn claim_prediction(&mut self, reward_id: U64) -> U128 {
+        let account_id = self.internal_player_id();
+
+        let pool = self
+            .prediction_pools
+            .get(&reward_id.0)
+            .expect("Prediction pool does not exist");
+        let outcome = pool.outcome.expect("Prediction pool is not resolved");
+
+        let key = (reward_id.0, account_id.clone());
+        let prediction = self.predictions.get(&key).expect("No prediction");
+
+        let mut user = self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        let winning_stakes = pool.stakes[outcome as usize];
+        let payout = if winning_stakes == 0 {
+            prediction.amount
+        } else if prediction.outcome == outcome {
+            let total_staked = pool.total_staked();
+            let prize_pool = total_staked - apply_bps(total_staked, pool.fee_bps as u32);
+            prediction.amount.safe_mul(prize_pool) / winning_stakes
+        } else {
+            0
+        };
+
+        // Stakes count as spent like wagers, so payouts are issued again and
+        // the fee is never returned. Without winners every stake is refunded.
+        self.predictions.remove(&key);
+        self.internal_close_escrow(&account_id);
+        self.internal_issue_points(&account_id, &mut user, payout);
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        U128(payout)
+    }
+
+    // View Functions
+    pub fn get_prediction_pool(&self, reward_id: U64) -> Option<PredictionPoolOutput> {
+        self.prediction_pools
+            .get(&reward_id.0)
+            .map(|pool| PredictionPoolOutput {
+                reward_id,
+                brackets: pool.brackets.into_iter().map(U64).collect(),
+                stakes: pool.stakes.into_iter().map(U128).collect(),
+                closes_at: U64(pool.closes_at),
+                fee_bps: pool.fee_bps,
+                outcome: pool.outcome,
+            })
+    }
+
+    pub fn get_prediction(
+        &self,
+        reward_id: U64,
+        account_id: AccountId,
+    ) -> Option<PredictionOutput> {
+        self.predictions
+            .get(&(reward_id.0, account_id))
+            .map(|prediction| PredictionOutput {
+                outcome: prediction.outcome,
+                amount: U128(prediction.amount),
+            })
+    }
+}
@@ -0,0 +1,313 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type QuestId = String;
+
+pub const MAX_QUEST_ID_LEN: usize = 64;
+pub const MAX_QUEST_PROOF_LEN: usize = 256;
+pub const MAX_ROTATION_POOL: usize = 32;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationPeriod {
+    Daily,
+    Weekly,
+}
+
+impl RotationPeriod {
+    fn duration_ms(self) -> u64 {
+        match self {
+            RotationPeriod::Daily => ONE_DAY,
+            RotationPeriod::Weekly => 7 * ONE_DAY,
+        }
+    }
+}
+
+/// Each period `active_count` quests of `pool` are active, picked from a
+/// hash of the period number so anyone can predict the schedule. Rotating
+/// quests can be completed again every period they are active.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct QuestRotation {
+    pub period: RotationPeriod,
+    pub active_count: u8,
+    pub pool: Vec<QuestId>,
+}
+
+impl QuestRotation {
+    fn period_index(&self, timestamp: Timestamp) -> u64 {
+        timestamp / self.period.duration_ms()
+    }
+
+    /// Partial Fisher-Yates shuffle of the pool seeded by the period.
+    fn active_quests(&self, period_index: u64) -> Vec<QuestId> {
+        let mut pool = self.pool.clone();
+
+        for index in 0..self.active_count as usize {
+            let mut input = period_index.to_le_bytes().to_vec();
+            input.push(index as u8);
+            let hash = env::sha256_array(&input);
+
+            let random = u32::from_le_bytes(hash[..4].try_into().unwrap()) as usize;
+            let pick = index + random % (pool.len() - index);
+            pool.swap(index, pick);
+        }
+
+        pool.truncate(self.active_count as usize);
+        pool
+    }
+}
+
+#[derive(Serialize)]
+pub struct ActiveQuestsOutput {
+    quests: Vec<QuestOutput>,
+    period_ends_at: U64,
+}
+
+/// A quest run by a partner contract, worth `points` once per account and
+/// completable by at most `max_completions` accounts (0 for no cap).
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Quest {
+    partner_id: AccountId,
+    points: Points,
+    max_completions: u64,
+    completions: u64,
+}
+
+#[derive(Serialize)]
+pub struct QuestOutput {
+    quest_id: QuestId,
+    partner_id: AccountId,
+    points: U128,
+    max_completions: U64,
+    completions: U64,
+}
+
+impl ArkanaCoreContract {
+    /// Rotating quests only count as completed within the current period.
+    fn internal_is_quest_completed(&self, key: &(QuestId, AccountId)) -> bool {
+        let completed_at = match self.quest_completions.get(key) {
+            Some(completed_at) => completed_at,
+            None => return false,
+        };
+
+        match &self.quest_rotation {
+            Some(rotation) if rotation.pool.contains(&key.0) => {
+                rotation.period_index(completed_at)
+                    == rotation.period_index(env::block_timestamp_ms())
+            }
+            _ => true,
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_quest(
+        &mut self,
+        quest_id: QuestId,
+        partner_id: AccountId,
+        points: U128,
+        max_completions: U64,
+    ) {
+        self.assert_owner();
+
+        assert!(
+            !quest_id.is_empty() && quest_id.len() <= MAX_QUEST_ID_LEN,
+            "Quest id must be between 1 and {} bytes",
+            MAX_QUEST_ID_LEN
+        );
+
+        let completions = self
+            .quests
+            .get(&quest_id)
+            .map(|quest| quest.completions)
+            .unwrap_or(0);
+
+        self.quests.insert(
+            &quest_id,
+            &Quest {
+                partner_id: partner_id.clone(),
+                points: points.0,
+                max_completions: max_completions.0,
+                completions,
+            },
+        );
+
+        ArkanaEvent::admin_action(
+            "set_quest",
+            serde_json::json!({
+                "quest_id": quest_id,
+                "partner_id": partner_id,
+                "points": points,
+                "max_completions": max_completions,
+            }),
+        );
+    }
+
+    pub fn remove_quest(&mut self, quest_id: QuestId) {
+        self.assert_owner();
+
+        self.quests.remove(&quest_id).expect("Quest does not exist");
+
+        if let Some(mut rotation) = self.quest_rotation.take() {
+            rotation.pool.retain(|id| id != &quest_id);
+            rotation.active_count = rotation.active_count.min(rotation.pool.len() as u8);
+            if !rotation.pool.is_empty() {
+                self.quest_rotation = Some(rotation);
+            }
+        }
+
+        ArkanaEvent::admin_action("remove_quest", serde_json::json!({ "quest_id": quest_id }));
+    }
+
+    /// Called by the quest's partner contract when `account_id` completes it.
+    /// Awards the quest points once per account, `proof` is only logged for
+    /// off-chain audits.
+    pub fn on_quest_completed(
+        &mut self,
+        account_id: AccountId,
+        quest_id: QuestId,
+        proof: String,
+    ) -> U128 {
+        self.assert_not_paused(PauseFeature::Claims);
+        self.assert_not_blacklisted(&account_id);
+
+        let mut quest = self.quests.get(&quest_id).expect("Quest does not exist");
+        assert_eq!(
+            quest.partner_id,
+            env::predecessor_account_id(),
+            "Unauthorized"
+        );
+        assert!(
+            proof.len() <= MAX_QUEST_PROOF_LEN,
+            "Proof cannot exceed {} bytes",
+            MAX_QUEST_PROOF_LEN
+        );
+        assert!(
+            quest.max_completions == 0 || quest.completions < quest.max_completions,
+            "Quest completion cap reached"
+        );
+
+        let key = (quest_id.clone(), account_id.clone());
+        assert!(
+            !self.internal_is_quest_completed(&key),
+            "Quest already completed"
+        );
+        if let Some(rotation) = &self.quest_rotation {
+            if rotation.pool.contains(&quest_id) {
+                let period_index = rotation.period_index(env::block_timestamp_ms());
+                assert!(
+                    rotation.active_quests(period_index).contains(&quest_id),
+                    "Quest is not active in this rotation"
+                );
+            }
+        }
+
+        let mut user = self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        quest.completions += 1;
+        self.quests.insert(&quest_id, &quest);
+        // Kept after unregistration so a quest cannot be farmed again
+        self.quest_completions
+            .insert(&key, &env::block_timestamp_ms());
+
+        let points = self.internal_apply_earnings_multiplier(quest.points);
+        self.internal_issue_points(&account_id, &mut user, points);
+        self.internal_save_user(&account_id, &user);
+        self.internal_record_history(
+            &account_id,
+            HistoryKind::Quest,
+            points as i128,
+            Some(quest_id.clone()),
+        );
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::QuestCompleted(vec![QuestCompletedData {
+            account_id,
+            quest_id,
+            points: U128(points),
+            proof,
+        }])
+        .emit();
+
+        U128(user.points)
+    }
+
+    /// Sets the quests that rotate automatically, `None` makes every quest
+    /// permanently active again.
+    pub fn set_quest_rotation(&mut self, rotation: Option<QuestRotation>) {
+        self.assert_owner();
+
+        if let Some(rotation) = &rotation {
+            assert!(
+                !rotation.pool.is_empty() && rotation.pool.len() <= MAX_ROTATION_POOL,
+                "Rotation pool must have between 1 and {} quests",
+                MAX_ROTATION_POOL
+            );
+            assert!(
+                rotation.active_count > 0 && rotation.active_count as usize <= rotation.pool.len(),
+                "Active count must be between 1 and the pool size"
+            );
+            for quest_id in rotation.pool.iter() {
+                assert!(self.quests.get(quest_id).is_some(), "Quest does not exist");
+            }
+        }
+
+        self.quest_rotation = rotation.clone();
+
+        ArkanaEvent::admin_action(
+            "set_quest_rotation",
+            serde_json::json!({ "rotation": rotation }),
+        );
+    }
+
+    // View Functions
+    pub fn get_quest_rotation(&self) -> Option<QuestRotation> {
+        self.quest_rotation.clone()
+    }
+
+    /// Quests of the current rotation period that still exist.
+    pub fn get_active_quests(&self) -> Option<ActiveQuestsOutput> {
+        let rotation = self.quest_rotation.as_ref()?;
+        let period_index = rotation.period_index(env::block_timestamp_ms());
+
+        Some(ActiveQuestsOutput {
+            quests: rotation
+                .active_quests(period_index)
+                .into_iter()
+                .filter_map(|quest_id| {
+                    self.quests.get(&quest_id).map(|quest| QuestOutput {
+                        quest_id,
+                        partner_id: quest.partner_id,
+                        points: U128(quest.points),
+                        max_completions: U64(quest.max_completions),
+                        completions: U64(quest.completions),
+                    })
+                })
+                .collect(),
+            period_ends_at: U64((period_index + 1) * rotation.period.duration_ms()),
+        })
+    }
+
+    pub fn get_quests(&self) -> Vec<QuestOutput> {
+        self.quests
+            .iter()
+            .map(|(quest_id, quest)| QuestOutput {
+                quest_id,
+                partner_id: quest.partner_id,
+                points: U128(quest.points),
+                max_completions: U64(quest.max_completions),
+                completions: U64(quest.completions),
+            })
+            .collect()
+    }
+
+    pub fn is_quest_completed(&self, account_id: AccountId, quest_id: QuestId) -> bool {
+        self.internal_is_quest_completed(&(quest_id, account_id))
+    }
+}
@@ -0,0 +1,134 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+/// What a random number is drawn for. It is hashed into the draw so
+/// different features never read the same bits.
+#[derive(BorshSerialize, Clone, Copy)]
+pub enum RandomPurpose {
+    RaffleWinner,
+    Spin,
+    Jackpot,
+    Lootbox,
+    LuckyDraw,
+    ScratchCard,
+    Wager,
+}
+
+/// Inputs of a raffle draw, kept so anyone can recompute it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RewardDraw {
+    pub(crate) random_seed: Vec<u8>,
+    pub(crate) caller: AccountId,
+    pub(crate) nonce: u64,
+    pub(crate) total_tickets: u64,
+    // Holders passed over from the drawn ticket on, each listed once
+    pub(crate) skipped: Vec<AccountId>,
+}
+
+#[derive(Serialize)]
+pub struct DrawReplayOutput {
+    // Base58 encoded block random seed
+    random_seed: String,
+    caller: AccountId,
+    nonce: U64,
+    total_tickets: U64,
+    ticket: U64,
+    // The winner is the first holder from here on not in `skipped`
+    ticket_holder: AccountId,
+    // Blacklisted or in the repeat-winner cooldown when drawn
+    skipped: Vec<AccountId>,
+    winner: Option<AccountId>,
+}
+
+/// `sha256(random_seed || caller || nonce || purpose)`, read as a little
+/// endian u32.
+pub(crate) fn random_number_from(
+    random_seed: &[u8],
+    caller: &AccountId,
+    nonce: u64,
+    purpose: RandomPurpose,
+) -> u32 {
+    let mut input = random_seed.to_vec();
+    input.extend_from_slice(caller.as_bytes());
+    input.extend_from_slice(&nonce.to_le_bytes());
+    input.extend(purpose.try_to_vec().unwrap());
+
+    let hash = env::sha256_array(&input);
+    u32::from_le_bytes(hash[..4].try_into().unwrap())
+}
+
+impl ArkanaCoreContract {
+    /// Draws from the block seed, the predecessor and a persisted nonce that
+    /// is bumped on every draw, so draws within one receipt are independent
+    /// of each other.
+    pub(crate) fn internal_random_number(&mut self, purpose: RandomPurpose) -> u32 {
+        self.random_nonce += 1;
+
+        random_number_from(
+            &env::random_seed(),
+            &env::predecessor_account_id(),
+            self.random_nonce,
+            purpose,
+        )
+    }
+
+    /// Draws a ticket out of `total_tickets` and returns it together with the
+    /// inputs needed to replay the draw.
+    pub(crate) fn internal_draw_ticket(&mut self, total_tickets: u64) -> (u64, RewardDraw) {
+        let random_number = self.internal_random_number(RandomPurpose::RaffleWinner);
+        let draw = RewardDraw {
+            random_seed: env::random_seed(),
+            caller: env::predecessor_account_id(),
+            nonce: self.random_nonce,
+            total_tickets,
+            skipped: vec![],
+        };
+
+        (random_number as u64 % total_tickets, draw)
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+
+    /// Recomputes the drawn ticket of a finalized raffle from its recorded
+    /// inputs.
+    pub fn replay_draw(&self, reward_id: U64) -> DrawReplayOutput {
+        let reward = self.internal_get_reward(reward_id.0);
+        let draw = reward.draw.expect("Reward has not been drawn");
+
+        let random_number = random_number_from(
+            &draw.random_seed,
+            &draw.caller,
+            draw.nonce,
+            RandomPurpose::RaffleWinner,
+        );
+        let ticket = random_number as u64 % draw.total_tickets;
+
+        let tickets = self
+            .reward_tickets
+            .get(&reward_id.0)
+            .expect("Tickets of the reward have been removed");
+        let ticket_holder = tickets
+            .purchases
+            .get(tickets.purchase_of(ticket))
+            .unwrap()
+            .1;
+
+        DrawReplayOutput {
+            random_seed: near_sdk::bs58::encode(&draw.random_seed).into_string(),
+            caller: draw.caller,
+            nonce: U64(draw.nonce),
+            total_tickets: U64(draw.total_tickets),
+            ticket: U64(ticket),
+            ticket_holder,
+            skipped: draw.skipped,
+            winner: reward.winner,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::{env, near_bindgen};
+use serde::Serialize;
+
+use crate::*;
+
+/// At most `max_actions` spins, claims and ticket purchases per account in
+/// any fixed window of `window_ms`. Zero `max_actions` disables the limit.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct RateLimitPolicy {
+    max_actions: u32,
+    window_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct RateLimitOutput {
+    max_actions: u32,
+    window_ms: U64,
+}
+
+impl ArkanaCoreContract {
+    /// Whether `user` has no actions left in the current window.
+    pub(crate) fn internal_is_rate_limited(&self, user: &User) -> bool {
+        let policy = &self.rate_limit;
+        let window_ends_at = user
+            .activity
+            .window_started_at()
+            .saturating_add(policy.window_ms);
+
+        policy.max_actions > 0
+            && env::block_timestamp_ms() < window_ends_at
+            && user.activity.window_actions() >= policy.max_actions
+    }
+
+    pub(crate) fn internal_consume_rate_limit(&self, user: &mut User) {
+        let policy = &self.rate_limit;
+        if policy.max_actions == 0 {
+            return;
+        }
+
+        let current_timestamp = env::block_timestamp_ms();
+        let mut window_ends_at = user
+            .activity
+            .window_started_at()
+            .saturating_add(policy.window_ms);
+        if current_timestamp >= window_ends_at {
+            user.activity.set_window_started_at(current_timestamp);
+            user.activity.set_window_actions(0);
+            window_ends_at = user
+                .activity
+                .window_started_at()
+                .saturating_add(policy.window_ms);
+        }
+
+        let window_actions = user.activity.window_actions();
+        if window_actions >= policy.max_actions {
+            panic!(
+                "Rate limit exceeded, please wait {} seconds",
+                milli_to_seconds(window_ends_at.saturating_sub(current_timestamp))
+            );
+        }
+
+        user.activity.set_window_actions(window_actions + 1);
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_rate_limit(&mut self, max_actions: u32, window_ms: U64) {
+        self.assert_owner();
+
+        assert!(
+            max_actions == 0 || window_ms.0 > 0,
+            "Window must be greater than zero"
+        );
+        assert!(
+            max_actions <= u32::from(u16::MAX),
+            "Max actions cannot exceed {}",
+            u16::MAX
+        );
+
+        self.rate_limit = RateLimitPolicy {
+            max_actions,
+            window_ms: window_ms.0,
+        };
+
+        ArkanaEvent::admin_action(
+            "set_rate_limit",
+            serde_json::json!({ "max_actions": max_actions, "window_ms": window_ms }),
+        );
+    }
+
+    // View Functions
+    pub fn get_rate_limit(&self) -> RateLimitOutput {
+        RateLimitOutput {
+            max_actions: self.rate_limit.max_actions,
+            window_ms: U64(self.rate_limit.window_ms),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub type CompetitionId = u64;
+
+/// Time-boxed race for the most referrals. Only referrals recorded within
+/// the window count, the top `prizes.len()` referrers are paid at the end.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ReferralCompetition {
+    starts_at: Timestamp,
+    ends_at: Timestamp,
+    prizes: Vec<Points>,
+    standings: Vec<(AccountId, u32)>,
+    settled: bool,
+}
+
+#[derive(Serialize)]
+pub struct ReferralStandingOutput {
+    rank: u32,
+    account_id: AccountId,
+    referrals: u32,
+    prize: U128,
+}
+
+#[derive(Serialize)]
+pub struct ReferralCompetitionOutput {
+    competition_id: U64,
+    starts_at: U64,
+    ends_at: U64,
+    standings: Vec<ReferralStandingOutput>,
+    settled: bool,
+}
+
+impl ArkanaCoreContract {
+    /// The latest competition, while it has not been settled.
+    fn internal_open_referral_competition(&self) -> Option<(CompetitionId, ReferralCompetition)> {
+        self.referral_competitions
+            .get(&self.last_referral_competition_id)
+            .filter(|competition| !competition.settled)
+            .map(|competition| (self.last_referral_competition_id, competition))
+    }
+
+    fn internal_count_referral(&mut self, referrer_id: &AccountId) {
+        let (competition_id, mut competition) = match self.internal_open_referral_competition() {
+            Some(open) => open,
+            None => return,
+        };
+
+        let current_timestamp = env::block_timestamp_ms();
+        if current_timestamp < competition.starts_at || current_timestamp >= competition.ends_at {
+            return;
+        }
+
+        let key = (competition_id, referrer_id.clone());
+        let referrals = self.referral_counts.get(&key).unwrap_or(0) + 1;
+        self.referral_counts.insert(&key, &referrals);
+
+        // Same ordering as the season leaderboard, earlier referrers win ties
+        let standings = &mut competition.standings;
+        if let Some(index) = standings.iter().position(|(a, _)| a == referrer_id) {
+            standings.remove(index);
+        }
+        let index = standings
+            .iter()
+            .position(|(_, count)| *count < referrals)
+            .unwrap_or(standings.len());
+        standings.insert(index, (referrer_id.clone(), referrals));
+        standings.truncate(LEADERBOARD_SIZE);
+
+        self.referral_competitions
+            .insert(&competition_id, &competition);
+    }
+
+    /// Pays the prizes of an ended competition. Prizes of accounts that have
+    /// unregistered since are forfeited.
+    fn internal_settle_referral_competition(&mut self, competition_id: CompetitionId) {
+        let mut competition = self
+            .referral_competitions
+            .get(&competition_id)
+            .expect("Competition does not exist");
+        assert!(!competition.settled, "Competition already settled");
+        assert!(
+            env::block_timestamp_ms() >= competition.ends_at,
+            "Competition has not ended"
+        );
+
+        for (index, (account_id, _)) in competition.standings.iter().enumerate() {
+            let prize = competition.prizes.get(index).copied().unwrap_or(0);
+            if prize == 0 || self.internal_find_user(account_id).is_none() {
+                continue;
+            }
+
+            // Like season prizes, these are not XP
+            let mut user = self.internal_get_user(account_id);
+            user.points = user.points.safe_add(prize);
+            self.supply.issued = self.supply.issued.safe_add(prize);
+            self.internal_save_user(account_id, &user);
+            self.internal_record_history(
+                account_id,
+                HistoryKind::ReferralPrize,
+                prize as i128,
+                Some(format!(
+                    "Referral competition {} rank {}",
+                    competition_id,
+                    index + 1
+                )),
+            );
+
+            ArkanaEvent::ReferralPrizePaid(vec![ReferralPrizePaidData {
+                competition_id: U64(competition_id),
+                rank: index as u32 + 1,
+                account_id: account_id.clone(),
+                prize: U128(prize),
+            }])
+            .emit();
+        }
+
+        competition.settled = true;
+        self.referral_competitions
+            .insert(&competition_id, &competition);
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Records who referred the caller. Can only be set once, the record is
+    /// kept through unregistration so referrals cannot be farmed.
+    pub fn set_referrer(&mut self, referrer_id: AccountId) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        assert!(referrer_id != account_id, "Cannot refer yourself");
+        assert!(
+            self.internal_find_user(&referrer_id).is_some(),
+            "Referrer is not registered"
+        );
+        assert!(
+            self.referrers.get(&account_id).is_none(),
+            "Referrer already set"
+        );
+        assert!(
+            self.referrers.get(&referrer_id) != Some(account_id.clone()),
+            "Cannot refer your referrer"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.referrers.insert(&account_id, &referrer_id);
+        self.internal_count_referral(&referrer_id);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::ReferralRecorded(vec![ReferralRecordedData {
+            account_id,
+            referrer_id,
+        }])
+        .emit();
+    }
+
+    /// Starts a referral competition, settling the previous one if it has
+    /// ended. The attached deposit covers its storage, the rest is refunded.
+    #[payable]
+    pub fn create_referral_competition(
+        &mut self,
+        starts_at: U64,
+        ends_at: U64,
+        prizes: Vec<U128>,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            !prizes.is_empty() && prizes.len() <= LEADERBOARD_SIZE,
+            "Competition must have between 1 and {} prizes",
+            LEADERBOARD_SIZE
+        );
+        assert!(
+            starts_at.0 < ends_at.0 && ends_at.0 > env::block_timestamp_ms(),
+            "Competition must end in the future"
+        );
+
+        if let Some((competition_id, _)) = self.internal_open_referral_competition() {
+            self.internal_settle_referral_competition(competition_id);
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let competition_id = self.last_referral_competition_id + 1;
+        self.last_referral_competition_id = competition_id;
+
+        self.referral_competitions.insert(
+            &competition_id,
+            &ReferralCompetition {
+                starts_at: starts_at.0,
+                ends_at: ends_at.0,
+                prizes: prizes.iter().map(|prize| prize.0).collect(),
+                standings: vec![],
+                settled: false,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_referral_competition",
+            serde_json::json!({
+                "competition_id": U64(competition_id),
+                "starts_at": starts_at,
+                "ends_at": ends_at,
+                "prizes": prizes,
+            }),
+        );
+
+        U64(competition_id)
+    }
+
+    /// Pays out an ended competition. Callable by anyone.
+    pub fn settle_referral_competition(&mut self, competition_id: U64) {
+        self.internal_settle_referral_competition(competition_id.0);
+    }
+
+    // View Functions
+    pub fn get_referrer(&self, account_id: AccountId) -> Option<AccountId> {
+        self.referrers.get(&account_id)
+    }
+
+    pub fn get_referral_competition(
+        &self,
+        competition_id: U64,
+    ) -> Option<ReferralCompetitionOutput> {
+        self.referral_competitions
+            .get(&competition_id.0)
+            .map(|competition| ReferralCompetitionOutput {
+                competition_id,
+                starts_at: U64(competition.starts_at),
+                ends_at: U64(competition.ends_at),
+                standings: competition
+                    .standings
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (account_id, referrals))| ReferralStandingOutput {
+                        rank: index as u32 + 1,
+                        account_id: account_id.clone(),
+                        referrals: *referrals,
+                        prize: U128(competition.prizes.get(index).copied().unwrap_or(0)),
+                    })
+                    .collect(),
+                settled: competition.settled,
+            })
+    }
+
+    pub fn get_referral_count(&self, competition_id: U64, account_id: AccountId) -> u32 {
+        self.referral_counts
+            .get(&(competition_id.0, account_id))
+            .unwrap_or(0)
+    }
+}
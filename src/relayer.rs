@@ -0,0 +1,116 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub const MAX_SPIN_RELAYERS: usize = 3;
+
+/// A relayer allowed to spin for a user, at most `daily_limit` spins per
+/// UTC day.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SpinRelayer {
+    relayer_id: AccountId,
+    daily_limit: u64,
+    usage: DailyUsage,
+}
+
+#[derive(Serialize)]
+pub struct SpinRelayerOutput {
+    relayer_id: AccountId,
+    daily_limit: U64,
+    used_today: U64,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Lets `relayer_id` call `play_spin_wheel_for` on behalf of the caller.
+    /// Authorizing an existing relayer again updates its limit.
+    #[payable]
+    pub fn authorize_spin_relayer(&mut self, relayer_id: AccountId, daily_limit: U64) {
+        assert_one_yocto();
+
+        let account_id = self.internal_player_id();
+        self.internal_get_user(&account_id);
+
+        assert!(daily_limit.0 > 0, "Daily limit must be greater than zero");
+
+        let initial_storage_usage = env::storage_usage();
+        let mut relayers = self.spin_relayers.get(&account_id).unwrap_or_default();
+        match relayers
+            .iter_mut()
+            .find(|relayer| relayer.relayer_id == relayer_id)
+        {
+            Some(relayer) => relayer.daily_limit = daily_limit.0,
+            None => {
+                assert!(
+                    relayers.len() < MAX_SPIN_RELAYERS,
+                    "Cannot authorize more than {} relayers",
+                    MAX_SPIN_RELAYERS
+                );
+                relayers.push(SpinRelayer {
+                    relayer_id,
+                    daily_limit: daily_limit.0,
+                    usage: DailyUsage::default(),
+                });
+            }
+        }
+        self.spin_relayers.insert(&account_id, &relayers);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+    }
+
+    #[payable]
+    pub fn revoke_spin_relayer(&mut self, relayer_id: AccountId) {
+        assert_one_yocto();
+
+        let account_id = self.internal_player_id();
+
+        let initial_storage_usage = env::storage_usage();
+        let mut relayers = self.spin_relayers.get(&account_id).unwrap_or_default();
+        let len = relayers.len();
+        relayers.retain(|relayer| relayer.relayer_id != relayer_id);
+        assert!(relayers.len() < len, "Relayer is not authorized");
+
+        if relayers.is_empty() {
+            self.spin_relayers.remove(&account_id);
+        } else {
+            self.spin_relayers.insert(&account_id, &relayers);
+        }
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+    }
+
+    /// Spins for `account_id`, crediting the result to them. Only callable
+    /// by a relayer they authorized, within its daily limit.
+    pub fn play_spin_wheel_for(&mut self, account_id: AccountId, is_free: bool) -> U128 {
+        let relayer_id = env::predecessor_account_id();
+
+        let mut relayers = self.spin_relayers.get(&account_id).unwrap_or_default();
+        let relayer = relayers
+            .iter_mut()
+            .find(|relayer| relayer.relayer_id == relayer_id)
+            .expect("Relayer is not authorized");
+        assert!(
+            relayer.usage.used_today() < Points::from(relayer.daily_limit),
+            "Relayer daily spin limit reached"
+        );
+        relayer.usage.consume(1, Points::from(relayer.daily_limit));
+        self.spin_relayers.insert(&account_id, &relayers);
+
+        U128(self.internal_play_spin_wheel(&account_id, is_free))
+    }
+
+    // View Functions
+    pub fn get_spin_relayers(&self, account_id: AccountId) -> Vec<SpinRelayerOutput> {
+        self.spin_relayers
+            .get(&account_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|relayer| SpinRelayerOutput {
+                used_today: U64(relayer.usage.used_today() as u64),
+                daily_limit: U64(relayer.daily_limit),
+                relayer_id: relayer.relayer_id,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,204 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub const MAX_SELF_EXCLUSION: u64 = 5 * 365 * ONE_DAY;
+
+/// Limits a user placed on their own spins, lootboxes and mini-games. Until
+/// they expire they can only be made stricter.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct SpendingLimits {
+    excluded_until: Timestamp,
+    daily_limit: Points,
+    limit_expires_at: Timestamp,
+    usage: DailyUsage,
+}
+
+impl SpendingLimits {
+    fn is_excluded(&self, timestamp: Timestamp) -> bool {
+        timestamp < self.excluded_until
+    }
+
+    fn has_limit(&self, timestamp: Timestamp) -> bool {
+        timestamp < self.limit_expires_at
+    }
+}
+
+#[derive(Serialize)]
+pub struct SpendingLimitsOutput {
+    excluded_until: Option<U64>,
+    daily_limit: Option<U128>,
+    limit_expires_at: Option<U64>,
+    spent_today: U128,
+}
+
+impl SpendKind {
+    pub(crate) fn is_gambling(&self) -> bool {
+        matches!(self, SpendKind::Spin | SpendKind::Lootbox | SpendKind::Game)
+    }
+}
+
+fn assert_valid_period(until: Timestamp) {
+    let current_timestamp = env::block_timestamp_ms();
+    assert!(until > current_timestamp, "Must end in the future");
+    assert!(
+        until - current_timestamp <= MAX_SELF_EXCLUSION,
+        "Cannot last longer than {} ms",
+        MAX_SELF_EXCLUSION
+    );
+}
+
+impl ArkanaCoreContract {
+    /// Why `account_id` may not spend `amount` on gambling right now.
+    pub(crate) fn internal_spending_check(
+        &self,
+        account_id: &AccountId,
+        amount: Points,
+    ) -> Result<(), String> {
+        let limits = match self.spending_limits.get(account_id) {
+            Some(limits) => limits,
+            None => return Ok(()),
+        };
+
+        let current_timestamp = env::block_timestamp_ms();
+        if limits.is_excluded(current_timestamp) {
+            return Err(format!("Self-excluded until {}", limits.excluded_until));
+        }
+        if limits.has_limit(current_timestamp)
+            && limits.usage.used_today().safe_add(amount) > limits.daily_limit
+        {
+            return Err("Daily spending limit exceeded".to_string());
+        }
+        Ok(())
+    }
+
+    /// Points `account_id` may still spend on gambling today, `None` without
+    /// a limit.
+    pub(crate) fn internal_spending_allowance(&self, account_id: &AccountId) -> Option<Points> {
+        let limits = self.spending_limits.get(account_id)?;
+
+        let current_timestamp = env::block_timestamp_ms();
+        if limits.is_excluded(current_timestamp) {
+            Some(0)
+        } else if limits.has_limit(current_timestamp) {
+            Some(limits.daily_limit.saturating_sub(limits.usage.used_today()))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn assert_not_self_excluded(&self, account_id: &AccountId) {
+        if let Err(error) = self.internal_spending_check(account_id, 0) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Counts `amount` against the account's daily limit, if it set one.
+    pub(crate) fn internal_track_gambling_spend(&mut self, account_id: &AccountId, amount: Points) {
+        if let Err(error) = self.internal_spending_check(account_id, amount) {
+            panic!("{}", error);
+        }
+
+        if let Some(mut limits) = self.spending_limits.get(account_id) {
+            if limits.has_limit(env::block_timestamp_ms()) {
+                limits.usage.consume(amount, limits.daily_limit);
+                self.spending_limits.insert(account_id, &limits);
+            }
+        }
+    }
+
+    /// Limits that have not expired yet survive unregistering, so they
+    /// cannot be shed by re-registering.
+    pub(crate) fn internal_clear_expired_limits(&mut self, account_id: &AccountId) {
+        if let Some(limits) = self.spending_limits.get(account_id) {
+            let current_timestamp = env::block_timestamp_ms();
+            if !limits.is_excluded(current_timestamp) && !limits.has_limit(current_timestamp) {
+                self.spending_limits.remove(account_id);
+            }
+        }
+    }
+
+    fn internal_update_spending_limits(&mut self, update: impl FnOnce(&mut SpendingLimits)) {
+        let account_id = self.internal_player_id();
+        self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        let mut limits = self.spending_limits.get(&account_id).unwrap_or_default();
+        update(&mut limits);
+        self.spending_limits.insert(&account_id, &limits);
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::SpendingLimitsUpdated(vec![SpendingLimitsUpdatedData {
+            account_id,
+            excluded_until: U64(limits.excluded_until),
+            daily_limit: U128(limits.daily_limit),
+            limit_expires_at: U64(limits.limit_expires_at),
+        }])
+        .emit();
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Locks the caller out of spins, lootboxes and mini-games until `until`.
+    /// Cannot be lifted or shortened, only extended.
+    #[payable]
+    pub fn self_exclude(&mut self, until: U64) {
+        assert_one_yocto();
+
+        assert_valid_period(until.0);
+
+        self.internal_update_spending_limits(|limits| {
+            assert!(
+                until.0 >= limits.excluded_until,
+                "Self-exclusion can only be extended"
+            );
+            limits.excluded_until = until.0;
+        });
+    }
+
+    /// Caps the points the caller spends on spins, lootboxes and mini-games
+    /// per UTC day until `until`. While a cap is in force it can only be
+    /// lowered or kept for longer.
+    #[payable]
+    pub fn set_daily_spending_limit(&mut self, daily_limit: U128, until: U64) {
+        assert_one_yocto();
+
+        assert_valid_period(until.0);
+
+        self.internal_update_spending_limits(|limits| {
+            if limits.has_limit(env::block_timestamp_ms()) {
+                assert!(
+                    daily_limit.0 <= limits.daily_limit && until.0 >= limits.limit_expires_at,
+                    "Spending limit can only be tightened until it expires"
+                );
+            }
+            limits.daily_limit = daily_limit.0;
+            limits.limit_expires_at = until.0;
+        });
+    }
+
+    // View Functions
+    pub fn get_spending_limits(&self, account_id: AccountId) -> SpendingLimitsOutput {
+        let limits = self.spending_limits.get(&account_id).unwrap_or_default();
+        let current_timestamp = env::block_timestamp_ms();
+        let has_limit = limits.has_limit(current_timestamp);
+
+        SpendingLimitsOutput {
+            excluded_until: limits
+                .is_excluded(current_timestamp)
+                .then_some(U64(limits.excluded_until)),
+            daily_limit: has_limit.then_some(U128(limits.daily_limit)),
+            limit_expires_at: has_limit.then_some(U64(limits.limit_expires_at)),
+            spent_today: U128(if has_limit {
+                limits.usage.used_today()
+            } else {
+                0
+            }),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub type RewardProposalId = u64;
+
+/// A reward suggested by a proposer contract (e.g. a community DAO). It is
+/// created once `reward_approval_threshold` admins approved it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RewardProposal {
+    proposer: AccountId,
+    title: String,
+    price: Points,
+    ended_at: Timestamp,
+    approvals: Vec<AccountId>,
+    proposed_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct RewardProposalOutput {
+    proposal_id: U64,
+    proposer: AccountId,
+    title: String,
+    price: U128,
+    ended_at: U64,
+    approvals: Vec<AccountId>,
+    proposed_at: U64,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[payable]
+    pub fn add_reward_proposer(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.reward_proposers.insert(&account_id);
+
+        ArkanaEvent::admin_action(
+            "add_reward_proposer",
+            serde_json::json!({ "account_id": account_id }),
+        );
+    }
+
+    #[payable]
+    pub fn remove_reward_proposer(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.reward_proposers.remove(&account_id);
+
+        ArkanaEvent::admin_action(
+            "remove_reward_proposer",
+            serde_json::json!({ "account_id": account_id }),
+        );
+    }
+
+    /// Number of approvals from the owner or operators a proposal needs.
+    pub fn set_reward_approval_threshold(&mut self, threshold: u8) {
+        self.assert_owner();
+
+        assert!(threshold > 0, "Invalid threshold");
+        self.reward_approval_threshold = threshold;
+
+        ArkanaEvent::admin_action(
+            "set_reward_approval_threshold",
+            serde_json::json!({ "threshold": threshold }),
+        );
+    }
+
+    /// Queues a reward for approval. The attached deposit covers the
+    /// proposal's storage, the rest is refunded.
+    #[payable]
+    pub fn propose_reward(&mut self, title: String, price: U128, ended_at: U64) -> U64 {
+        let proposer = env::predecessor_account_id();
+        assert!(self.reward_proposers.contains(&proposer), "Unauthorized");
+        assert_valid_reward(&title, price.0, ended_at.0, env::block_timestamp_ms());
+
+        let initial_storage_usage = env::storage_usage();
+
+        let proposal_id = self.last_reward_proposal_id + 1;
+        self.last_reward_proposal_id = proposal_id;
+        self.reward_proposals.insert(
+            &proposal_id,
+            &RewardProposal {
+                proposer: proposer.clone(),
+                title: title.clone(),
+                price: price.0,
+                ended_at: ended_at.0,
+                approvals: vec![],
+                proposed_at: env::block_timestamp_ms(),
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::RewardProposed(vec![RewardProposedData {
+            proposal_id: U64(proposal_id),
+            proposer,
+            title,
+            price,
+            ended_at,
+        }])
+        .emit();
+
+        U64(proposal_id)
+    }
+
+    /// Records the caller's approval and creates the reward once the
+    /// threshold is reached. Returns the id of the created reward.
+    pub fn approve_reward_proposal(&mut self, proposal_id: U64) -> Option<U64> {
+        self.assert_owner_or_operator();
+
+        let predecessor_id = env::predecessor_account_id();
+        let mut proposal = self
+            .reward_proposals
+            .get(&proposal_id.0)
+            .expect("Proposal does not exist");
+
+        assert!(
+            !proposal.approvals.contains(&predecessor_id),
+            "Already approved"
+        );
+        proposal.approvals.push(predecessor_id);
+
+        // Approvals from operators removed in the meantime no longer count
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|account_id| **account_id == self.owner || self.operators.contains(account_id))
+            .count();
+
+        if approvals < self.reward_approval_threshold as usize {
+            self.reward_proposals.insert(&proposal_id.0, &proposal);
+            return None;
+        }
+
+        self.reward_proposals.remove(&proposal_id.0);
+        let reward_id =
+            self.internal_create_reward(proposal.title, proposal.price, proposal.ended_at);
+
+        ArkanaEvent::RewardProposalResolved(vec![RewardProposalResolvedData {
+            proposal_id,
+            reward_id: Some(U64(reward_id)),
+        }])
+        .emit();
+
+        Some(U64(reward_id))
+    }
+
+    /// Drops a proposal, by the owner or the proposer itself.
+    pub fn reject_reward_proposal(&mut self, proposal_id: U64) {
+        let proposal = self
+            .reward_proposals
+            .get(&proposal_id.0)
+            .expect("Proposal does not exist");
+
+        let predecessor_id = env::predecessor_account_id();
+        assert!(
+            predecessor_id == self.owner || predecessor_id == proposal.proposer,
+            "Unauthorized"
+        );
+
+        self.reward_proposals.remove(&proposal_id.0);
+
+        ArkanaEvent::RewardProposalResolved(vec![RewardProposalResolvedData {
+            proposal_id,
+            reward_id: None,
+        }])
+        .emit();
+    }
+
+    // View Functions
+    pub fn get_reward_proposals(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<RewardProposalOutput> {
+        paginate(self.reward_proposals.iter(), from_index, limit).map(|(proposal_id, proposal)| {
+            RewardProposalOutput {
+                proposal_id: U64(proposal_id),
+                proposer: proposal.proposer,
+                title: proposal.title,
+                price: U128(proposal.price),
+                ended_at: U64(proposal.ended_at),
+                approvals: proposal.approvals,
+                proposed_at: U64(proposal.proposed_at),
+            }
+        })
+    }
+}
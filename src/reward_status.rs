@@ -0,0 +1,80 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::near_bindgen;
+use serde::Serialize;
+
+use crate::*;
+
+/// Lifecycle of a raffle. Tickets are only sold while `Active` and before
+/// `ended_at`, a draw moves it through `Drawing` to `Finalized`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardStatus {
+    Active,
+    Drawing,
+    Finalized,
+    Cancelled,
+}
+
+impl RewardStatus {
+    fn can_become(self, next: RewardStatus) -> bool {
+        matches!(
+            (self, next),
+            (RewardStatus::Active, RewardStatus::Drawing)
+                | (RewardStatus::Active, RewardStatus::Cancelled)
+                | (RewardStatus::Drawing, RewardStatus::Finalized)
+        )
+    }
+
+    /// Completes "Reward ..." in transition errors.
+    pub(crate) fn describe(self) -> &'static str {
+        match self {
+            RewardStatus::Active => "is still active",
+            RewardStatus::Drawing => "is being drawn",
+            RewardStatus::Finalized => "finalized",
+            RewardStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub(crate) fn is_closed(self) -> bool {
+        matches!(self, RewardStatus::Finalized | RewardStatus::Cancelled)
+    }
+}
+
+impl Reward {
+    pub(crate) fn set_status(&mut self, next: RewardStatus) {
+        assert!(
+            self.status.can_become(next),
+            "Reward {}",
+            self.status.describe()
+        );
+        self.status = next;
+    }
+
+    /// Whether tickets can still be added at `timestamp`.
+    pub(crate) fn is_open(&self, timestamp: Timestamp) -> bool {
+        self.status == RewardStatus::Active && timestamp < self.ended_at
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Closes a raffle that has not handed out any tickets yet, so it can no
+    /// longer be entered or drawn.
+    pub fn cancel_reward(&mut self, reward_id: U64) {
+        self.assert_owner();
+
+        let mut reward = self.internal_get_reward(reward_id.0);
+        let tickets = self.reward_tickets.get(&reward_id.0).unwrap();
+        assert!(tickets.total == 0, "Reward already has tickets");
+
+        reward.set_status(RewardStatus::Cancelled);
+        self.rewards.insert(&reward_id.0, &reward);
+        self.reward_items.remove(&reward_id.0);
+
+        ArkanaEvent::admin_action(
+            "cancel_reward",
+            serde_json::json!({ "reward_id": reward_id }),
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, Balance, Promise};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type TaskId = u64;
+
+/// Time-based transitions the owner can schedule ahead of time.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    StartNewSeason,
+    // e.g. switching to a seasonal wheel. Queued when scheduled, the task
+    // then holds the matching `ExecuteConfigChange`
+    ApplyConfigChange {
+        change: ConfigChange,
+    },
+    ExecuteConfigChange {
+        change_id: U64,
+    },
+    CreateReward {
+        title: String,
+        price: U128,
+        ended_at: U64,
+    },
+    FinalizeReward {
+        reward_id: U64,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ScheduledTask {
+    action: ScheduledAction,
+    execute_at: Timestamp,
+    // Paid to the keeper executing the task
+    bounty: Balance,
+}
+
+#[derive(Serialize)]
+pub struct ScheduledTaskOutput {
+    task_id: U64,
+    action: ScheduledAction,
+    execute_at: U64,
+    bounty: U128,
+    is_due: bool,
+}
+
+impl ArkanaCoreContract {
+    /// Whether the task's target is gone, e.g. a reward finalized by hand or
+    /// a config change cancelled in the meantime.
+    fn internal_is_stale_action(&self, action: &ScheduledAction) -> bool {
+        match action {
+            ScheduledAction::ExecuteConfigChange { change_id } => {
+                self.pending_config_changes.get(&change_id.0).is_none()
+            }
+            ScheduledAction::FinalizeReward { reward_id } => self
+                .rewards
+                .get(&reward_id.0)
+                .is_none_or(|reward| reward.status != RewardStatus::Active),
+            _ => false,
+        }
+    }
+
+    fn internal_refund_task_bounty(&mut self, task_id: U64, bounty: Balance) {
+        if bounty > 0 {
+            Promise::new(self.owner.clone()).transfer(bounty);
+        }
+
+        ArkanaEvent::TaskCancelled(vec![TaskExecutedData {
+            task_id,
+            account_id: env::predecessor_account_id(),
+            bounty: U128(bounty),
+        }])
+        .emit();
+    }
+
+    fn internal_execute_scheduled_action(&mut self, action: ScheduledAction) {
+        match action {
+            ScheduledAction::StartNewSeason => {
+                self.internal_start_new_season();
+            }
+            // Only tasks scheduled before changes were queued up front
+            ScheduledAction::ApplyConfigChange { change } => {
+                assert!(
+                    !change.is_guarded() || self.guardians.is_empty(),
+                    "Requires guardian approval"
+                );
+                self.internal_queue_config_change(change);
+            }
+            ScheduledAction::ExecuteConfigChange { change_id } => {
+                self.execute_config_change(change_id);
+            }
+            ScheduledAction::CreateReward {
+                title,
+                price,
+                ended_at,
+            } => {
+                self.internal_create_reward(title, price.0, ended_at.0);
+            }
+            ScheduledAction::FinalizeReward { reward_id } => {
+                self.finalize_reward(reward_id, false);
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Schedules `action` for `execute_at`. The attached deposit covers the
+    /// keeper bounty plus storage, the rest is refunded. Config changes have
+    /// to respect the config timelock and are queued right away, so they show
+    /// up in `get_pending_config_changes`.
+    #[payable]
+    pub fn schedule_task(&mut self, action: ScheduledAction, execute_at: U64, bounty: U128) -> U64 {
+        self.assert_owner();
+
+        let initial_storage_usage = env::storage_usage();
+
+        let action = match action {
+            ScheduledAction::ApplyConfigChange { change } => {
+                assert!(
+                    !change.is_guarded() || self.guardians.is_empty(),
+                    "Requires guardian approval"
+                );
+                let change_id = self.internal_queue_config_change_at(change, execute_at.0);
+                ScheduledAction::ExecuteConfigChange {
+                    change_id: U64(change_id),
+                }
+            }
+            ScheduledAction::CreateReward {
+                title,
+                price,
+                ended_at,
+            } => {
+                assert_valid_reward(&title, price.0, ended_at.0, execute_at.0);
+                ScheduledAction::CreateReward {
+                    title,
+                    price,
+                    ended_at,
+                }
+            }
+            action => action,
+        };
+
+        let task_id = self.last_task_id + 1;
+        self.last_task_id = task_id;
+        self.scheduled_tasks.insert(
+            &task_id,
+            &ScheduledTask {
+                action: action.clone(),
+                execute_at: execute_at.0,
+                bounty: bounty.0,
+            },
+        );
+
+        let storage_cost =
+            Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+        let required = bounty.0.safe_add(storage_cost);
+        let attached_deposit = env::attached_deposit();
+        assert!(
+            attached_deposit >= required,
+            "Must attach {} yoctoNEAR to cover the bounty and storage",
+            required
+        );
+
+        let refund = attached_deposit - required;
+        if refund > 1 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+
+        ArkanaEvent::TaskScheduled(vec![TaskScheduledData {
+            task_id: U64(task_id),
+            action,
+            execute_at,
+            bounty,
+        }])
+        .emit();
+
+        U64(task_id)
+    }
+
+    /// Cancels a task and returns its bounty to the owner. A config change
+    /// the task would execute is cancelled with it.
+    #[payable]
+    pub fn cancel_task(&mut self, task_id: U64) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        let task = self
+            .scheduled_tasks
+            .remove(&task_id.0)
+            .expect("Task does not exist");
+
+        if let ScheduledAction::ExecuteConfigChange { change_id } = task.action {
+            if self.pending_config_changes.remove(&change_id.0).is_some() {
+                ArkanaEvent::ConfigChangeCancelled(vec![ConfigChangeAppliedData { change_id }])
+                    .emit();
+            }
+        }
+
+        self.internal_refund_task_bounty(task_id, task.bounty);
+    }
+
+    /// Executes a due task and pays its bounty to the caller. Callable by
+    /// anyone so no single keeper has to be trusted. A stale task is dropped
+    /// instead and its bounty returned to the owner.
+    pub fn execute_task(&mut self, task_id: U64) {
+        let task = self
+            .scheduled_tasks
+            .get(&task_id.0)
+            .expect("Task does not exist");
+
+        assert!(
+            env::block_timestamp_ms() >= task.execute_at,
+            "Task is not due yet"
+        );
+
+        self.scheduled_tasks.remove(&task_id.0);
+        if self.internal_is_stale_action(&task.action) {
+            self.internal_refund_task_bounty(task_id, task.bounty);
+            return;
+        }
+        self.internal_execute_scheduled_action(task.action);
+
+        let keeper_id = env::predecessor_account_id();
+        if task.bounty > 0 {
+            Promise::new(keeper_id.clone()).transfer(task.bounty);
+        }
+
+        ArkanaEvent::TaskExecuted(vec![TaskExecutedData {
+            task_id,
+            account_id: keeper_id,
+            bounty: U128(task.bounty),
+        }])
+        .emit();
+    }
+    // View Functions
+    pub fn get_scheduled_tasks(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<ScheduledTaskOutput> {
+        let current_timestamp = env::block_timestamp_ms();
+
+        paginate(self.scheduled_tasks.iter(), from_index, limit).map(|(task_id, task)| {
+            ScheduledTaskOutput {
+                task_id: U64(task_id),
+                action: task.action,
+                execute_at: U64(task.execute_at),
+                bounty: U128(task.bounty),
+                is_due: current_timestamp >= task.execute_at,
+            }
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type ScratchBatchId = u64;
+
+pub const MAX_SCRATCH_TIERS: usize = 8;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct ScratchTier {
+    // Zero for losing cards
+    pub prize: U128,
+    pub count: u32,
+}
+
+/// A fixed pool of cards. Every card sold is drawn from what is left, so the
+/// batch pays out exactly the sum of its tiers once sold out.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ScratchBatch {
+    title: String,
+    price: Points,
+    tiers: Vec<ScratchTier>,
+    remaining: u32,
+}
+
+#[derive(Serialize)]
+pub struct ScratchBatchOutput {
+    batch_id: U64,
+    title: String,
+    price: U128,
+    // Cards left per tier
+    tiers: Vec<ScratchTier>,
+    remaining: u32,
+}
+
+impl ScratchBatch {
+    /// Takes the card `random_number` lands on out of the pool and returns
+    /// its prize.
+    fn draw(&mut self, random_number: u32) -> Points {
+        assert!(self.remaining > 0, "Scratch cards are sold out");
+
+        let random_number = random_number % self.remaining;
+
+        let mut cumulative_count = 0;
+        for tier in self.tiers.iter_mut() {
+            cumulative_count += tier.count;
+            if random_number < cumulative_count {
+                tier.count -= 1;
+                self.remaining -= 1;
+                return tier.prize.0;
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the batch's storage, the rest is refunded.
+    #[payable]
+    pub fn create_scratch_batch(
+        &mut self,
+        title: String,
+        price: U128,
+        tiers: Vec<ScratchTier>,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(price.0 > 0, "Price must be greater than zero");
+        assert!(
+            !tiers.is_empty() && tiers.len() <= MAX_SCRATCH_TIERS,
+            "Batch must have between 1 and {} tiers",
+            MAX_SCRATCH_TIERS
+        );
+        let remaining = tiers.iter().fold(0u32, |total, tier| {
+            total.checked_add(tier.count).expect("Too many cards")
+        });
+        assert!(remaining > 0, "Batch must have at least one card");
+
+        let initial_storage_usage = env::storage_usage();
+
+        let batch_id = self.last_scratch_batch_id + 1;
+        self.last_scratch_batch_id = batch_id;
+
+        self.scratch_batches.insert(
+            &batch_id,
+            &ScratchBatch {
+                title: title.clone(),
+                price: price.0,
+                tiers: tiers.clone(),
+                remaining,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_scratch_batch",
+            serde_json::json!({
+                "batch_id": U64(batch_id),
+                "title": title,
+                "price": price,
+                "tiers": tiers,
+            }),
+        );
+
+        U64(batch_id)
+    }
+
+    /// Withdraws a batch from sale, its unsold prizes are never paid out.
+    pub fn remove_scratch_batch(&mut self, batch_id: U64) {
+        self.assert_owner();
+
+        self.scratch_batches
+            .remove(&batch_id.0)
+            .expect("Scratch batch does not exist");
+
+        ArkanaEvent::admin_action(
+            "remove_scratch_batch",
+            serde_json::json!({ "batch_id": batch_id }),
+        );
+    }
+
+    /// Buys a card from the batch and reveals it right away. Returns the
+    /// points won.
+    pub fn buy_scratch_card(&mut self, batch_id: U64) -> U128 {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut batch = self
+            .scratch_batches
+            .get(&batch_id.0)
+            .expect("Scratch batch does not exist");
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+
+        if user.points < batch.price {
+            panic!("Points insufficient");
+        }
+        self.internal_spend_points(&account_id, &mut user, batch.price, SpendKind::Game);
+        self.internal_feed_jackpot(&account_id, &mut user, batch.price);
+
+        let prize = batch.draw(self.internal_random_number(RandomPurpose::ScratchCard));
+        if prize > 0 {
+            self.internal_issue_points(&account_id, &mut user, prize);
+        }
+
+        self.scratch_batches.insert(&batch_id.0, &batch);
+        self.internal_save_user(&account_id, &user);
+
+        ArkanaEvent::ScratchCardRevealed(vec![ScratchCardRevealedData {
+            account_id,
+            batch_id,
+            prize: U128(prize),
+            remaining: batch.remaining,
+        }])
+        .emit();
+
+        U128(prize)
+    }
+
+    // View Functions
+    pub fn get_scratch_batches(&self) -> Vec<ScratchBatchOutput> {
+        self.scratch_batches
+            .iter()
+            .map(|(batch_id, batch)| ScratchBatchOutput {
+                batch_id: U64(batch_id),
+                title: batch.title,
+                price: U128(batch.price),
+                tiers: batch.tiers,
+                remaining: batch.remaining,
+            })
+            .collect()
+    }
+}
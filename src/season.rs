@@ -0,0 +1,200 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SeasonSnapshot {
+    points: Points,
+    xp: u64,
+    season_xp: u64,
+}
+
+#[derive(Serialize)]
+pub struct SeasonSnapshotOutput {
+    season: SeasonId,
+    points: U128,
+    xp: U64,
+    season_xp: U64,
+}
+
+#[derive(Serialize)]
+pub struct SeasonOutput {
+    season: SeasonId,
+    started_at: U64,
+    carry_over_bps: u16,
+    next_carry_over_bps: u16,
+}
+
+fn carry_over(points: Points, carry_over_bps: u16) -> Points {
+    apply_bps(points, carry_over_bps as u32)
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_clear_season_snapshots(&mut self, account_id: &AccountId) {
+        for season in 0..self.current_season {
+            self.season_snapshots.remove(&(account_id.clone(), season));
+        }
+    }
+
+    /// Records the user's balances for the season they last interacted in.
+    /// Balances cannot change without an interaction, so the values at the
+    /// first interaction of a new season are exactly the season-end values.
+    ///
+    /// Each skipped season transition applies the carry-over policy that was
+    /// in force when that season started.
+    pub(crate) fn internal_settle_season(&mut self, account_id: &AccountId, user: &mut User) {
+        let points_before = user.points;
+
+        while user.season < self.current_season {
+            self.season_snapshots.insert(
+                &(account_id.clone(), user.season),
+                &SeasonSnapshot {
+                    points: user.points,
+                    xp: user.xp,
+                    season_xp: user.season_xp,
+                },
+            );
+
+            user.season += 1;
+            user.season_xp = 0;
+            user.points = carry_over(user.points, self.internal_carry_over_bps(user.season));
+        }
+
+        if user.points != points_before {
+            self.supply.burned = self.supply.burned.safe_add(points_before - user.points);
+            self.internal_record_history(
+                account_id,
+                HistoryKind::SeasonRollover,
+                -((points_before - user.points) as i128),
+                None,
+            );
+        }
+    }
+
+    pub(crate) fn internal_carry_over_bps(&self, season: SeasonId) -> u16 {
+        self.season_carry_over_bps.get(&season).unwrap_or(MAX_BPS)
+    }
+
+    pub(crate) fn internal_start_new_season(&mut self) -> SeasonId {
+        let ended_season = self.current_season;
+        self.internal_close_season_standings(ended_season);
+        self.current_season += 1;
+        self.season_started_at = env::block_timestamp_ms();
+
+        if self.next_season_carry_over_bps != MAX_BPS {
+            self.season_carry_over_bps
+                .insert(&self.current_season, &self.next_season_carry_over_bps);
+        }
+
+        ArkanaEvent::SeasonStarted(vec![SeasonStartedData {
+            season: self.current_season,
+            ended_season,
+            started_at: U64(self.season_started_at),
+            carry_over_bps: self.next_season_carry_over_bps,
+        }])
+        .emit();
+
+        self.current_season
+    }
+
+    /// Spendable points of a user as if their season rollover were settled now.
+    pub(crate) fn internal_settled_points(&self, user: &User) -> Points {
+        self.internal_carried_over(user.points, user.season)
+    }
+
+    /// What is left of `points` held since `season` once the carry-over of
+    /// every later season is applied.
+    pub(crate) fn internal_carried_over(&self, points: Points, season: SeasonId) -> Points {
+        (season + 1..=self.current_season).fold(points, |points, season| {
+            carry_over(points, self.internal_carry_over_bps(season))
+        })
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[payable]
+    pub fn start_new_season(&mut self) -> SeasonId {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.internal_start_new_season()
+    }
+
+    /// Sets the share of spendable and staked points, in basis points, users
+    /// keep when the next season starts. `10000` carries everything over, `0`
+    /// resets balances.
+    #[payable]
+    pub fn set_season_carry_over(&mut self, carry_over_bps: u16) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(
+            carry_over_bps <= MAX_BPS,
+            "Carry over cannot exceed {} bps",
+            MAX_BPS
+        );
+
+        self.next_season_carry_over_bps = carry_over_bps;
+
+        ArkanaEvent::admin_action(
+            "set_season_carry_over",
+            serde_json::json!({ "carry_over_bps": carry_over_bps }),
+        );
+    }
+
+    // View Functions
+    pub fn get_current_season(&self) -> SeasonOutput {
+        SeasonOutput {
+            season: self.current_season,
+            started_at: U64(self.season_started_at),
+            carry_over_bps: self.internal_carry_over_bps(self.current_season),
+            next_carry_over_bps: self.next_season_carry_over_bps,
+        }
+    }
+
+    pub fn get_season_snapshot(
+        &self,
+        account_id: AccountId,
+        season: SeasonId,
+    ) -> Option<SeasonSnapshotOutput> {
+        if season >= self.current_season {
+            return None;
+        }
+
+        if let Some(snapshot) = self.season_snapshots.get(&(account_id.clone(), season)) {
+            return Some(SeasonSnapshotOutput {
+                season,
+                points: U128(snapshot.points),
+                xp: U64(snapshot.xp),
+                season_xp: U64(snapshot.season_xp),
+            });
+        }
+
+        // Not settled yet: replay the rollovers the user would go through
+        let user = self.internal_find_user(&account_id)?;
+        if user.season > season {
+            return None;
+        }
+
+        let points = (user.season + 1..=season).fold(user.points, |points, season| {
+            carry_over(points, self.internal_carry_over_bps(season))
+        });
+
+        Some(SeasonSnapshotOutput {
+            season,
+            points: U128(points),
+            xp: U64(user.xp),
+            season_xp: U64(if user.season == season {
+                user.season_xp
+            } else {
+                0
+            }),
+        })
+    }
+}
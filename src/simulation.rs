@@ -0,0 +1,119 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(Serialize)]
+pub struct SegmentOddsOutput {
+    // With the running earnings multiplier applied
+    points: U128,
+    item: Option<ItemId>,
+    // Draw outcomes landing on the segment, out of `total_outcomes`
+    outcomes: u32,
+}
+
+/// What the next spin of an account could land on and how many it can pay
+/// for right now.
+#[derive(Serialize)]
+pub struct SpinSimulationOutput {
+    segments: Vec<SegmentOddsOutput>,
+    total_outcomes: u32,
+    expected_points: U128,
+    spin_price: U128,
+    // Paid spins covered by the account's points and spending limit
+    affordable_spins: U64,
+    free_spin_available: bool,
+}
+
+/// What a daily claim would credit right now.
+#[derive(Serialize)]
+pub struct ClaimSimulationOutput {
+    // Why the claim would fail, see `estimate_daily_claim`
+    error: Option<String>,
+    claim_points: U128,
+    claim_streak: u32,
+    check_in_points: U128,
+    check_in_streak: Option<u8>,
+    check_in_bonus: Option<CheckInBonus>,
+    total_points: U128,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    // View Functions
+
+    /// Odds of the next spin of `account_id`, including the wheel's miss
+    /// streak and the account's odds booster.
+    pub fn simulate_spin(&self, account_id: AccountId) -> SpinSimulationOutput {
+        let user = self.internal_find_user(&account_id);
+        let odds_boost = user.as_ref().map(|user| user.odds_boost).unwrap_or(0);
+        let (outcomes, total_outcomes) = self
+            .wheel_weights
+            .odds(self.spinwheel_wr as u32 + odds_boost as u32);
+
+        let segments: Vec<SegmentOddsOutput> = self
+            .wheel_segments
+            .iter()
+            .zip(outcomes)
+            .map(|(segment, outcomes)| SegmentOddsOutput {
+                points: U128(self.internal_apply_earnings_multiplier(segment.points.0)),
+                item: segment.item.clone(),
+                outcomes,
+            })
+            .collect();
+        let expected_points = segments
+            .iter()
+            .map(|segment| segment.points.0.safe_mul(Points::from(segment.outcomes)))
+            .fold(0, |total: Points, points| total.safe_add(points))
+            / Points::from(total_outcomes);
+
+        let affordable_spins = match &user {
+            Some(user) if self.spin_wheel_price > 0 => {
+                let points = self.internal_settled_points(user);
+                let allowance = self
+                    .internal_spending_allowance(&account_id)
+                    .unwrap_or(points);
+                u64::try_from(points.min(allowance) / self.spin_wheel_price).unwrap_or(u64::MAX)
+            }
+            _ => 0,
+        };
+
+        SpinSimulationOutput {
+            segments,
+            total_outcomes,
+            expected_points: U128(expected_points),
+            spin_price: U128(self.spin_wheel_price),
+            affordable_spins: U64(affordable_spins),
+            free_spin_available: self
+                .estimate_spin_wheel(account_id, true)
+                .into_result()
+                .is_ok(),
+        }
+    }
+
+    /// Points a daily claim of `account_id` would credit, with claim bonuses,
+    /// the earnings multiplier and today's check-in applied.
+    pub fn simulate_daily_claim(&self, account_id: AccountId) -> ClaimSimulationOutput {
+        let user = self
+            .internal_find_user(&account_id)
+            .unwrap_or_else(|| User::new(self.current_season));
+
+        let claim_points = self.internal_daily_claim_points(&account_id, &user);
+        let check_in = self.internal_next_check_in(&user);
+        let check_in_points = check_in
+            .as_ref()
+            .map(|(_, day)| self.internal_apply_earnings_multiplier(day.points.0))
+            .unwrap_or(0);
+
+        ClaimSimulationOutput {
+            error: self.estimate_daily_claim(account_id).into_result().err(),
+            claim_points: U128(claim_points),
+            claim_streak: self.internal_next_claim_streak(&user, env::block_timestamp_ms()),
+            check_in_points: U128(check_in_points),
+            check_in_streak: check_in.as_ref().map(|(streak, _)| *streak),
+            check_in_bonus: check_in.and_then(|(_, day)| day.bonus),
+            total_points: U128(claim_points.safe_add(check_in_points)),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, ext_contract, near_bindgen, AccountId, Gas, Promise, PromiseResult};
+use serde::Serialize;
+
+use crate::*;
+
+pub const MAX_DISPLAY_NAME_LEN: usize = 64;
+pub const MAX_AVATAR_URL_LEN: usize = 256;
+
+const GAS_FOR_SOCIAL_GET: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_ON_SOCIAL_PROFILE: Gas = Gas(10_000_000_000_000);
+const IPFS_GATEWAY: &str = "https://ipfs.near.social/ipfs/";
+
+#[allow(dead_code)]
+#[ext_contract(ext_social)]
+trait SocialDb {
+    fn get(&self, keys: Vec<String>) -> serde_json::Value;
+}
+
+/// Display data copied from the account's NEAR Social profile.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone)]
+pub struct SocialProfile {
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub synced_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct UserListingOutput {
+    account_id: AccountId,
+    points: U128,
+    xp: U64,
+    season_xp: U64,
+    profile: Option<SocialProfile>,
+    title: Option<TitleId>,
+}
+
+fn truncate(value: &str, max_len: usize) -> String {
+    value.chars().take(max_len).collect()
+}
+
+/// Reads `{account_id: {profile: {name, image}}}` as returned by `get`.
+fn parse_social_profile(account_id: &AccountId, data: &serde_json::Value) -> SocialProfile {
+    let profile = &data[account_id.as_str()]["profile"];
+
+    let name = profile["name"]
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .map(|name| truncate(name, MAX_DISPLAY_NAME_LEN));
+
+    let image = &profile["image"];
+    let avatar_url = image["url"]
+        .as_str()
+        .map(|url| url.to_string())
+        .or_else(|| {
+            image["ipfs_cid"]
+                .as_str()
+                .map(|cid| format!("{}{}", IPFS_GATEWAY, cid))
+        })
+        .filter(|url| url.len() <= MAX_AVATAR_URL_LEN);
+
+    SocialProfile {
+        name,
+        avatar_url,
+        synced_at: U64(env::block_timestamp_ms()),
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_social_db(&mut self, contract_id: AccountId) {
+        self.assert_owner();
+
+        self.social_db = contract_id.clone();
+
+        ArkanaEvent::admin_action(
+            "set_social_db",
+            serde_json::json!({ "contract_id": contract_id }),
+        );
+    }
+
+    /// Copies the caller's NEAR Social name and avatar into the contract so
+    /// they show up in `get_users`. Only the account itself can opt in, the
+    /// cached copy is charged to its storage balance.
+    pub fn sync_social_profile(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        assert!(
+            self.internal_find_user(&account_id).is_some(),
+            "User does not exist"
+        );
+
+        ext_social::ext(self.social_db.clone())
+            .with_static_gas(GAS_FOR_SOCIAL_GET)
+            .get(vec![
+                format!("{}/profile/name", account_id),
+                format!("{}/profile/image/**", account_id),
+            ])
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_SOCIAL_PROFILE)
+                    .on_social_profile(account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_social_profile(&mut self, account_id: AccountId) -> Option<SocialProfile> {
+        let data: serde_json::Value = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                serde_json::from_slice(&value).expect("Invalid social profile")
+            }
+            _ => panic!("Social profile lookup failed"),
+        };
+
+        // Unregistered while the lookup was in flight
+        self.internal_find_user(&account_id)?;
+
+        let profile = parse_social_profile(&account_id, &data);
+
+        let initial_storage_usage = env::storage_usage();
+        self.social_profiles.insert(&account_id, &profile);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        Some(profile)
+    }
+
+    /// Withdraws consent and deletes the cached profile.
+    pub fn remove_social_profile(&mut self) {
+        let account_id = env::predecessor_account_id();
+
+        let initial_storage_usage = env::storage_usage();
+        self.social_profiles.remove(&account_id);
+        if self.internal_find_user(&account_id).is_some() {
+            self.internal_update_storage_usage(&account_id, initial_storage_usage);
+        }
+    }
+
+    // View Functions
+    pub fn get_social_profile(&self, account_id: AccountId) -> Option<SocialProfile> {
+        self.social_profiles.get(&account_id)
+    }
+
+    pub fn get_users(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<UserListingOutput> {
+        paginate(self.user_ids.as_vector().iter(), from_index, limit)
+            .map(|account_id| (User::from(self.users.get(&account_id).unwrap()), account_id))
+            .map(|(user, account_id)| UserListingOutput {
+                points: U128(self.internal_settled_points(&user)),
+                xp: U64(user.xp),
+                season_xp: U64(if user.season == self.current_season {
+                    user.season_xp
+                } else {
+                    0
+                }),
+                profile: self.social_profiles.get(&account_id),
+                title: self.internal_equipped_title(&account_id),
+                account_id,
+            })
+    }
+}
@@ -0,0 +1,312 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+use serde::Serialize;
+
+use crate::*;
+
+pub type StakeId = u64;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Stake {
+    account_id: AccountId,
+    amount: Points,
+    reward: Points,
+    staked_at: Timestamp,
+    unlock_at: Timestamp,
+    // Season `amount` was last carried over into
+    season: SeasonId,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy)]
+pub struct StakingOption {
+    duration_days: u32,
+    yield_bps: u16,
+}
+
+#[derive(Serialize)]
+pub struct StakeOutput {
+    stake_id: U64,
+    amount: U128,
+    reward: U128,
+    staked_at: U64,
+    unlock_at: U64,
+}
+
+#[derive(Serialize)]
+pub struct StakingConfigOutput {
+    options: Vec<StakingOption>,
+    early_unstake_penalty_bps: u16,
+    emission_budget: U128,
+}
+
+impl ArkanaCoreContract {
+    /// Applies the carry-over of the seasons started since the stake was last
+    /// settled to its principal, so staking does not dodge the haircut.
+    fn internal_settle_stake(&mut self, stake: &mut Stake) {
+        let amount = self.internal_carried_over(stake.amount, stake.season);
+        let burned = stake.amount - amount;
+        self.supply.staked = self.supply.staked.safe_sub(burned);
+        self.supply.burned = self.supply.burned.safe_add(burned);
+
+        stake.amount = amount;
+        stake.season = self.current_season;
+    }
+
+    /// Removes every stake of `account_id`, burning the principal and
+    /// returning the reserved yield to the budget. Returns the points burned.
+    pub(crate) fn internal_forfeit_stakes(&mut self, account_id: &AccountId) -> Points {
+        let mut user_stakes = match self.user_stakes.remove(account_id) {
+            Some(user_stakes) => user_stakes,
+            None => return 0,
+        };
+
+        let mut burned = 0;
+        for stake_id in user_stakes.iter() {
+            if let Some(stake) = self.stakes.remove(&stake_id) {
+                self.supply.staked = self.supply.staked.safe_sub(stake.amount);
+                self.supply.burned = self.supply.burned.safe_add(stake.amount);
+                self.staking_emission_budget = self.staking_emission_budget.safe_add(stake.reward);
+                burned = stake.amount.safe_add(burned);
+            }
+        }
+        user_stakes.clear();
+
+        burned
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Adds or replaces the yield paid for locking points for `duration_days`.
+    /// `yield_bps` is paid once over the full lock period.
+    #[payable]
+    pub fn set_staking_option(&mut self, duration_days: u32, yield_bps: u16) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(duration_days > 0, "Duration must be at least one day");
+
+        self.staking_options.insert(
+            &duration_days,
+            &StakingOption {
+                duration_days,
+                yield_bps,
+            },
+        );
+
+        ArkanaEvent::admin_action(
+            "set_staking_option",
+            serde_json::json!({ "duration_days": duration_days, "yield_bps": yield_bps }),
+        );
+    }
+
+    #[payable]
+    pub fn remove_staking_option(&mut self, duration_days: u32) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.staking_options
+            .remove(&duration_days)
+            .expect("Staking option does not exist");
+
+        ArkanaEvent::admin_action(
+            "remove_staking_option",
+            serde_json::json!({ "duration_days": duration_days }),
+        );
+    }
+
+    #[payable]
+    pub fn set_early_unstake_penalty(&mut self, penalty_bps: u16) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        assert!(
+            penalty_bps <= MAX_BPS,
+            "Penalty cannot exceed {} bps",
+            MAX_BPS
+        );
+
+        self.early_unstake_penalty_bps = penalty_bps;
+
+        ArkanaEvent::admin_action(
+            "set_early_unstake_penalty",
+            serde_json::json!({ "penalty_bps": penalty_bps }),
+        );
+    }
+
+    /// Adds points to the budget staking yield is paid from.
+    #[payable]
+    pub fn fund_staking_budget(&mut self, amount: U128) -> U128 {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.staking_emission_budget = self.staking_emission_budget.safe_add(amount.0);
+
+        ArkanaEvent::admin_action(
+            "fund_staking_budget",
+            serde_json::json!({ "amount": amount }),
+        );
+
+        U128(self.staking_emission_budget)
+    }
+
+    pub fn stake_points(&mut self, amount: U128, duration_days: u32) -> U64 {
+        self.assert_not_paused(PauseFeature::Staking);
+
+        let account_id = self.internal_player_id();
+
+        assert!(amount.0 > 0, "Amount must be greater than zero");
+
+        let option = self
+            .staking_options
+            .get(&duration_days)
+            .expect("Staking option does not exist");
+
+        let mut user = self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        if user.points < amount.0 {
+            panic!("Points insufficient");
+        }
+
+        // Yield is reserved up front so the budget can never be overcommitted
+        let reward = apply_bps(amount.0, option.yield_bps as u32);
+        assert!(
+            reward <= self.staking_emission_budget,
+            "Staking emission budget exhausted"
+        );
+        self.staking_emission_budget -= reward;
+
+        user.points -= amount.0;
+        self.supply.staked = self.supply.staked.safe_add(amount.0);
+
+        let current_timestamp = env::block_timestamp_ms();
+        let stake_id = self.last_stake_id + 1;
+        self.last_stake_id = stake_id;
+
+        self.stakes.insert(
+            &stake_id,
+            &Stake {
+                account_id: account_id.clone(),
+                amount: amount.0,
+                reward,
+                staked_at: current_timestamp,
+                unlock_at: current_timestamp.safe_add((duration_days as u64).safe_mul(ONE_DAY)),
+                season: self.current_season,
+            },
+        );
+
+        let mut user_stakes = self.user_stakes.get(&account_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::UserStakes {
+                account_id_hash: env::sha256_array(account_id.as_bytes()),
+            })
+        });
+        user_stakes.insert(&stake_id);
+        self.user_stakes.insert(&account_id, &user_stakes);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_record_history(&account_id, HistoryKind::Stake, -(amount.0 as i128), None);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PointsStaked(vec![PointsStakedData {
+            account_id,
+            stake_id: U64(stake_id),
+            amount,
+            reward: U128(reward),
+            duration_days,
+        }])
+        .emit();
+
+        U64(stake_id)
+    }
+
+    /// Returns the stake to its owner. Unstaking before `unlock_at` forfeits the
+    /// yield and burns `early_unstake_penalty_bps` of the principal.
+    pub fn unstake_points(&mut self, stake_id: U64) -> U128 {
+        self.assert_not_paused(PauseFeature::Staking);
+
+        let account_id = self.internal_player_id();
+
+        let mut stake = self.stakes.get(&stake_id.0).expect("Stake does not exist");
+        assert_eq!(stake.account_id, account_id, "Unauthorized");
+        self.internal_settle_stake(&mut stake);
+
+        let mut user = self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        self.supply.staked = self.supply.staked.safe_sub(stake.amount);
+        user.points = user.points.safe_add(stake.amount);
+
+        let is_early = env::block_timestamp_ms() < stake.unlock_at;
+        let (reward, penalty) = if is_early {
+            self.staking_emission_budget = self.staking_emission_budget.safe_add(stake.reward);
+            let penalty = apply_bps(stake.amount, self.early_unstake_penalty_bps as u32);
+            self.internal_burn_points(&mut user, penalty);
+            (0, penalty)
+        } else {
+            self.internal_issue_points(&account_id, &mut user, stake.reward);
+            (stake.reward, 0)
+        };
+
+        self.stakes.remove(&stake_id.0);
+        let mut user_stakes = self.user_stakes.get(&account_id).unwrap();
+        user_stakes.remove(&stake_id.0);
+        if user_stakes.is_empty() {
+            self.user_stakes.remove(&account_id);
+        } else {
+            self.user_stakes.insert(&account_id, &user_stakes);
+        }
+
+        let received = stake.amount.safe_add(reward).safe_sub(penalty);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_record_history(&account_id, HistoryKind::Unstake, received as i128, None);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::PointsUnstaked(vec![PointsUnstakedData {
+            account_id,
+            stake_id,
+            amount: U128(stake.amount),
+            reward: U128(reward),
+            penalty: U128(penalty),
+        }])
+        .emit();
+
+        U128(received)
+    }
+
+    // View Functions
+    pub fn get_staking_config(&self) -> StakingConfigOutput {
+        StakingConfigOutput {
+            options: self.staking_options.values().collect(),
+            early_unstake_penalty_bps: self.early_unstake_penalty_bps,
+            emission_budget: U128(self.staking_emission_budget),
+        }
+    }
+
+    pub fn get_user_stakes(&self, account_id: AccountId) -> Vec<StakeOutput> {
+        let user_stakes = match self.user_stakes.get(&account_id) {
+            Some(user_stakes) => user_stakes,
+            None => return vec![],
+        };
+
+        user_stakes
+            .iter()
+            .filter_map(|stake_id| {
+                self.stakes.get(&stake_id).map(|stake| StakeOutput {
+                    stake_id: U64(stake_id),
+                    amount: U128(self.internal_carried_over(stake.amount, stake.season)),
+                    reward: U128(stake.reward),
+                    staked_at: U64(stake.staked_at),
+                    unlock_at: U64(stake.unlock_at),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{
+    env, ext_contract, near_bindgen, AccountId, Balance, EpochHeight, Gas, Promise, PromiseResult,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const ONE_NEAR: Balance = 1_000_000_000_000_000_000_000_000;
+
+const GAS_FOR_GET_STAKED_BALANCE: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_ON_STAKED_BALANCE: Gas = Gas(15_000_000_000_000);
+
+#[allow(dead_code)]
+#[ext_contract(ext_staking_pool)]
+trait StakingPool {
+    fn get_account_staked_balance(&self, account_id: AccountId) -> U128;
+}
+
+/// Partner staking pool whose delegators earn `points_per_near` per whole
+/// NEAR staked and epoch.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct StakingPoolConfig {
+    pub pool_id: AccountId,
+    pub points_per_near: U128,
+}
+
+/// The stake seen at the user's last claim. Accrual uses the lower of the
+/// last and the current stake so stake added right before a claim does not
+/// earn for past epochs.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PoolStakeCheckpoint {
+    epoch_height: EpochHeight,
+    staked_balance: Balance,
+}
+
+#[derive(Serialize)]
+pub struct PoolStakeCheckpointOutput {
+    epoch_height: U64,
+    staked_balance: U128,
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_staking_pool(&mut self, config: Option<StakingPoolConfig>) {
+        self.assert_owner();
+
+        self.staking_pool = config.clone();
+
+        ArkanaEvent::admin_action("set_staking_pool", serde_json::json!({ "config": config }));
+    }
+
+    /// Checks the caller's stake in the partner pool and credits the points
+    /// accrued since their last claim. The first claim only records a
+    /// checkpoint.
+    pub fn claim_staking_points(&mut self) -> Promise {
+        self.assert_not_paused(PauseFeature::Claims);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        self.internal_get_user(&account_id);
+
+        let config = self.staking_pool.clone().expect("Staking pool is not set");
+
+        ext_staking_pool::ext(config.pool_id)
+            .with_static_gas(GAS_FOR_GET_STAKED_BALANCE)
+            .get_account_staked_balance(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_STAKED_BALANCE)
+                    .on_staked_balance(account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_staked_balance(&mut self, account_id: AccountId) -> U128 {
+        let staked_balance = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                serde_json::from_slice::<U128>(&value)
+                    .expect("Invalid staked balance")
+                    .0
+            }
+            _ => panic!("Staking pool call failed"),
+        };
+
+        let config = self.staking_pool.clone().expect("Staking pool is not set");
+        let mut user = match self.internal_find_user(&account_id) {
+            Some(_) => self.internal_get_user(&account_id),
+            None => return U128(0),
+        };
+
+        let epoch_height = env::epoch_height();
+        let points = match self.pool_stake_checkpoints.get(&account_id) {
+            Some(checkpoint) => {
+                let epochs = epoch_height.saturating_sub(checkpoint.epoch_height);
+                let eligible_near =
+                    std::cmp::min(checkpoint.staked_balance, staked_balance) / ONE_NEAR;
+                eligible_near
+                    .safe_mul(config.points_per_near.0)
+                    .safe_mul(Points::from(epochs))
+            }
+            None => 0,
+        };
+
+        let initial_storage_usage = env::storage_usage();
+        self.pool_stake_checkpoints.insert(
+            &account_id,
+            &PoolStakeCheckpoint {
+                epoch_height,
+                staked_balance,
+            },
+        );
+
+        if points > 0 {
+            self.internal_issue_points(&account_id, &mut user, points);
+        }
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::StakingPointsClaimed(vec![StakingPointsClaimedData {
+            account_id,
+            staked_balance: U128(staked_balance),
+            points: U128(points),
+        }])
+        .emit();
+
+        U128(points)
+    }
+
+    // View Functions
+    pub fn get_staking_pool(&self) -> Option<StakingPoolConfig> {
+        self.staking_pool.clone()
+    }
+
+    pub fn get_pool_stake_checkpoint(
+        &self,
+        account_id: AccountId,
+    ) -> Option<PoolStakeCheckpointOutput> {
+        self.pool_stake_checkpoints
+            .get(&account_id)
+            .map(|checkpoint| PoolStakeCheckpointOutput {
+                epoch_height: U64(checkpoint.epoch_height),
+                staked_balance: U128(checkpoint.staked_balance),
+            })
+    }
+}
@@ -0,0 +1,381 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Balance, Promise, StorageUsage};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct StorageAccount {
+    pub(crate) deposit: Balance,
+    pub(crate) used_bytes: StorageUsage,
+}
+
+impl StorageAccount {
+    fn used_balance(&self) -> Balance {
+        Balance::from(self.used_bytes) * env::storage_byte_cost()
+    }
+
+    pub(crate) fn available_balance(&self) -> Balance {
+        self.deposit.saturating_sub(self.used_balance())
+    }
+}
+
+#[derive(Serialize)]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+#[derive(Serialize)]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+impl ArkanaCoreContract {
+    /// Measures the bytes a registration takes with the longest possible
+    /// account id, used as the NEP-145 minimum balance.
+    pub(crate) fn internal_measure_registration_storage_usage(&mut self) {
+        let initial_storage_usage = env::storage_usage();
+        let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
+
+        self.users.insert(
+            &tmp_account_id,
+            &VUser::Current(User::new(self.current_season)),
+        );
+        self.user_ids.insert(&tmp_account_id);
+        self.storage_accounts
+            .insert(&tmp_account_id, &StorageAccount::default());
+        self.registration_storage_usage = env::storage_usage() - initial_storage_usage;
+
+        self.users.remove(&tmp_account_id);
+        self.user_ids.remove(&tmp_account_id);
+        self.storage_accounts.remove(&tmp_account_id);
+    }
+
+    /// Attributes the storage added (or freed) since `initial_storage_usage`
+    /// to `account_id` and checks it is still covered by their deposit.
+    pub(crate) fn internal_update_storage_usage(
+        &mut self,
+        account_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        let current_storage_usage = env::storage_usage();
+        let mut storage_account = self.storage_accounts.get(account_id).unwrap_or_default();
+
+        if current_storage_usage >= initial_storage_usage {
+            storage_account.used_bytes += current_storage_usage - initial_storage_usage;
+            assert!(
+                storage_account.used_balance() <= storage_account.deposit,
+                "Insufficient storage balance, top up with storage_deposit"
+            );
+        } else {
+            storage_account.used_bytes = storage_account
+                .used_bytes
+                .saturating_sub(initial_storage_usage - current_storage_usage);
+        }
+
+        self.storage_accounts.insert(account_id, &storage_account);
+    }
+
+    /// Gives the cost of `freed_bytes` back to whoever paid for them. An
+    /// unregistered account is refunded what was held back from its deposit,
+    /// a registered one gets the bytes off its storage usage and anything
+    /// left over goes to the treasury.
+    pub(crate) fn internal_release_storage(
+        &mut self,
+        payer: Option<&AccountId>,
+        freed_bytes: StorageUsage,
+    ) {
+        let mut unclaimed_bytes = freed_bytes;
+
+        if let Some(account_id) = payer {
+            if let Some(retained_bytes) = self.retained_storage.get(account_id) {
+                let released = std::cmp::min(unclaimed_bytes, retained_bytes);
+                unclaimed_bytes -= released;
+
+                if released < retained_bytes {
+                    self.retained_storage
+                        .insert(account_id, &(retained_bytes - released));
+                } else {
+                    self.retained_storage.remove(account_id);
+                }
+
+                let refund = Balance::from(released) * env::storage_byte_cost();
+                if refund > 0 {
+                    self.internal_track_operation(
+                        Promise::new(account_id.clone()).transfer(refund),
+                        PendingOperation::StorageWithdrawal {
+                            account_id: account_id.clone(),
+                            amount: U128(refund),
+                        },
+                    );
+                }
+            } else if let Some(mut storage_account) = self.storage_accounts.get(account_id) {
+                let released = std::cmp::min(unclaimed_bytes, storage_account.used_bytes);
+                unclaimed_bytes -= released;
+
+                storage_account.used_bytes -= released;
+                self.storage_accounts.insert(account_id, &storage_account);
+            }
+        }
+
+        self.treasury_balance = self
+            .treasury_balance
+            .safe_add(Balance::from(unclaimed_bytes) * env::storage_byte_cost());
+    }
+
+    /// Deletes the account and everything indexed under it, then refunds the
+    /// storage deposit minus the cost of what stays behind (raffle tickets,
+    /// history queued for cleanup). That part is refunded as it gets cleaned
+    /// up.
+    /// Without `force` the account must not hold any points or stakes.
+    fn internal_unregister(&mut self, force: bool) -> bool {
+        let account_id = env::predecessor_account_id();
+
+        if self.internal_find_user(&account_id).is_none() {
+            return false;
+        }
+
+        assert!(
+            !self.internal_has_staked_nfts(&account_id),
+            "Unstake NFTs before unregistering"
+        );
+        self.assert_not_in_guild(&account_id);
+        assert!(
+            self.open_escrows.get(&account_id).is_none(),
+            "Settle poll locks, predictions, bids and listings before unregistering"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        let has_stakes = self.user_stakes.get(&account_id).is_some();
+        assert!(
+            force || (user.points == 0 && !has_stakes),
+            "Account still holds points or stakes, use force to forfeit them"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        let storage_account = self.storage_accounts.get(&account_id).unwrap_or_default();
+
+        let points = user.points;
+        self.internal_burn_points(&mut user, points);
+        let burned_points = points + self.internal_forfeit_stakes(&account_id);
+
+        self.users.remove(&account_id);
+        self.user_ids.remove(&account_id);
+        self.storage_accounts.remove(&account_id);
+        self.internal_clear_season_snapshots(&account_id);
+        self.membership_verifications.remove(&account_id);
+        self.social_profiles.remove(&account_id);
+        self.spin_relayers.remove(&account_id);
+        self.pool_stake_checkpoints.remove(&account_id);
+        self.internal_remove_season_standing(&account_id);
+        self.inventories.remove(&account_id);
+        self.wager_usage.remove(&account_id);
+        self.purchased_titles.remove(&account_id);
+        self.equipped_titles.remove(&account_id);
+        self.pass_progress.remove(&account_id);
+        self.personhood_verifications.remove(&account_id);
+        self.tournament_entries.remove(&account_id);
+        self.gift_usage.remove(&account_id);
+        self.internal_clear_expired_limits(&account_id);
+        self.internal_unlink_all_wallets(&account_id);
+        self.former_users.insert(&account_id);
+
+        let freed_bytes = initial_storage_usage.saturating_sub(env::storage_usage());
+        let retained_bytes = storage_account.used_bytes.saturating_sub(freed_bytes);
+        let refund = storage_account
+            .deposit
+            .saturating_sub(Balance::from(retained_bytes) * env::storage_byte_cost());
+
+        if retained_bytes > 0 {
+            let previously_retained = self.retained_storage.get(&account_id).unwrap_or(0);
+            self.retained_storage
+                .insert(&account_id, &(previously_retained + retained_bytes));
+        }
+
+        if refund > 0 {
+            self.internal_track_operation(
+                Promise::new(account_id.clone()).transfer(refund),
+                PendingOperation::StorageWithdrawal {
+                    account_id: account_id.clone(),
+                    amount: U128(refund),
+                },
+            );
+        }
+
+        // Cleaned up history is refunded out of the retained bytes
+        self.internal_clear_history(&account_id);
+
+        ArkanaEvent::AccountUnregistered(vec![AccountUnregisteredData {
+            account_id,
+            burned_points: U128(burned_points),
+            refund: U128(refund),
+        }])
+        .emit();
+
+        true
+    }
+
+    /// Counts an escrow that keeps `account_id` from unregistering.
+    pub(crate) fn internal_open_escrow(&mut self, account_id: &AccountId) {
+        let count = self.open_escrows.get(account_id).unwrap_or(0);
+        self.open_escrows.insert(account_id, &(count + 1));
+    }
+
+    pub(crate) fn internal_close_escrow(&mut self, account_id: &AccountId) {
+        match self.open_escrows.get(account_id).unwrap_or(0) {
+            0 | 1 => {
+                self.open_escrows.remove(account_id);
+            }
+            count => {
+                self.open_escrows.insert(account_id, &(count - 1));
+            }
+        }
+    }
+
+    fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        self.internal_find_user(account_id)?;
+
+        let storage_account = self.storage_accounts.get(account_id).unwrap_or_default();
+        Some(StorageBalance {
+            total: U128(storage_account.deposit),
+            available: U128(storage_account.available_balance()),
+        })
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// NEP-145 deposit. The first deposit for an account registers it and has
+    /// to cover `storage_balance_bounds().min`, which includes the
+    /// registration fee.
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+
+        if self.internal_find_user(&account_id).is_some() {
+            if registration_only {
+                if amount > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+            } else {
+                let mut storage_account =
+                    self.storage_accounts.get(&account_id).unwrap_or_default();
+                storage_account.deposit += amount;
+                self.storage_accounts.insert(&account_id, &storage_account);
+            }
+        } else {
+            self.assert_not_paused(PauseFeature::All);
+
+            let min_balance = self.storage_balance_bounds().min.0;
+            assert!(
+                amount >= min_balance,
+                "The attached deposit is less than the minimum storage balance"
+            );
+
+            let registration_fee = self.registration_fee;
+            if registration_fee > 0 {
+                self.internal_deposit_treasury(
+                    registration_fee,
+                    TreasurySource::RegistrationFee,
+                    &account_id,
+                );
+            }
+
+            let deposit = if registration_only {
+                let refund = amount - min_balance;
+                if refund > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(refund);
+                }
+                min_balance - registration_fee
+            } else {
+                amount - registration_fee
+            };
+
+            self.internal_register_user(&account_id);
+            self.storage_accounts.insert(
+                &account_id,
+                &StorageAccount {
+                    deposit,
+                    used_bytes: self.registration_storage_usage,
+                },
+            );
+        }
+
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+
+        let account_id = env::predecessor_account_id();
+        let mut storage_account = self
+            .storage_accounts
+            .get(&account_id)
+            .expect("The account is not registered");
+
+        let available = storage_account.available_balance();
+        let amount = amount.map(|a| a.0).unwrap_or(available);
+        assert!(
+            amount <= available,
+            "The amount is greater than the available storage balance"
+        );
+
+        if amount > 0 {
+            storage_account.deposit -= amount;
+            self.storage_accounts.insert(&account_id, &storage_account);
+            self.internal_track_operation(
+                Promise::new(account_id.clone()).transfer(amount),
+                PendingOperation::StorageWithdrawal {
+                    account_id: account_id.clone(),
+                    amount: U128(amount),
+                },
+            );
+        }
+
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    /// NEP-145 unregistration, see `unregister_account`.
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+
+        self.internal_unregister(force.unwrap_or(false))
+    }
+
+    /// Removes the caller's account, history, snapshots and stakes and
+    /// refunds their storage deposit. Returns false if the caller is not
+    /// registered. Blacklist entries, the referrer and charity burns are
+    /// kept, and registering again starts without `INIT_POINT`.
+    #[payable]
+    pub fn unregister_account(&mut self, force: bool) -> bool {
+        assert_one_yocto();
+
+        self.internal_unregister(force)
+    }
+
+    // View Functions
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(
+                Balance::from(self.registration_storage_usage) * env::storage_byte_cost()
+                    + self.registration_fee,
+            ),
+            max: None,
+        }
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(&account_id)
+    }
+}
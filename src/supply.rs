@@ -0,0 +1,143 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::near_bindgen;
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct SupplyStats {
+    pub issued: Points,
+    pub burned: Points,
+    pub spent_on_tickets: Points,
+    pub spent_on_spins: Points,
+    pub spent_on_lootboxes: Points,
+    // Stakes and entry prices of mini-games
+    pub spent_on_games: Points,
+    pub staked: Points,
+    // Locked while on other chains
+    pub bridged: Points,
+}
+
+#[derive(Serialize)]
+pub struct SupplyStatsOutput {
+    issued: U128,
+    burned: U128,
+    spent_on_tickets: U128,
+    spent_on_spins: U128,
+    spent_on_lootboxes: U128,
+    spent_on_games: U128,
+    staked: U128,
+    bridged: U128,
+    outstanding: U128,
+}
+
+pub enum SpendKind {
+    Ticket,
+    Spin,
+    Lootbox,
+    Game,
+}
+
+impl ArkanaCoreContract {
+    /// Credits newly created points to a user.
+    pub(crate) fn internal_issue_points(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        amount: Points,
+    ) {
+        let level = user.level();
+
+        // XP stays u64, it only needs to outgrow the level curve
+        let xp = u64::try_from(amount).unwrap_or(u64::MAX);
+        user.points = user.points.safe_add(amount);
+        user.xp = user.xp.saturating_add(xp);
+        user.season_xp = user.season_xp.saturating_add(xp);
+
+        self.supply.issued = self.supply.issued.safe_add(amount);
+        self.internal_update_season_standings(account_id, user.season_xp);
+        self.internal_reach_milestones(account_id, user);
+        self.internal_add_tournament_score(account_id, amount);
+
+        if user.level() > level {
+            ArkanaEvent::LevelUp(vec![LevelUpData {
+                account_id: account_id.clone(),
+                level: user.level(),
+            }])
+            .emit();
+            self.internal_notify_partners(
+                HookEvent::LevelUp,
+                serde_json::json!({ "account_id": account_id, "level": user.level() }),
+            );
+        }
+    }
+
+    /// Removes points from circulation without anything in exchange.
+    pub(crate) fn internal_burn_points(&mut self, user: &mut User, amount: Points) {
+        user.points = user
+            .points
+            .checked_sub(amount)
+            .expect("Points insufficient");
+
+        self.supply.burned = self.supply.burned.safe_add(amount);
+    }
+
+    pub(crate) fn internal_spend_points(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        amount: Points,
+        kind: SpendKind,
+    ) {
+        if kind.is_gambling() {
+            self.internal_track_gambling_spend(account_id, amount);
+        }
+
+        user.points = user
+            .points
+            .checked_sub(amount)
+            .expect("Points insufficient");
+
+        match kind {
+            SpendKind::Ticket => {
+                self.supply.spent_on_tickets = self.supply.spent_on_tickets.safe_add(amount)
+            }
+            SpendKind::Spin => {
+                self.supply.spent_on_spins = self.supply.spent_on_spins.safe_add(amount)
+            }
+            SpendKind::Lootbox => {
+                self.supply.spent_on_lootboxes = self.supply.spent_on_lootboxes.safe_add(amount)
+            }
+            SpendKind::Game => {
+                self.supply.spent_on_games = self.supply.spent_on_games.safe_add(amount)
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn get_supply_stats(&self) -> SupplyStatsOutput {
+        let supply = &self.supply;
+
+        SupplyStatsOutput {
+            issued: U128(supply.issued),
+            burned: U128(supply.burned),
+            spent_on_tickets: U128(supply.spent_on_tickets),
+            spent_on_spins: U128(supply.spent_on_spins),
+            spent_on_lootboxes: U128(supply.spent_on_lootboxes),
+            spent_on_games: U128(supply.spent_on_games),
+            staked: U128(supply.staked),
+            bridged: U128(supply.bridged),
+            outstanding: U128(
+                supply.issued
+                    - supply.burned
+                    - supply.spent_on_tickets
+                    - supply.spent_on_spins
+                    - supply.spent_on_lootboxes
+                    - supply.spent_on_games,
+            ),
+        }
+    }
+}
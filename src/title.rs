@@ -0,0 +1,188 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type TitleId = String;
+
+pub const MAX_TITLE_ID_LEN: usize = 32;
+pub const MAX_TITLES: u64 = 64;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TitleUnlock {
+    // Available to everyone who unlocked the achievement
+    Achievement { achievement: Achievement },
+    // Bought once with `buy_title`, the points are burned
+    Purchase { price: U128 },
+}
+
+/// Display flair shown next to an account, purely cosmetic.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct TitleDefinition {
+    pub name: String,
+    pub media: Option<String>,
+    pub unlock: TitleUnlock,
+}
+
+#[derive(Serialize)]
+pub struct TitleOutput {
+    title_id: TitleId,
+    #[serde(flatten)]
+    title: TitleDefinition,
+    owned: Option<bool>,
+}
+
+impl ArkanaCoreContract {
+    fn internal_owns_title(&self, account_id: &AccountId, user: &User, title_id: &TitleId) -> bool {
+        match self.titles.get(title_id).map(|title| title.unlock) {
+            Some(TitleUnlock::Achievement { achievement }) => user.has_achievement(achievement),
+            Some(TitleUnlock::Purchase { .. }) => self
+                .purchased_titles
+                .get(account_id)
+                .unwrap_or_default()
+                .contains(title_id),
+            None => false,
+        }
+    }
+
+    /// The title shown in listings, hidden once its definition is removed.
+    pub(crate) fn internal_equipped_title(&self, account_id: &AccountId) -> Option<TitleId> {
+        self.equipped_titles
+            .get(account_id)
+            .filter(|title_id| self.titles.get(title_id).is_some())
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Defines, updates or (with `None`) removes a title. Purchases of a
+    /// removed title are kept in case it is defined again.
+    #[payable]
+    pub fn set_title(&mut self, title_id: TitleId, title: Option<TitleDefinition>) {
+        self.assert_owner();
+
+        assert!(
+            !title_id.is_empty() && title_id.len() <= MAX_TITLE_ID_LEN,
+            "Title id must be between 1 and {} bytes",
+            MAX_TITLE_ID_LEN
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        match &title {
+            Some(title) => {
+                assert!(
+                    self.titles.get(&title_id).is_some() || self.titles.len() < MAX_TITLES,
+                    "Cannot define more than {} titles",
+                    MAX_TITLES
+                );
+                self.titles.insert(&title_id, title);
+            }
+            None => {
+                self.titles.remove(&title_id);
+            }
+        }
+
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        ArkanaEvent::admin_action(
+            "set_title",
+            serde_json::json!({ "title_id": title_id, "title": title }),
+        );
+    }
+
+    pub fn buy_title(&mut self, title_id: TitleId) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let title = self.titles.get(&title_id).expect("Title does not exist");
+        let price = match title.unlock {
+            TitleUnlock::Purchase { price } => price.0,
+            TitleUnlock::Achievement { .. } => panic!("Title is not for sale"),
+        };
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        assert!(
+            !self.internal_owns_title(&account_id, &user, &title_id),
+            "Title already owned"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_burn_points(&mut user, price);
+
+        let mut purchased = self.purchased_titles.get(&account_id).unwrap_or_default();
+        purchased.push(title_id.clone());
+        self.purchased_titles.insert(&account_id, &purchased);
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::TitleBought(vec![TitleBoughtData {
+            account_id,
+            title_id,
+            price: U128(price),
+        }])
+        .emit();
+    }
+
+    /// Shows an owned title next to the caller's account, `None` hides it.
+    pub fn equip_title(&mut self, title_id: Option<TitleId>) {
+        let account_id = self.internal_player_id();
+        let user = self.internal_get_user(&account_id);
+
+        let initial_storage_usage = env::storage_usage();
+
+        match &title_id {
+            Some(title_id) => {
+                assert!(
+                    self.internal_owns_title(&account_id, &user, title_id),
+                    "Title is not owned"
+                );
+                self.equipped_titles.insert(&account_id, title_id);
+            }
+            None => {
+                self.equipped_titles.remove(&account_id);
+            }
+        }
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::TitleEquipped(vec![TitleEquippedData {
+            account_id,
+            title_id,
+        }])
+        .emit();
+    }
+
+    // View Functions
+
+    /// Every defined title, with whether `account_id` owns it when given.
+    pub fn get_titles(&self, account_id: Option<AccountId>) -> Vec<TitleOutput> {
+        let user = account_id
+            .as_ref()
+            .and_then(|account_id| self.internal_find_user(account_id));
+
+        self.titles
+            .iter()
+            .map(|(title_id, title)| TitleOutput {
+                owned: account_id.as_ref().map(|account_id| {
+                    user.as_ref()
+                        .is_some_and(|user| self.internal_owns_title(account_id, user, &title_id))
+                }),
+                title_id,
+                title,
+            })
+            .collect()
+    }
+
+    pub fn get_equipped_title(&self, account_id: AccountId) -> Option<TitleId> {
+        self.internal_equipped_title(&account_id)
+    }
+}
@@ -0,0 +1,402 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub type TournamentId = u64;
+
+pub const MAX_TOURNAMENT_STAGES: usize = 8;
+pub const MAX_TOURNAMENT_PLAYERS: usize = 256;
+
+/// A scoring window opening when the previous stage is advanced. Points
+/// earned in the window are the score, the top `advancing` players go on to
+/// the next stage, or are the finalists after the last one.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct TournamentStage {
+    pub duration_ms: U64,
+    pub advancing: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TournamentStatus {
+    Registration,
+    Stage { index: u8 },
+    Finished,
+}
+
+/// Entry fees make up the pool, finalists get `prize_shares_bps` of it by
+/// rank and whatever is left is burned.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Tournament {
+    title: String,
+    entry_fee: Points,
+    registration_ends_at: Timestamp,
+    stages: Vec<TournamentStage>,
+    prize_shares_bps: Vec<u16>,
+    pool: Points,
+    status: TournamentStatus,
+    stage_ends_at: Timestamp,
+    // Players still in, ranked after every stage
+    players: Vec<(AccountId, Points)>,
+}
+
+#[derive(Serialize)]
+pub struct TournamentStandingOutput {
+    rank: u32,
+    account_id: AccountId,
+    score: U128,
+}
+
+#[derive(Serialize)]
+pub struct TournamentOutput {
+    tournament_id: U64,
+    title: String,
+    entry_fee: U128,
+    registration_ends_at: U64,
+    stages: Vec<TournamentStage>,
+    prize_shares_bps: Vec<u16>,
+    pool: U128,
+    status: TournamentStatus,
+    stage_ends_at: U64,
+    standings: Vec<TournamentStandingOutput>,
+}
+
+impl Tournament {
+    fn to_output(&self, tournament_id: TournamentId) -> TournamentOutput {
+        let mut players = self.players.clone();
+        players.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        TournamentOutput {
+            tournament_id: U64(tournament_id),
+            title: self.title.clone(),
+            entry_fee: U128(self.entry_fee),
+            registration_ends_at: U64(self.registration_ends_at),
+            stages: self.stages.clone(),
+            prize_shares_bps: self.prize_shares_bps.clone(),
+            pool: U128(self.pool),
+            status: self.status,
+            stage_ends_at: U64(self.stage_ends_at),
+            standings: players
+                .into_iter()
+                .enumerate()
+                .map(|(index, (account_id, score))| TournamentStandingOutput {
+                    rank: index as u32 + 1,
+                    account_id,
+                    score: U128(score),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ArkanaCoreContract {
+    /// Adds points earned by `account_id` to their score in the stage they
+    /// are playing, if one is running.
+    pub(crate) fn internal_add_tournament_score(&mut self, account_id: &AccountId, amount: Points) {
+        let tournament_id = match self.tournament_entries.get(account_id) {
+            Some(tournament_id) => tournament_id,
+            None => return,
+        };
+        let mut tournament = self.tournaments.get(&tournament_id).unwrap();
+
+        if !matches!(tournament.status, TournamentStatus::Stage { .. })
+            || env::block_timestamp_ms() >= tournament.stage_ends_at
+        {
+            return;
+        }
+
+        if let Some((_, score)) = tournament
+            .players
+            .iter_mut()
+            .find(|(player_id, _)| player_id == account_id)
+        {
+            *score = score.safe_add(amount);
+            self.tournaments.insert(&tournament_id, &tournament);
+        }
+    }
+
+    /// Frees players from the tournament so they can enter another one.
+    fn internal_release_tournament_players(
+        &mut self,
+        tournament_id: TournamentId,
+        players: &[(AccountId, Points)],
+    ) {
+        for (account_id, _) in players {
+            // They may have unregistered and entered another one since
+            if self.tournament_entries.get(account_id) == Some(tournament_id) {
+                self.tournament_entries.remove(account_id);
+            }
+        }
+    }
+
+    /// Pays the finalists their share of the pool by rank. Shares of accounts
+    /// that have unregistered and unassigned shares are burned.
+    fn internal_pay_tournament_prizes(
+        &mut self,
+        tournament_id: TournamentId,
+        tournament: &Tournament,
+    ) {
+        let mut paid = 0;
+
+        for (index, (account_id, _)) in tournament.players.iter().enumerate() {
+            let prize = tournament
+                .prize_shares_bps
+                .get(index)
+                .map(|share_bps| apply_bps(tournament.pool, *share_bps as u32))
+                .unwrap_or(0);
+            if prize == 0 || self.internal_find_user(account_id).is_none() {
+                continue;
+            }
+
+            let mut user = self.internal_get_user(account_id);
+            user.points = user.points.safe_add(prize);
+            self.supply.issued = self.supply.issued.safe_add(prize);
+            self.internal_save_user(account_id, &user);
+            self.internal_record_history(
+                account_id,
+                HistoryKind::TournamentPrize,
+                prize as i128,
+                Some(format!("Tournament {} rank {}", tournament_id, index + 1)),
+            );
+            paid += prize;
+
+            ArkanaEvent::TournamentPrizePaid(vec![TournamentPrizePaidData {
+                tournament_id: U64(tournament_id),
+                rank: index as u32 + 1,
+                account_id: account_id.clone(),
+                prize: U128(prize),
+            }])
+            .emit();
+        }
+
+        self.supply.burned = self.supply.burned.safe_add(tournament.pool - paid);
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the tournament's storage, the rest is
+    /// refunded.
+    #[payable]
+    pub fn create_tournament(
+        &mut self,
+        title: String,
+        entry_fee: U128,
+        registration_ends_at: U64,
+        stages: Vec<TournamentStage>,
+        prize_shares_bps: Vec<u16>,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            !stages.is_empty() && stages.len() <= MAX_TOURNAMENT_STAGES,
+            "Tournament must have between 1 and {} stages",
+            MAX_TOURNAMENT_STAGES
+        );
+        assert!(
+            stages
+                .iter()
+                .all(|stage| stage.duration_ms.0 > 0 && stage.advancing > 0),
+            "Stages must last and advance at least one player"
+        );
+        assert!(
+            stages
+                .windows(2)
+                .all(|pair| pair[1].advancing <= pair[0].advancing),
+            "Stages cannot advance more players than the previous one"
+        );
+        assert!(
+            prize_shares_bps.len() <= stages.last().unwrap().advancing as usize,
+            "More prizes than finalists"
+        );
+        assert!(
+            prize_shares_bps
+                .iter()
+                .map(|share| *share as u32)
+                .sum::<u32>()
+                <= MAX_BPS as u32,
+            "Prize shares cannot exceed {} bps",
+            MAX_BPS
+        );
+        assert!(
+            registration_ends_at.0 > env::block_timestamp_ms(),
+            "Registration must end in the future"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let tournament_id = self.last_tournament_id + 1;
+        self.last_tournament_id = tournament_id;
+
+        self.tournaments.insert(
+            &tournament_id,
+            &Tournament {
+                title: title.clone(),
+                entry_fee: entry_fee.0,
+                registration_ends_at: registration_ends_at.0,
+                stages: stages.clone(),
+                prize_shares_bps: prize_shares_bps.clone(),
+                pool: 0,
+                status: TournamentStatus::Registration,
+                stage_ends_at: registration_ends_at.0,
+                players: vec![],
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_tournament",
+            serde_json::json!({
+                "tournament_id": U64(tournament_id),
+                "title": title,
+                "entry_fee": entry_fee,
+                "registration_ends_at": registration_ends_at,
+                "stages": stages,
+                "prize_shares_bps": prize_shares_bps,
+            }),
+        );
+
+        U64(tournament_id)
+    }
+
+    /// Pays the entry fee into the pool. A player can be in one tournament
+    /// at a time.
+    pub fn enter_tournament(&mut self, tournament_id: U64) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut tournament = self
+            .tournaments
+            .get(&tournament_id.0)
+            .expect("Tournament does not exist");
+        assert!(
+            tournament.status == TournamentStatus::Registration
+                && env::block_timestamp_ms() < tournament.registration_ends_at,
+            "Tournament registration is closed"
+        );
+        assert!(
+            tournament.players.len() < MAX_TOURNAMENT_PLAYERS,
+            "Tournament is full"
+        );
+        assert!(
+            self.tournament_entries.get(&account_id).is_none(),
+            "Already in a tournament"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        if tournament.entry_fee > 0 {
+            self.internal_spend_points(
+                &account_id,
+                &mut user,
+                tournament.entry_fee,
+                SpendKind::Game,
+            );
+            tournament.pool = tournament.pool.safe_add(tournament.entry_fee);
+        }
+        tournament.players.push((account_id.clone(), 0));
+
+        self.tournaments.insert(&tournament_id.0, &tournament);
+        self.tournament_entries
+            .insert(&account_id, &tournament_id.0);
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::TournamentEntered(vec![TournamentEnteredData {
+            tournament_id,
+            account_id,
+            entry_fee: U128(tournament.entry_fee),
+        }])
+        .emit();
+    }
+
+    /// Moves the tournament on once registration or the running stage is
+    /// over: starts the next stage with the top players, or pays the
+    /// finalists after the last one. Callable by anyone.
+    pub fn advance_tournament(&mut self, tournament_id: U64) -> TournamentStatus {
+        let mut tournament = self
+            .tournaments
+            .get(&tournament_id.0)
+            .expect("Tournament does not exist");
+
+        let current_timestamp = env::block_timestamp_ms();
+        assert!(
+            tournament.status != TournamentStatus::Finished,
+            "Tournament has finished"
+        );
+        assert!(
+            current_timestamp >= tournament.stage_ends_at,
+            "Tournament stage has not ended"
+        );
+
+        let next_index = match tournament.status {
+            TournamentStatus::Registration => 0,
+            TournamentStatus::Stage { index } => {
+                // Stable, so ties keep the previous stage's order
+                tournament.players.sort_by(|(_, a), (_, b)| b.cmp(a));
+                let advancing = tournament.stages[index as usize].advancing as usize;
+                if tournament.players.len() > advancing {
+                    let eliminated = tournament.players.split_off(advancing);
+                    self.internal_release_tournament_players(tournament_id.0, &eliminated);
+                }
+                index as usize + 1
+            }
+            TournamentStatus::Finished => unreachable!(),
+        };
+
+        if next_index < tournament.stages.len() && !tournament.players.is_empty() {
+            for (_, score) in tournament.players.iter_mut() {
+                *score = 0;
+            }
+            tournament.status = TournamentStatus::Stage {
+                index: next_index as u8,
+            };
+            tournament.stage_ends_at =
+                current_timestamp.safe_add(tournament.stages[next_index].duration_ms.0);
+        } else {
+            tournament.status = TournamentStatus::Finished;
+            self.internal_pay_tournament_prizes(tournament_id.0, &tournament);
+            let finalists = tournament.players.clone();
+            self.internal_release_tournament_players(tournament_id.0, &finalists);
+        }
+
+        self.tournaments.insert(&tournament_id.0, &tournament);
+
+        ArkanaEvent::TournamentAdvanced(vec![TournamentAdvancedData {
+            tournament_id,
+            status: tournament.status,
+            players: tournament.players.len() as u32,
+        }])
+        .emit();
+
+        tournament.status
+    }
+
+    // View Functions
+    pub fn get_tournament(&self, tournament_id: U64) -> Option<TournamentOutput> {
+        self.tournaments
+            .get(&tournament_id.0)
+            .map(|tournament| tournament.to_output(tournament_id.0))
+    }
+
+    pub fn get_tournaments(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Page<TournamentOutput> {
+        paginate(self.tournaments.iter(), from_index, limit)
+            .map(|(tournament_id, tournament)| tournament.to_output(tournament_id))
+    }
+
+    pub fn get_player_tournament(&self, account_id: AccountId) -> Option<U64> {
+        self.tournament_entries.get(&account_id).map(U64)
+    }
+}
@@ -0,0 +1,233 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Balance, Promise};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TreasurySource {
+    RegistrationFee,
+    Spin,
+    Ticket,
+    Lootbox,
+    BattlePass,
+}
+
+#[derive(Serialize)]
+pub struct TreasuryOutput {
+    balance: U128,
+    treasurers: Vec<AccountId>,
+    registration_fee: U128,
+    spin_wheel_price_near: U128,
+    spin_wheel_price_usd: U128,
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn internal_deposit_treasury(
+        &mut self,
+        amount: Balance,
+        source: TreasurySource,
+        account_id: &AccountId,
+    ) {
+        let protocol_fee = self.internal_take_protocol_fee(amount);
+        self.treasury_balance = self.treasury_balance.safe_add(amount - protocol_fee);
+
+        ArkanaEvent::TreasuryDeposit(vec![TreasuryDepositData {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+            source,
+            protocol_fee: U128(protocol_fee),
+        }])
+        .emit();
+    }
+
+    /// Takes `price` out of the attached deposit into the treasury and refunds
+    /// the rest to the predecessor.
+    pub(crate) fn internal_charge_near(&mut self, price: Balance, source: TreasurySource) {
+        let attached_deposit = env::attached_deposit();
+        assert!(attached_deposit >= price, "Must attach {} yoctoNEAR", price);
+
+        let predecessor_id = env::predecessor_account_id();
+        self.internal_deposit_treasury(price, source, &predecessor_id);
+
+        let refund = attached_deposit - price;
+        if refund > 0 {
+            Promise::new(predecessor_id).transfer(refund);
+        }
+    }
+
+    fn internal_spin_price_near(&self) -> Balance {
+        if self.spin_wheel_price_usd > 0 {
+            return self.internal_usd_cents_to_near(self.spin_wheel_price_usd);
+        }
+
+        assert!(
+            self.spin_wheel_price_near > 0,
+            "Spin wheel is not sold for NEAR"
+        );
+        self.spin_wheel_price_near
+    }
+
+    fn internal_ticket_price_near(&self, reward_id: RewardId) -> Balance {
+        if let Some(price_cents) = self.reward_usd_prices.get(&reward_id) {
+            return self.internal_usd_cents_to_near(price_cents);
+        }
+
+        self.reward_near_prices
+            .get(&reward_id)
+            .expect("Reward is not sold for NEAR")
+    }
+
+    pub(crate) fn internal_withdraw_treasury(
+        &mut self,
+        amount: Balance,
+        receiver_id: AccountId,
+    ) -> Promise {
+        assert!(amount > 0, "Amount must be greater than zero");
+        assert!(
+            amount <= self.treasury_balance,
+            "Treasury balance insufficient"
+        );
+
+        self.treasury_balance -= amount;
+
+        self.internal_track_operation(
+            Promise::new(receiver_id.clone()).transfer(amount),
+            PendingOperation::TreasuryWithdrawal {
+                receiver_id,
+                amount: U128(amount),
+            },
+        )
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    #[payable]
+    pub fn add_treasurer(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.treasurers.insert(&account_id);
+
+        ArkanaEvent::admin_action(
+            "add_treasurer",
+            serde_json::json!({ "account_id": account_id }),
+        );
+    }
+
+    #[payable]
+    pub fn remove_treasurer(&mut self, account_id: AccountId) {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.treasurers.remove(&account_id);
+
+        ArkanaEvent::admin_action(
+            "remove_treasurer",
+            serde_json::json!({ "account_id": account_id }),
+        );
+    }
+
+    pub fn set_reward_near_price(&mut self, reward_id: U64, price: Option<U128>) {
+        self.assert_owner();
+
+        self.assert_reward_exists(reward_id.0);
+
+        match price {
+            Some(price) if price.0 > 0 => {
+                self.reward_near_prices.insert(&reward_id.0, &price.0);
+            }
+            _ => {
+                self.reward_near_prices.remove(&reward_id.0);
+            }
+        }
+
+        ArkanaEvent::admin_action(
+            "set_reward_near_price",
+            serde_json::json!({ "reward_id": reward_id, "price": price }),
+        );
+    }
+
+    #[payable]
+    pub fn play_spin_wheel_with_near(&mut self) -> U128 {
+        self.assert_not_paused(PauseFeature::Spins);
+
+        let predecessor_id = self.internal_player_id();
+        self.assert_not_blacklisted(&predecessor_id);
+        self.assert_not_self_excluded(&predecessor_id);
+
+        let price = self.internal_spin_price_near();
+
+        let mut user = self.internal_get_user(&predecessor_id);
+        self.internal_consume_rate_limit(&mut user);
+
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_charge_near(price, TreasurySource::Spin);
+
+        let segment = self.internal_spin_wheel(&mut user);
+        let result = self.internal_award_spin(&predecessor_id, &mut user, &segment);
+
+        self.internal_save_user(&predecessor_id, &user);
+        self.internal_update_storage_usage(&predecessor_id, initial_storage_usage);
+
+        U128(result)
+    }
+
+    #[payable]
+    pub fn buy_ticket_with_near(&mut self, reward_id: U64, amount: U64) -> (U64, U64) {
+        self.assert_not_paused(PauseFeature::Tickets);
+
+        let predecessor_id = self.internal_player_id();
+        self.assert_not_blacklisted(&predecessor_id);
+
+        let price = self.internal_ticket_price_near(reward_id.0);
+
+        assert_valid_ticket_amount(amount.0);
+        self.internal_get_open_reward(reward_id.0);
+
+        // Only registered users can hold tickets
+        let mut user = self.internal_get_user(&predecessor_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_charge_near(price.safe_mul(amount.0 as Balance), TreasurySource::Ticket);
+
+        self.internal_add_tickets(reward_id.0, &predecessor_id, amount.0);
+        self.internal_record_raffle_entries(&predecessor_id, &mut user, amount.0);
+        self.internal_save_user(&predecessor_id, &user);
+        self.internal_update_storage_usage(&predecessor_id, initial_storage_usage);
+
+        (reward_id, amount)
+    }
+
+    /// Sends NEAR out of the treasury. Once guardians are set up withdrawals
+    /// go through a guardian proposal instead.
+    #[payable]
+    pub fn withdraw_treasury(&mut self, amount: U128, receiver_id: AccountId) -> Promise {
+        assert_one_yocto();
+
+        assert!(
+            self.treasurers.contains(&env::predecessor_account_id()),
+            "Unauthorized"
+        );
+        assert!(self.guardians.is_empty(), "Requires guardian approval");
+
+        self.internal_withdraw_treasury(amount.0, receiver_id)
+    }
+
+    // View Functions
+    pub fn get_treasury(&self) -> TreasuryOutput {
+        TreasuryOutput {
+            balance: U128(self.treasury_balance),
+            treasurers: self.treasurers.to_vec(),
+            registration_fee: U128(self.registration_fee),
+            spin_wheel_price_near: U128(self.spin_wheel_price_near),
+            spin_wheel_price_usd: U128(self.spin_wheel_price_usd),
+        }
+    }
+}
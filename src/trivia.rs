@@ -0,0 +1,290 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::{env, near_bindgen, AccountId, CryptoHash};
+use serde::Serialize;
+
+use crate::*;
+
+pub type TriviaId = u64;
+
+pub const MAX_TRIVIA_QUESTION_LEN: usize = 280;
+pub const MAX_TRIVIA_ANSWER_LEN: usize = 64;
+
+/// A question answered in two rounds: guesses are committed as hashes
+/// until `commit_ends_at`, then revealed once the owner has revealed the
+/// answer, until `reveal_ends_at`. Correct revealers split `pool`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Trivia {
+    question: String,
+    // sha256 of "{answer}:{salt}"
+    answer_hash: CryptoHash,
+    answer: Option<String>,
+    pool: Points,
+    commit_ends_at: Timestamp,
+    reveal_ends_at: Timestamp,
+    guesses: u32,
+    correct_guesses: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TriviaGuess {
+    // sha256 of "{trivia_id}:{account_id}:{answer}:{salt}"
+    guess_hash: CryptoHash,
+    correct: bool,
+}
+
+#[derive(Serialize)]
+pub struct TriviaOutput {
+    trivia_id: U64,
+    question: String,
+    answer: Option<String>,
+    pool: U128,
+    commit_ends_at: U64,
+    reveal_ends_at: U64,
+    guesses: u32,
+    correct_guesses: u32,
+}
+
+/// Answers are compared ignoring case and surrounding whitespace.
+fn normalize_answer(answer: &str) -> String {
+    answer.trim().to_lowercase()
+}
+
+fn to_crypto_hash(hash: &Base64VecU8) -> CryptoHash {
+    hash.0.as_slice().try_into().expect("Hash must be 32 bytes")
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// The attached deposit covers the question's storage, the rest is
+    /// refunded. The pool is issued to the winners when they claim.
+    #[payable]
+    pub fn create_trivia(
+        &mut self,
+        question: String,
+        answer_hash: Base64VecU8,
+        pool: U128,
+        commit_ends_at: U64,
+        reveal_ends_at: U64,
+    ) -> U64 {
+        self.assert_owner();
+
+        assert!(
+            !question.is_empty() && question.len() <= MAX_TRIVIA_QUESTION_LEN,
+            "Question must be between 1 and {} bytes",
+            MAX_TRIVIA_QUESTION_LEN
+        );
+        assert!(pool.0 > 0, "Pool must be greater than zero");
+        assert!(
+            env::block_timestamp_ms() < commit_ends_at.0 && commit_ends_at.0 < reveal_ends_at.0,
+            "Reveal phase must follow the commit phase"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let trivia_id = self.last_trivia_id + 1;
+        self.last_trivia_id = trivia_id;
+
+        self.trivia.insert(
+            &trivia_id,
+            &Trivia {
+                question: question.clone(),
+                answer_hash: to_crypto_hash(&answer_hash),
+                answer: None,
+                pool: pool.0,
+                commit_ends_at: commit_ends_at.0,
+                reveal_ends_at: reveal_ends_at.0,
+                guesses: 0,
+                correct_guesses: 0,
+            },
+        );
+
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        ArkanaEvent::admin_action(
+            "create_trivia",
+            serde_json::json!({
+                "trivia_id": U64(trivia_id),
+                "question": question,
+                "pool": pool,
+                "commit_ends_at": commit_ends_at,
+                "reveal_ends_at": reveal_ends_at,
+            }),
+        );
+
+        U64(trivia_id)
+    }
+
+    /// Publishes the answer committed in `create_trivia`, opening reveals.
+    pub fn reveal_trivia_answer(&mut self, trivia_id: U64, answer: String, salt: String) {
+        self.assert_owner();
+
+        let mut trivia = self
+            .trivia
+            .get(&trivia_id.0)
+            .expect("Trivia does not exist");
+        assert!(trivia.answer.is_none(), "Answer already revealed");
+
+        let current_timestamp = env::block_timestamp_ms();
+        assert!(
+            current_timestamp >= trivia.commit_ends_at && current_timestamp < trivia.reveal_ends_at,
+            "Not in the reveal phase"
+        );
+        assert!(
+            env::sha256_array(format!("{}:{}", answer, salt).as_bytes()) == trivia.answer_hash,
+            "Answer does not match its hash"
+        );
+
+        trivia.answer = Some(answer.clone());
+        self.trivia.insert(&trivia_id.0, &trivia);
+
+        ArkanaEvent::admin_action(
+            "reveal_trivia_answer",
+            serde_json::json!({ "trivia_id": trivia_id, "answer": answer }),
+        );
+    }
+
+    /// Commits a guess, see `TriviaGuess::guess_hash` for the format. Can
+    /// be replaced until the commit phase ends.
+    pub fn submit_trivia_guess(&mut self, trivia_id: U64, guess_hash: Base64VecU8) {
+        self.assert_not_paused(PauseFeature::All);
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+
+        let mut trivia = self
+            .trivia
+            .get(&trivia_id.0)
+            .expect("Trivia does not exist");
+        assert!(
+            env::block_timestamp_ms() < trivia.commit_ends_at,
+            "Commit phase has ended"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        let key = (trivia_id.0, account_id.clone());
+        if self.trivia_guesses.get(&key).is_none() {
+            trivia.guesses += 1;
+            self.trivia.insert(&trivia_id.0, &trivia);
+        }
+        self.trivia_guesses.insert(
+            &key,
+            &TriviaGuess {
+                guess_hash: to_crypto_hash(&guess_hash),
+                correct: false,
+            },
+        );
+
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+    }
+
+    /// Opens the caller's committed guess and returns whether it is correct.
+    pub fn reveal_trivia_guess(&mut self, trivia_id: U64, answer: String, salt: String) -> bool {
+        let account_id = self.internal_player_id();
+
+        let mut trivia = self
+            .trivia
+            .get(&trivia_id.0)
+            .expect("Trivia does not exist");
+        let correct_answer = trivia.answer.clone().expect("Answer is not revealed yet");
+        assert!(
+            env::block_timestamp_ms() < trivia.reveal_ends_at,
+            "Reveal phase has ended"
+        );
+        assert!(
+            answer.len() <= MAX_TRIVIA_ANSWER_LEN,
+            "Answer cannot exceed {} bytes",
+            MAX_TRIVIA_ANSWER_LEN
+        );
+
+        let key = (trivia_id.0, account_id.clone());
+        let mut guess = self.trivia_guesses.get(&key).expect("No guess submitted");
+        assert!(!guess.correct, "Guess already revealed");
+
+        let guess_hash = env::sha256_array(
+            format!("{}:{}:{}:{}", trivia_id.0, account_id, answer, salt).as_bytes(),
+        );
+        assert!(
+            guess_hash == guess.guess_hash,
+            "Guess does not match its hash"
+        );
+
+        let correct = normalize_answer(&answer) == normalize_answer(&correct_answer);
+        if correct {
+            guess.correct = true;
+            self.trivia_guesses.insert(&key, &guess);
+            trivia.correct_guesses += 1;
+            self.trivia.insert(&trivia_id.0, &trivia);
+        }
+
+        ArkanaEvent::TriviaGuessRevealed(vec![TriviaGuessRevealedData {
+            trivia_id,
+            account_id,
+            correct,
+        }])
+        .emit();
+
+        correct
+    }
+
+    /// Pays the caller's share of the pool once the reveal phase has ended
+    /// and clears their guess. Returns zero for wrong or unrevealed guesses.
+    pub fn claim_trivia_prize(&mut self, trivia_id: U64) -> U128 {
+        let account_id = self.internal_player_id();
+
+        let trivia = self
+            .trivia
+            .get(&trivia_id.0)
+            .expect("Trivia does not exist");
+        assert!(
+            env::block_timestamp_ms() >= trivia.reveal_ends_at,
+            "Reveal phase has not ended"
+        );
+
+        let key = (trivia_id.0, account_id.clone());
+        let guess = self.trivia_guesses.get(&key).expect("No guess submitted");
+
+        let mut user = self.internal_get_user(&account_id);
+        let initial_storage_usage = env::storage_usage();
+
+        let prize = if guess.correct {
+            trivia.pool / trivia.correct_guesses as Points
+        } else {
+            0
+        };
+
+        self.trivia_guesses.remove(&key);
+        if prize > 0 {
+            self.internal_issue_points(&account_id, &mut user, prize);
+        }
+        self.internal_save_user(&account_id, &user);
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        U128(prize)
+    }
+
+    // View Functions
+    pub fn get_trivia(&self, from_index: Option<U64>, limit: Option<u64>) -> Page<TriviaOutput> {
+        paginate(self.trivia.iter(), from_index, limit).map(|(trivia_id, trivia)| TriviaOutput {
+            trivia_id: U64(trivia_id),
+            question: trivia.question,
+            answer: trivia.answer,
+            pool: U128(trivia.pool),
+            commit_ends_at: U64(trivia.commit_ends_at),
+            reveal_ends_at: U64(trivia.reveal_ends_at),
+            guesses: trivia.guesses,
+            correct_guesses: trivia.correct_guesses,
+        })
+    }
+
+    /// Whether `account_id` has an open guess and if it was revealed correct.
+    pub fn get_trivia_guess(&self, trivia_id: U64, account_id: AccountId) -> Option<bool> {
+        self.trivia_guesses
+            .get(&(trivia_id.0, account_id))
+            .map(|guess| guess.correct)
+    }
+}
@@ -0,0 +1,82 @@
+use near_sdk::env;
+
+use crate::*;
+
+pub const MAX_REWARD_TITLE_LEN: usize = 64;
+pub const MAX_REWARD_DURATION: u64 = 365 * ONE_DAY;
+pub const MAX_TICKETS_PER_CALL: u64 = 1_000;
+
+pub const ERR_REWARD_NOT_FOUND: &str = "Reward does not exist";
+pub const ERR_REWARD_ENDED: &str = "Reward has ended";
+
+/// Checks what a reward is created with. `starts_at` is when it gets
+/// created, later than now for scheduled and voted rewards.
+pub(crate) fn assert_valid_reward(
+    title: &str,
+    price: Points,
+    ended_at: Timestamp,
+    starts_at: Timestamp,
+) {
+    assert!(
+        !title.is_empty() && title.len() <= MAX_REWARD_TITLE_LEN,
+        "Title must be between 1 and {} bytes",
+        MAX_REWARD_TITLE_LEN
+    );
+    assert!(price > 0, "Price must be greater than zero");
+    assert!(ended_at > starts_at, "Reward must end in the future");
+    assert!(
+        ended_at - starts_at <= MAX_REWARD_DURATION,
+        "Reward cannot run longer than {} ms",
+        MAX_REWARD_DURATION
+    );
+}
+
+pub(crate) fn validate_ticket_amount(amount: u64) -> Result<(), String> {
+    if amount == 0 {
+        return Err("Amount must be greater than zero".to_string());
+    }
+    if amount > MAX_TICKETS_PER_CALL {
+        return Err(format!(
+            "Cannot buy more than {} tickets per call",
+            MAX_TICKETS_PER_CALL
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn assert_valid_ticket_amount(amount: u64) {
+    if let Err(error) = validate_ticket_amount(amount) {
+        panic!("{}", error);
+    }
+}
+
+impl ArkanaCoreContract {
+    pub(crate) fn assert_reward_exists(&self, reward_id: RewardId) {
+        assert!(
+            self.rewards.get(&reward_id).is_some(),
+            "{}",
+            ERR_REWARD_NOT_FOUND
+        );
+    }
+
+    pub(crate) fn internal_get_reward(&self, reward_id: RewardId) -> Reward {
+        self.rewards.get(&reward_id).unwrap_or_else(|| {
+            assert!(
+                self.legacy_rewards.get(&reward_id).is_none(),
+                "Reward has not been migrated yet"
+            );
+            panic!("{}", ERR_REWARD_NOT_FOUND)
+        })
+    }
+
+    /// Reward that still sells tickets.
+    pub(crate) fn internal_get_open_reward(&self, reward_id: RewardId) -> Reward {
+        let reward = self.internal_get_reward(reward_id);
+        assert!(
+            reward.is_open(env::block_timestamp_ms()),
+            "{}",
+            ERR_REWARD_ENDED
+        );
+        reward
+    }
+}
@@ -0,0 +1,130 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near_bindgen, AccountId, Gas, Promise, PromiseResult};
+use serde::Serialize;
+
+use crate::*;
+
+pub const MEMBERSHIP_CACHE_TTL: u64 = ONE_DAY;
+pub const MAX_VERIFIED_COLLECTIONS: usize = 10;
+
+const GAS_FOR_NFT_SUPPLY_FOR_OWNER: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_ON_MEMBERSHIP_VERIFIED: Gas = Gas(10_000_000_000_000);
+
+/// Collections an account was found holding at `verified_at`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MembershipVerification {
+    collections: Vec<AccountId>,
+    verified_at: Timestamp,
+}
+
+#[derive(Serialize)]
+pub struct MembershipTierOutput {
+    collections: Vec<AccountId>,
+    verified_at: U64,
+    expires_at: U64,
+}
+
+impl ArkanaCoreContract {
+    /// Collections the account held at its last verification, empty once the
+    /// cache has expired.
+    pub(crate) fn internal_verified_collections(&self, account_id: &AccountId) -> Vec<AccountId> {
+        match self.membership_verifications.get(account_id) {
+            Some(verification)
+                if env::block_timestamp_ms() < verification.verified_at + MEMBERSHIP_CACHE_TTL =>
+            {
+                verification.collections
+            }
+            _ => vec![],
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// Asks every collection allowed to `verify_holding` whether
+    /// `account_id` owns any of its tokens and caches the answer for
+    /// `MEMBERSHIP_CACHE_TTL`. Callable by anyone.
+    pub fn verify_membership(&mut self, account_id: AccountId) -> Promise {
+        assert!(
+            self.internal_find_user(&account_id).is_some(),
+            "User does not exist"
+        );
+
+        let contract_ids: Vec<AccountId> = self
+            .membership_contracts
+            .iter()
+            .filter(|contract_id| {
+                self.internal_membership_allows(contract_id, MembershipMethod::VerifyHolding)
+            })
+            .take(MAX_VERIFIED_COLLECTIONS)
+            .collect();
+
+        assert!(!contract_ids.is_empty(), "No collections to verify against");
+
+        let supply_queries = contract_ids
+            .iter()
+            .map(|contract_id| {
+                ext_nft::ext(contract_id.clone())
+                    .with_static_gas(GAS_FOR_NFT_SUPPLY_FOR_OWNER)
+                    .nft_supply_for_owner(account_id.clone())
+            })
+            .reduce(|joined, query| joined.and(query))
+            .unwrap();
+
+        supply_queries.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_MEMBERSHIP_VERIFIED)
+                .on_membership_verified(account_id, contract_ids),
+        )
+    }
+
+    #[private]
+    pub fn on_membership_verified(
+        &mut self,
+        account_id: AccountId,
+        contract_ids: Vec<AccountId>,
+    ) -> Vec<AccountId> {
+        // Failed or malformed answers count as not holding
+        let collections: Vec<AccountId> = contract_ids
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| match env::promise_result(*index as u64) {
+                PromiseResult::Successful(value) => serde_json::from_slice::<U128>(&value)
+                    .map(|supply| supply.0 > 0)
+                    .unwrap_or(false),
+                _ => false,
+            })
+            .map(|(_, contract_id)| contract_id)
+            .collect();
+
+        let initial_storage_usage = env::storage_usage();
+        self.membership_verifications.insert(
+            &account_id,
+            &MembershipVerification {
+                collections: collections.clone(),
+                verified_at: env::block_timestamp_ms(),
+            },
+        );
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::MembershipVerified(vec![MembershipVerifiedData {
+            account_id,
+            collections: collections.clone(),
+        }])
+        .emit();
+
+        collections
+    }
+
+    // View Functions
+    pub fn get_membership_tier(&self, account_id: AccountId) -> Option<MembershipTierOutput> {
+        self.membership_verifications
+            .get(&account_id)
+            .map(|verification| MembershipTierOutput {
+                expires_at: U64(verification.verified_at + MEMBERSHIP_CACHE_TTL),
+                verified_at: U64(verification.verified_at),
+                collections: verification.collections,
+            })
+    }
+}
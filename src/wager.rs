@@ -0,0 +1,315 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, BlockHeight};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+pub const MAX_HOUSE_EDGE_BPS: u16 = 2_000;
+pub const DICE_SIDES: u8 = 100;
+
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CoinSide {
+    Heads,
+    Tails,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WagerGame {
+    CoinFlip { choice: CoinSide },
+    // Wins if the roll, from 1 to `DICE_SIDES`, is within the range
+    Dice { low: u8, high: u8 },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+pub struct WagerConfig {
+    pub house_edge_bps: u16,
+    // Total stake per user and UTC day, zero disables wagers
+    pub daily_stake_limit: U128,
+}
+
+impl Default for WagerConfig {
+    fn default() -> Self {
+        Self {
+            house_edge_bps: 0,
+            daily_stake_limit: U128(0),
+        }
+    }
+}
+
+/// A wager is placed in one block and settled with the randomness of a later
+/// one, so the outcome is unknown when the stake is committed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingWager {
+    game: WagerGame,
+    stake: Points,
+    // Paid out if the wager wins
+    payout: Points,
+    placed_at: BlockHeight,
+}
+
+#[derive(Serialize)]
+pub struct PendingWagerOutput {
+    game: WagerGame,
+    stake: U128,
+    payout: U128,
+    placed_at: U64,
+    can_settle: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct HouseBankroll {
+    balance: Points,
+    // Payouts of pending wagers, not available for new ones
+    reserved: Points,
+}
+
+#[derive(Serialize)]
+pub struct HouseBankrollOutput {
+    balance: U128,
+    reserved: U128,
+    available: U128,
+}
+
+#[derive(Serialize)]
+pub struct WagerOutcome {
+    won: bool,
+    payout: U128,
+}
+
+impl ArkanaCoreContract {
+    /// Takes the stake into the house bankroll, reserves the payout from it
+    /// and records the wager for settlement in a later block.
+    pub(crate) fn internal_place_wager(&mut self, game: WagerGame, stake: Points, payout: Points) {
+        self.assert_not_paused(PauseFeature::All);
+        assert!(stake > 0, "Stake must be greater than zero");
+
+        let account_id = self.internal_player_id();
+        self.assert_not_blacklisted(&account_id);
+        assert!(
+            self.pending_wagers.get(&account_id).is_none(),
+            "Settle the previous wager first"
+        );
+
+        let mut user = self.internal_get_user(&account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        let daily_stake_limit = self.wager_config.daily_stake_limit.0;
+        let mut usage = self.wager_usage.get(&account_id).unwrap_or_default();
+        assert!(
+            usage.used_today().safe_add(stake) <= daily_stake_limit,
+            "Daily stake limit exceeded"
+        );
+        usage.consume(stake, daily_stake_limit);
+        self.wager_usage.insert(&account_id, &usage);
+
+        if user.points < stake {
+            panic!("Points insufficient");
+        }
+        self.internal_spend_points(&account_id, &mut user, stake, SpendKind::Game);
+        let contribution = self.internal_feed_jackpot(&account_id, &mut user, stake);
+        self.internal_save_user(&account_id, &user);
+
+        let bankroll = &mut self.house_bankroll;
+        bankroll.balance = bankroll.balance.safe_add(stake - contribution);
+        assert!(
+            bankroll.balance - bankroll.reserved >= payout,
+            "House bankroll cannot cover the payout"
+        );
+        bankroll.reserved += payout;
+
+        self.pending_wagers.insert(
+            &account_id,
+            &PendingWager {
+                game: game.clone(),
+                stake,
+                payout,
+                placed_at: env::block_height(),
+            },
+        );
+
+        self.internal_update_storage_usage(&account_id, initial_storage_usage);
+
+        ArkanaEvent::WagerPlaced(vec![WagerPlacedData {
+            account_id,
+            game,
+            stake: U128(stake),
+            payout: U128(payout),
+        }])
+        .emit();
+    }
+
+    fn internal_wager_won(&mut self, game: &WagerGame) -> bool {
+        let random_number = self.internal_random_number(RandomPurpose::Wager);
+
+        match *game {
+            WagerGame::CoinFlip { choice } => {
+                let side = if random_number.is_multiple_of(2) {
+                    CoinSide::Heads
+                } else {
+                    CoinSide::Tails
+                };
+                side == choice
+            }
+            WagerGame::Dice { low, high } => {
+                let roll = (random_number % DICE_SIDES as u32) as u8 + 1;
+                (low..=high).contains(&roll)
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    pub fn set_wager_config(&mut self, config: WagerConfig) {
+        self.assert_owner();
+
+        assert!(
+            config.house_edge_bps <= MAX_HOUSE_EDGE_BPS,
+            "House edge cannot exceed {} bps",
+            MAX_HOUSE_EDGE_BPS
+        );
+
+        self.wager_config = config;
+
+        ArkanaEvent::admin_action("set_wager_config", serde_json::json!({ "config": config }));
+    }
+
+    /// Stakes points on a coin side. A win pays twice the stake minus the
+    /// house edge once settled with `settle_wager`.
+    pub fn coin_flip(&mut self, stake: U128, choice: CoinSide) -> U128 {
+        let gross_payout = stake.0.safe_mul(2);
+        let payout =
+            gross_payout - apply_bps(gross_payout, self.wager_config.house_edge_bps as u32);
+
+        self.internal_place_wager(WagerGame::CoinFlip { choice }, stake.0, payout);
+
+        U128(payout)
+    }
+
+    /// Stakes points on a dice roll landing within `low..=high`. The payout
+    /// scales with the odds: stake * sides / range, minus the house edge.
+    pub fn roll_dice(&mut self, stake: U128, low: u8, high: u8) -> U128 {
+        assert!(
+            1 <= low && low <= high && high <= DICE_SIDES,
+            "Range must be within 1 and {}",
+            DICE_SIDES
+        );
+        let range = (high - low + 1) as Points;
+        assert!(
+            range < DICE_SIDES as Points,
+            "Range cannot cover every side"
+        );
+
+        let gross_payout = stake.0.safe_mul(DICE_SIDES as Points) / range;
+        let payout =
+            gross_payout - apply_bps(gross_payout, self.wager_config.house_edge_bps as u32);
+
+        self.internal_place_wager(WagerGame::Dice { low, high }, stake.0, payout);
+
+        U128(payout)
+    }
+
+    /// Adds points the house can pay out from, on top of collected stakes.
+    #[payable]
+    pub fn fund_house_bankroll(&mut self, amount: U128) -> U128 {
+        assert_one_yocto();
+
+        self.assert_owner();
+
+        self.house_bankroll.balance = self.house_bankroll.balance.safe_add(amount.0);
+
+        ArkanaEvent::admin_action(
+            "fund_house_bankroll",
+            serde_json::json!({ "amount": amount }),
+        );
+
+        U128(self.house_bankroll.balance)
+    }
+
+    /// Resolves the pending wager of `account_id` with the current block's
+    /// randomness. Callable by anyone from the block after it was placed.
+    pub fn settle_wager(&mut self, account_id: AccountId) -> WagerOutcome {
+        let wager = self
+            .pending_wagers
+            .get(&account_id)
+            .expect("No pending wager");
+        assert!(
+            env::block_height() > wager.placed_at,
+            "Wager can only be settled from the next block"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        self.pending_wagers.remove(&account_id);
+
+        let won = self.internal_wager_won(&wager.game);
+        let payout = if won { wager.payout } else { 0 };
+
+        self.house_bankroll.reserved -= wager.payout;
+        self.house_bankroll.balance -= payout;
+
+        // The account may have unregistered in the meantime
+        if self.internal_find_user(&account_id).is_some() {
+            if payout > 0 {
+                let mut user = self.internal_get_user(&account_id);
+                self.internal_issue_points(&account_id, &mut user, payout);
+                self.internal_save_user(&account_id, &user);
+            }
+            self.internal_update_storage_usage(&account_id, initial_storage_usage);
+        }
+
+        ArkanaEvent::WagerSettled(vec![WagerSettledData {
+            account_id,
+            game: wager.game,
+            stake: U128(wager.stake),
+            payout: U128(payout),
+        }])
+        .emit();
+
+        WagerOutcome {
+            won,
+            payout: U128(payout),
+        }
+    }
+
+    // View Functions
+    pub fn get_wager_config(&self) -> WagerConfig {
+        self.wager_config
+    }
+
+    pub fn get_house_bankroll(&self) -> HouseBankrollOutput {
+        let bankroll = &self.house_bankroll;
+
+        HouseBankrollOutput {
+            balance: U128(bankroll.balance),
+            reserved: U128(bankroll.reserved),
+            available: U128(bankroll.balance - bankroll.reserved),
+        }
+    }
+
+    pub fn get_pending_wager(&self, account_id: AccountId) -> Option<PendingWagerOutput> {
+        self.pending_wagers
+            .get(&account_id)
+            .map(|wager| PendingWagerOutput {
+                game: wager.game,
+                stake: U128(wager.stake),
+                payout: U128(wager.payout),
+                placed_at: U64(wager.placed_at),
+                can_settle: env::block_height() > wager.placed_at,
+            })
+    }
+
+    pub fn get_wager_stake_today(&self, account_id: AccountId) -> U128 {
+        U128(
+            self.wager_usage
+                .get(&account_id)
+                .map(|usage| usage.used_today())
+                .unwrap_or(0),
+        )
+    }
+}
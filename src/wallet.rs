@@ -0,0 +1,150 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId};
+
+use crate::*;
+
+pub const MAX_LINKED_WALLETS: usize = 5;
+pub const WALLET_LINK_TTL: u64 = ONE_DAY;
+
+/// A link requested by `primary_id` that the wallet has yet to confirm.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingWalletLink {
+    primary_id: AccountId,
+    requested_at: Timestamp,
+}
+
+impl ArkanaCoreContract {
+    /// The profile `account_id` plays for, itself unless it is a linked wallet.
+    pub(crate) fn internal_resolve_wallet(&self, account_id: AccountId) -> AccountId {
+        self.wallet_links.get(&account_id).unwrap_or(account_id)
+    }
+
+    fn internal_unlink_wallet(&mut self, primary_id: &AccountId, wallet_id: &AccountId) {
+        let mut wallets = self.linked_wallets.get(primary_id).unwrap_or_default();
+        wallets.retain(|linked_id| linked_id != wallet_id);
+        if wallets.is_empty() {
+            self.linked_wallets.remove(primary_id);
+        } else {
+            self.linked_wallets.insert(primary_id, &wallets);
+        }
+        self.wallet_links.remove(wallet_id);
+
+        ArkanaEvent::WalletUnlinked(vec![WalletLinkData {
+            account_id: primary_id.clone(),
+            wallet_id: wallet_id.clone(),
+        }])
+        .emit();
+    }
+
+    pub(crate) fn internal_unlink_all_wallets(&mut self, primary_id: &AccountId) {
+        for wallet_id in self.linked_wallets.get(primary_id).unwrap_or_default() {
+            self.internal_unlink_wallet(primary_id, &wallet_id);
+        }
+    }
+}
+
+#[near_bindgen]
+impl ArkanaCoreContract {
+    /// First step of linking, called by the primary account. The wallet has
+    /// to confirm within `WALLET_LINK_TTL`.
+    pub fn request_wallet_link(&mut self, wallet_id: AccountId) {
+        let primary_id = env::predecessor_account_id();
+
+        assert!(
+            self.wallet_links.get(&primary_id).is_none(),
+            "A linked wallet cannot be a primary account"
+        );
+        self.internal_get_user(&primary_id);
+
+        let initial_storage_usage = env::storage_usage();
+        self.pending_wallet_links.insert(
+            &wallet_id,
+            &PendingWalletLink {
+                primary_id: primary_id.clone(),
+                requested_at: env::block_timestamp_ms(),
+            },
+        );
+        self.internal_update_storage_usage(&primary_id, initial_storage_usage);
+    }
+
+    /// Second step, called by the wallet. From then on the wallet's spins,
+    /// claims, tickets and stakes accrue to `primary_id`.
+    #[payable]
+    pub fn confirm_wallet_link(&mut self, primary_id: AccountId) {
+        assert_one_yocto();
+
+        let wallet_id = env::predecessor_account_id();
+
+        let pending = self
+            .pending_wallet_links
+            .remove(&wallet_id)
+            .expect("No pending link for this wallet");
+        assert_eq!(
+            pending.primary_id, primary_id,
+            "No pending link for this wallet"
+        );
+        assert!(
+            env::block_timestamp_ms() < pending.requested_at + WALLET_LINK_TTL,
+            "Link request expired"
+        );
+        assert!(
+            self.internal_find_user(&wallet_id).is_none(),
+            "Wallet already has its own account"
+        );
+        assert!(
+            self.wallet_links.get(&wallet_id).is_none(),
+            "Wallet is already linked"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+
+        let mut wallets = self.linked_wallets.get(&primary_id).unwrap_or_default();
+        assert!(
+            wallets.len() < MAX_LINKED_WALLETS,
+            "Cannot link more than {} wallets",
+            MAX_LINKED_WALLETS
+        );
+        wallets.push(wallet_id.clone());
+        self.linked_wallets.insert(&primary_id, &wallets);
+        self.wallet_links.insert(&wallet_id, &primary_id);
+
+        // The request was paid by the primary account
+        self.internal_update_storage_usage(&primary_id, initial_storage_usage);
+
+        ArkanaEvent::WalletLinked(vec![WalletLinkData {
+            account_id: primary_id,
+            wallet_id,
+        }])
+        .emit();
+    }
+
+    /// Callable by either side of the link.
+    #[payable]
+    pub fn unlink_wallet(&mut self, wallet_id: AccountId) {
+        assert_one_yocto();
+
+        let predecessor_id = env::predecessor_account_id();
+
+        let primary_id = self
+            .wallet_links
+            .get(&wallet_id)
+            .expect("Wallet is not linked");
+        assert!(
+            predecessor_id == primary_id || predecessor_id == wallet_id,
+            "Unauthorized"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_unlink_wallet(&primary_id, &wallet_id);
+        self.internal_update_storage_usage(&primary_id, initial_storage_usage);
+    }
+
+    // View Functions
+    pub fn get_linked_wallets(&self, account_id: AccountId) -> Vec<AccountId> {
+        self.linked_wallets.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_primary_account(&self, wallet_id: AccountId) -> Option<AccountId> {
+        self.wallet_links.get(&wallet_id)
+    }
+}
@@ -0,0 +1,248 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use serde::{Deserialize, Serialize};
+
+use near_sdk::{env, AccountId};
+
+use crate::*;
+
+pub const MAX_WHEEL_SEGMENTS: usize = 16;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct WheelSegment {
+    pub points: U128,
+    pub weight: u16,
+    // Extra weight per 10 consecutive spins that missed every resetting segment
+    pub boost_weight: u16,
+    pub resets_boost: bool,
+    // Granted on top of the points, skipped if the item is no longer defined
+    #[serde(default)]
+    pub item: Option<ItemId>,
+}
+
+pub fn default_wheel_segments() -> Vec<WheelSegment> {
+    [
+        (1, 50, 0, false),
+        (3, 80, 0, false),
+        (7, 70, 0, true),
+        (9, 20, 3, true),
+        (12, 10, 2, true),
+        (15, 2, 1, true),
+    ]
+    .into_iter()
+    .map(
+        |(points, weight, boost_weight, resets_boost)| WheelSegment {
+            points: U128(points),
+            weight,
+            boost_weight,
+            resets_boost,
+            item: None,
+        },
+    )
+    .collect()
+}
+
+/// Cumulative base and boost weights of the wheel segments, built when the
+/// wheel is set so a spin only has to binary-search them.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WheelWeights {
+    cumulative: Vec<u32>,
+    cumulative_boost: Vec<u32>,
+    total: u32,
+    total_boost: u32,
+}
+
+impl WheelWeights {
+    pub(crate) fn new(segments: &[WheelSegment]) -> Self {
+        assert_valid_wheel_segments(segments);
+
+        let (mut total, mut total_boost) = (0, 0);
+        let (cumulative, cumulative_boost) = segments
+            .iter()
+            .map(|segment| {
+                total += segment.weight as u32;
+                total_boost += segment.boost_weight as u32;
+                (total, total_boost)
+            })
+            .unzip();
+
+        Self {
+            cumulative,
+            cumulative_boost,
+            total,
+            total_boost,
+        }
+    }
+
+    fn weight_up_to(&self, index: usize, boost: u32) -> u32 {
+        self.cumulative[index] + (boost * self.cumulative_boost[index]) / 10
+    }
+
+    /// How many of the `total` draw outcomes land on each segment, as
+    /// `(outcomes, total)`.
+    pub(crate) fn odds(&self, boost: u32) -> (Vec<u32>, u32) {
+        let total = self.total + (boost * self.total_boost) / 10;
+        let last = self.cumulative.len() - 1;
+
+        let outcomes = (0..=last)
+            .map(|index| {
+                let low = if index == 0 {
+                    0
+                } else {
+                    self.weight_up_to(index - 1, boost) + 1
+                };
+                let high = self.weight_up_to(index, boost).min(total - 1);
+                (high + 1).saturating_sub(low)
+            })
+            .collect();
+
+        (outcomes, total)
+    }
+
+    /// Index of the segment `random_number` lands on.
+    pub(crate) fn draw(&self, boost: u32, random_number: u32) -> usize {
+        let random_number = random_number % (self.total + (boost * self.total_boost) / 10);
+
+        let (mut low, mut high) = (0, self.cumulative.len() - 1);
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.weight_up_to(mid, boost) >= random_number {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        low
+    }
+}
+
+pub(crate) fn assert_valid_wheel_segments(segments: &[WheelSegment]) {
+    assert!(
+        !segments.is_empty() && segments.len() <= MAX_WHEEL_SEGMENTS,
+        "Wheel must have between 1 and {} segments",
+        MAX_WHEEL_SEGMENTS
+    );
+    assert!(
+        segments.iter().map(|s| s.weight as u32).sum::<u32>() > 0,
+        "Wheel total weight cannot be zero"
+    );
+    assert!(
+        segments.iter().any(|s| s.resets_boost),
+        "Wheel must have a segment that resets the boost"
+    );
+}
+
+impl ArkanaCoreContract {
+    /// Draws a wheel segment. Boosted segments get more likely the longer
+    /// the wheel goes without landing on a resetting segment, or with an
+    /// odds booster.
+    pub(crate) fn internal_draw_wheel_segment(&mut self, odds_boost: u8) -> WheelSegment {
+        let boost = self.spinwheel_wr as u32 + odds_boost as u32;
+        let random_number = self.internal_random_number(RandomPurpose::Spin);
+        let index = self.wheel_weights.draw(boost, random_number);
+
+        self.wheel_segments[index].clone()
+    }
+
+    /// Replaces the wheel along with its weight tables.
+    pub(crate) fn internal_set_wheel_segments(&mut self, segments: Vec<WheelSegment>) {
+        self.wheel_weights = WheelWeights::new(&segments);
+        self.wheel_segments = segments;
+        self.spinwheel_wr = 0;
+    }
+
+    /// Spins the wheel once for `user`, using up their odds booster.
+    pub(crate) fn internal_spin_wheel(&mut self, user: &mut User) -> WheelSegment {
+        let segment = self.internal_draw_wheel_segment(std::mem::take(&mut user.odds_boost));
+
+        if segment.resets_boost {
+            self.spinwheel_wr = 0;
+        } else {
+            self.spinwheel_wr = self.spinwheel_wr.saturating_add(1);
+        }
+
+        segment
+    }
+
+    /// Credits what a spin landed on, with any multiplier event applied, and
+    /// returns the points won. Item storage is accounted by the caller.
+    pub(crate) fn internal_award_spin(
+        &mut self,
+        account_id: &AccountId,
+        user: &mut User,
+        segment: &WheelSegment,
+    ) -> Points {
+        let points = self.internal_apply_earnings_multiplier(segment.points.0);
+        self.internal_issue_points(account_id, user, points);
+        self.internal_unlock_achievement(account_id, user, Achievement::FirstSpin);
+        self.internal_add_pass_xp(account_id, PassXpSource::Spin);
+
+        if let Some(item_id) = &segment.item {
+            if self.items.get(item_id).is_some() {
+                self.internal_grant_item(account_id, item_id, 1);
+            }
+        }
+
+        points
+    }
+
+    /// Free or paid spin for `account_id`, see `play_spin_wheel`.
+    pub(crate) fn internal_play_spin_wheel(
+        &mut self,
+        account_id: &AccountId,
+        is_free: bool,
+    ) -> Points {
+        self.assert_not_paused(PauseFeature::Spins);
+        self.assert_not_blacklisted(account_id);
+        self.assert_not_self_excluded(account_id);
+
+        let mut user = self.internal_get_user(account_id);
+        self.internal_consume_rate_limit(&mut user);
+        let initial_storage_usage = env::storage_usage();
+
+        if is_free {
+            self.assert_human_if_required(account_id, false);
+
+            let current_timestamp = env::block_timestamp_ms();
+            let delta_ms = current_timestamp.saturating_sub(user.activity.last_free_spin());
+
+            // Staked NFTs can grant extra free spins within the same cooldown
+            if delta_ms >= self.free_spin_cooldown_ms {
+                user.activity.set_last_free_spin(current_timestamp);
+                user.activity.set_free_spins_used(0);
+            } else {
+                let extra_free_spins = self.internal_nft_boost(account_id).extra_free_spins;
+                let free_spins_used = user.activity.free_spins_used();
+                if free_spins_used == 0 || free_spins_used > extra_free_spins {
+                    panic!(
+                        "Cannot play spin wheel for free, please wait {} seconds",
+                        milli_to_seconds(self.free_spin_cooldown_ms - delta_ms)
+                    );
+                }
+            }
+            user.activity
+                .set_free_spins_used(user.activity.free_spins_used() + 1);
+        } else {
+            if user.points < self.spin_wheel_price {
+                panic!("Cannot play, user points insufficient");
+            }
+
+            self.internal_spend_points(
+                account_id,
+                &mut user,
+                self.spin_wheel_price,
+                SpendKind::Spin,
+            );
+            self.internal_feed_jackpot(account_id, &mut user, self.spin_wheel_price);
+        }
+
+        let segment = self.internal_spin_wheel(&mut user);
+        let result = self.internal_award_spin(account_id, &mut user, &segment);
+
+        self.internal_save_user(account_id, &user);
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+        result
+    }
+}